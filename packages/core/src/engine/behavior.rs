@@ -0,0 +1,371 @@
+use crate::{
+    errors::RPGXError,
+    prelude::{Coordinates, Direction, PathOptions, PawnId, Rect, Scene},
+};
+
+/// Built-in movement of a non-player [`Pawn`](crate::prelude::Pawn), run one step
+/// at a time by [`Scene::run_behaviors`].
+///
+/// Every step goes through [`Map::find_path_with`](crate::prelude::Map::find_path_with)
+/// and [`Scene::move_to`], so pawns with a behavior follow the same blocking and
+/// collision rules as the player.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Behavior {
+    /// Walks to each waypoint in turn, starting over after the last one.
+    Patrol {
+        waypoints: Vec<Coordinates>,
+        /// Index of the waypoint currently headed to.
+        next: usize,
+    },
+    /// Takes random steps without leaving `area`.
+    Wander {
+        area: Rect,
+        /// State of the random generator, so that the walk can be replayed.
+        seed: u64,
+    },
+    /// Walks towards another pawn and stays within `distance` tiles of it.
+    Follow { target: PawnId, distance: u32 },
+    /// Runs away from `from` until it is at least `distance` tiles away.
+    Flee { from: Coordinates, distance: u32 },
+}
+
+impl Behavior {
+    /// Patrols `waypoints` in order, starting with the first one.
+    pub fn patrol(waypoints: Vec<Coordinates>) -> Self {
+        Self::Patrol { waypoints, next: 0 }
+    }
+
+    /// Wanders inside `area`, with `seed` picking the random walk.
+    pub fn wander(area: Rect, seed: u64) -> Self {
+        Self::Wander { area, seed }
+    }
+
+    /// Follows pawn `target`, stopping `distance` tiles away from it.
+    pub fn follow(target: PawnId, distance: u32) -> Self {
+        Self::Follow { target, distance }
+    }
+
+    /// Flees `from` until `distance` tiles away from it.
+    pub fn flee(from: Coordinates, distance: u32) -> Self {
+        Self::Flee { from, distance }
+    }
+}
+
+/// Advances a splitmix64 generator and returns its next value.
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *seed;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+fn manhattan(a: Coordinates, b: Coordinates) -> u32 {
+    a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+}
+
+impl Scene {
+    /// Gives pawn `id` a behavior, replacing its previous one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id.
+    pub fn set_behavior(&mut self, id: PawnId, behavior: Behavior) -> Result<(), RPGXError> {
+        if !self.pawns.contains_key(&id) {
+            return Err(RPGXError::PawnNotFound);
+        }
+        self.behaviors.insert(id, behavior);
+        Ok(())
+    }
+
+    /// Removes the behavior of pawn `id` and returns it.
+    pub fn clear_behavior(&mut self, id: PawnId) -> Option<Behavior> {
+        self.behaviors.remove(&id)
+    }
+
+    /// Returns the behavior of pawn `id`, if any.
+    pub fn behavior(&self, id: PawnId) -> Option<&Behavior> {
+        self.behaviors.get(&id)
+    }
+
+    /// Moves every pawn with a behavior by at most one tile, in loading order.
+    ///
+    /// A pawn whose next tile is blocked, for example by another pawn when
+    /// [`Scene::pawn_collision`] is enabled, waits for a later call.
+    /// Returns the pawns that moved along with their new position.
+    pub fn run_behaviors(&mut self) -> Vec<(PawnId, Coordinates)> {
+        let ids: Vec<PawnId> = self
+            .pawns
+            .keys()
+            .filter(|id| self.behaviors.contains_key(id))
+            .copied()
+            .collect();
+
+        ids.into_iter()
+            .filter_map(|id| {
+                let next = self.behavior_step(id)?;
+                self.try_move(id, next).ok().map(|pointer| (id, pointer))
+            })
+            .collect()
+    }
+
+    /// Returns the tile pawn `id` should step onto next according to its behavior,
+    /// updating the behavior state. Returns `None` when the pawn should wait.
+    pub fn behavior_step(&mut self, id: PawnId) -> Option<Coordinates> {
+        let current = self.pawn(id)?.pointer;
+        // Taken out while deciding, since deciding reads the rest of the scene
+        let mut behavior = self.behaviors.remove(&id)?;
+        let next = self.decide(id, current, &mut behavior);
+        self.behaviors.insert(id, behavior);
+        next
+    }
+
+    fn decide(
+        &self,
+        id: PawnId,
+        current: Coordinates,
+        behavior: &mut Behavior,
+    ) -> Option<Coordinates> {
+        match behavior {
+            Behavior::Patrol { waypoints, next } => self.patrol_step(id, current, waypoints, next),
+            Behavior::Wander { area, seed } => self.wander_step(id, current, area, seed),
+            Behavior::Follow { target, distance } => {
+                let goal = self.pawn(*target)?.pointer;
+                if manhattan(current, goal) > *distance {
+                    self.first_step(id, goal)
+                } else {
+                    None
+                }
+            }
+            Behavior::Flee { from, distance } => self.flee_step(id, current, *from, *distance),
+        }
+    }
+
+    /// Returns the tile after `current` on the path of `id` to `goal`.
+    fn first_step(&self, id: PawnId, goal: Coordinates) -> Option<Coordinates> {
+        self.steps_to(id, goal).ok()?.get(1).copied()
+    }
+
+    fn patrol_step(
+        &self,
+        id: PawnId,
+        current: Coordinates,
+        waypoints: &[Coordinates],
+        next: &mut usize,
+    ) -> Option<Coordinates> {
+        // Skip reached and unreachable waypoints, trying each one at most once
+        for _ in 0..waypoints.len() {
+            let waypoint = *waypoints.get(*next % waypoints.len())?;
+            if waypoint != current
+                && let Some(step) = self.first_step(id, waypoint)
+            {
+                return Some(step);
+            }
+            *next = (*next + 1) % waypoints.len();
+        }
+        None
+    }
+
+    fn wander_step(
+        &self,
+        id: PawnId,
+        current: Coordinates,
+        area: &Rect,
+        seed: &mut u64,
+    ) -> Option<Coordinates> {
+        if area.shape.area() == 0 {
+            return None;
+        }
+        if !area.contains(&current) {
+            // Head back to the closest tile of the area
+            let back = Coordinates::new(
+                current
+                    .x
+                    .clamp(area.origin.x, area.origin.x + area.shape.width - 1),
+                current
+                    .y
+                    .clamp(area.origin.y, area.origin.y + area.shape.height - 1),
+            );
+            return self.first_step(id, back);
+        }
+
+        let directions = [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ];
+        let start = (next_random(seed) % directions.len() as u64) as usize;
+        let occupied = self.occupied_by_others(id);
+        (0..directions.len())
+            .map(|i| directions[(start + i) % directions.len()])
+            .filter_map(|direction| current + direction.to_delta())
+            .find(|target| {
+                area.contains(target) && self.map.is_passable(target) && !occupied.contains(target)
+            })
+    }
+
+    fn flee_step(
+        &self,
+        id: PawnId,
+        current: Coordinates,
+        from: Coordinates,
+        distance: u32,
+    ) -> Option<Coordinates> {
+        if manhattan(current, from) >= distance {
+            return None;
+        }
+
+        // Among the tiles reachable within the missing distance, aim for the
+        // furthest from the threat, then the cheapest to reach.
+        let missing = distance - manhattan(current, from);
+        let options = PathOptions {
+            max_cost: Some(missing.saturating_mul(2)),
+            ..Default::default()
+        };
+        let reachable = self.map.reachable_from_with(
+            &current,
+            &PathOptions {
+                forbidden: self.occupied_by_others(id),
+                ..options
+            },
+        );
+        let (target, _) = reachable
+            .iter()
+            .filter(|(tile, _)| manhattan(*tile, from) > manhattan(current, from))
+            .max_by_key(|(tile, cost)| {
+                (
+                    manhattan(*tile, from),
+                    std::cmp::Reverse(*cost),
+                    std::cmp::Reverse((tile.y, tile.x)),
+                )
+            })?;
+        reachable.path_to(&target)?.get(1).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Effect, Layer, Map, Mask, Pawn, TextureId};
+
+    fn field() -> Map {
+        Map::new(
+            "field".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![
+                    Mask::new("grass".into(), vec![Rect::from_xywh(0, 0, 10, 10)], vec![]),
+                    Mask::new(
+                        "rock".into(),
+                        vec![],
+                        vec![Effect::Block(Rect::from_xywh(2, 0, 1, 2))],
+                    ),
+                ],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        )
+    }
+
+    fn spawn(scene: &mut Scene, x: u32, y: u32) -> PawnId {
+        scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(x, y),
+            texture_id: TextureId(0),
+        })
+    }
+
+    #[test]
+    fn patrol_loops_over_waypoints() {
+        let mut scene = Scene::new("test".into(), field(), None);
+        let guard = spawn(&mut scene, 0, 0);
+        let waypoints = vec![Coordinates::new(4, 0), Coordinates::new(0, 0)];
+        scene
+            .set_behavior(guard, Behavior::patrol(waypoints))
+            .unwrap();
+
+        let mut visited = Vec::new();
+        for _ in 0..20 {
+            for (_, pointer) in scene.run_behaviors() {
+                assert!(!scene.map.is_blocking_at(&pointer));
+                visited.push(pointer);
+            }
+        }
+        assert!(visited.contains(&Coordinates::new(4, 0)));
+        assert_eq!(
+            visited
+                .iter()
+                .filter(|p| **p == Coordinates::new(0, 0))
+                .count(),
+            visited
+                .iter()
+                .filter(|p| **p == Coordinates::new(4, 0))
+                .count()
+        );
+    }
+
+    #[test]
+    fn wander_stays_in_area() {
+        let mut scene = Scene::new("test".into(), field(), None);
+        let sheep = spawn(&mut scene, 1, 1);
+        let area = Rect::from_xywh(0, 0, 4, 4);
+        scene
+            .set_behavior(sheep, Behavior::wander(area, 7))
+            .unwrap();
+
+        let mut moves = 0;
+        for _ in 0..50 {
+            moves += scene.run_behaviors().len();
+            let pointer = scene.pawn(sheep).unwrap().pointer;
+            assert!(area.contains(&pointer));
+            assert!(!scene.map.is_blocking_at(&pointer));
+        }
+        assert_eq!(moves, 50);
+    }
+
+    #[test]
+    fn follow_keeps_distance() {
+        let mut scene = Scene::new("test".into(), field(), None);
+        scene.pawn_collision = true;
+        let hero = spawn(&mut scene, 9, 9);
+        let dog = spawn(&mut scene, 0, 0);
+        scene.set_behavior(dog, Behavior::follow(hero, 1)).unwrap();
+
+        for _ in 0..30 {
+            scene.run_behaviors();
+        }
+        let dog_at = scene.pawn(dog).unwrap().pointer;
+        assert_eq!(manhattan(dog_at, Coordinates::new(9, 9)), 1);
+
+        // The dog does not move while close enough
+        assert!(scene.run_behaviors().is_empty());
+    }
+
+    #[test]
+    fn flee_gets_away() {
+        let mut scene = Scene::new("test".into(), field(), None);
+        let rabbit = spawn(&mut scene, 5, 5);
+        let threat = Coordinates::new(4, 5);
+        scene
+            .set_behavior(rabbit, Behavior::flee(threat, 6))
+            .unwrap();
+
+        let mut last = manhattan(Coordinates::new(5, 5), threat);
+        while let Some((_, pointer)) = scene.run_behaviors().pop() {
+            let now = manhattan(pointer, threat);
+            assert!(now > last);
+            last = now;
+        }
+        assert!(last >= 6);
+    }
+
+    #[test]
+    fn rejects_unknown_pawns() {
+        let mut scene = Scene::new("test".into(), field(), None);
+        assert_eq!(
+            scene.set_behavior(PawnId(3), Behavior::flee(Coordinates::new(0, 0), 2)),
+            Err(RPGXError::PawnNotFound)
+        );
+    }
+}
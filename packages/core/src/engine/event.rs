@@ -0,0 +1,269 @@
+use crate::{
+    errors::RPGXError,
+    prelude::{ActionId, Coordinates, PawnId, Scene, TurnEvent},
+};
+
+/// Something that happened in the [`Engine`](crate::prelude::Engine), drained with
+/// [`Engine::drain_events`](crate::prelude::Engine::drain_events).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameEvent {
+    /// A pawn moved from one tile to another.
+    PawnMoved {
+        pawn: PawnId,
+        from: Coordinates,
+        to: Coordinates,
+    },
+    /// A pawn stepped onto a tile of a [`Mask`](crate::prelude::Mask) it wasn't on.
+    MaskEntered {
+        pawn: PawnId,
+        mask: String,
+        at: Coordinates,
+    },
+    /// A pawn stepped off the tiles of a mask, `at` being its new position.
+    MaskExited {
+        pawn: PawnId,
+        mask: String,
+        at: Coordinates,
+    },
+    /// A pawn fired an action effect, `at` being the tile holding the action.
+    ///
+    /// When actions fire is described by their [`Trigger`](crate::prelude::Trigger).
+    ActionTriggered {
+        pawn: PawnId,
+        action: ActionId,
+        at: Coordinates,
+    },
+    /// A move asked with [`Scene::move_to`] or [`Scene::step_to`] was refused.
+    Blocked { pawn: PawnId, error: RPGXError },
+    /// A scene was pushed to the timeline at `index`.
+    ScenePushed { index: usize },
+    /// The scene at `index` was popped from the timeline.
+    ScenePopped { index: usize },
+    /// Notification of the turn scheduler, see [`Scene::start_turns`].
+    Turn(TurnEvent),
+}
+
+/// Events queued in a [`Scene`] until drained.
+///
+/// Cloning gives an empty queue, so a scene cloned to be pushed on the timeline
+/// doesn't deliver the pending events of the original a second time.
+#[derive(Debug, Default)]
+pub(crate) struct EventQueue(Vec<GameEvent>);
+
+impl Clone for EventQueue {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl Scene {
+    /// Returns the events of this scene since the last call, oldest first.
+    ///
+    /// Events are queued until drained, usually by
+    /// [`Engine::drain_events`](crate::prelude::Engine::drain_events).
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events.0)
+    }
+
+    pub(crate) fn emit(&mut self, event: GameEvent) {
+        self.events.0.push(event);
+    }
+
+    /// Emits the events of a move of pawn `id` from `from` to `to`, none if the pawn
+    /// stayed on its tile.
+    pub(crate) fn emit_move(&mut self, id: PawnId, from: Coordinates, to: Coordinates) {
+        if from == to {
+            return;
+        }
+        self.emit(GameEvent::PawnMoved { pawn: id, from, to });
+
+        let before = self.map.get_masks_at(&from);
+        let after = self.map.get_masks_at(&to);
+        for mask in before.iter().filter(|mask| !after.contains(mask)) {
+            self.emit(GameEvent::MaskExited {
+                pawn: id,
+                mask: mask.clone(),
+                at: to,
+            });
+        }
+        for mask in after.iter().filter(|mask| !before.contains(mask)) {
+            self.emit(GameEvent::MaskEntered {
+                pawn: id,
+                mask: mask.clone(),
+                at: to,
+            });
+        }
+
+        self.fire_move_triggers(id, from, to);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    use crate::prelude::{Direction, Effect, Engine, Layer, Map, Mask, Rect, TextureId};
+
+    fn garden() -> Map {
+        Map::new(
+            "garden".into(),
+            vec![
+                Layer::new(
+                    "ground".into(),
+                    vec![Mask::new(
+                        "grass".into(),
+                        vec![Rect::from_xywh(0, 0, 4, 1)],
+                        vec![],
+                    )],
+                    1,
+                ),
+                Layer::new(
+                    "objects".into(),
+                    vec![
+                        Mask::new(
+                            "pond".into(),
+                            vec![Rect::from_xywh(2, 0, 2, 1)],
+                            vec![Effect::Action(ActionId(7))],
+                        ),
+                        Mask::new(
+                            "rock".into(),
+                            vec![],
+                            vec![Effect::Block(Rect::from_xywh(3, 0, 1, 1))],
+                        ),
+                    ],
+                    2,
+                ),
+            ],
+            Coordinates::new(0, 0),
+        )
+    }
+
+    #[test]
+    fn emits_moves_masks_and_actions() {
+        let mut scene = Scene::new("test".into(), garden(), None);
+        let hero = scene.load_pawn(TextureId(0));
+        scene.step_to(hero, Direction::Right).unwrap();
+        scene.step_to(hero, Direction::Right).unwrap();
+        assert!(scene.step_to(hero, Direction::Right).is_err());
+        scene.step_to(hero, Direction::Left).unwrap();
+
+        let at = Coordinates::new(2, 0);
+        let back = Coordinates::new(1, 0);
+        assert_eq!(
+            scene.drain_events(),
+            vec![
+                GameEvent::PawnMoved {
+                    pawn: hero,
+                    from: Coordinates::new(0, 0),
+                    to: back,
+                },
+                GameEvent::PawnMoved {
+                    pawn: hero,
+                    from: back,
+                    to: at,
+                },
+                GameEvent::MaskEntered {
+                    pawn: hero,
+                    mask: "pond".into(),
+                    at,
+                },
+                GameEvent::ActionTriggered {
+                    pawn: hero,
+                    action: ActionId(7),
+                    at,
+                },
+                GameEvent::Blocked {
+                    pawn: hero,
+                    error: RPGXError::TileNotWalkable(Coordinates::new(3, 0)),
+                },
+                GameEvent::PawnMoved {
+                    pawn: hero,
+                    from: at,
+                    to: back,
+                },
+                GameEvent::MaskExited {
+                    pawn: hero,
+                    mask: "pond".into(),
+                    at: back,
+                },
+            ]
+        );
+        assert!(scene.drain_events().is_empty());
+    }
+
+    #[test]
+    fn walks_emit_no_move_on_the_start_tile() {
+        let mut scene = Scene::new("test".into(), garden(), None);
+        let hero = scene.load_pawn(TextureId(0));
+        block_on(scene.walk_to(hero, Coordinates::new(1, 0))).unwrap();
+        assert_eq!(
+            scene.drain_events(),
+            vec![GameEvent::PawnMoved {
+                pawn: hero,
+                from: Coordinates::new(0, 0),
+                to: Coordinates::new(1, 0),
+            }]
+        );
+    }
+
+    #[test]
+    fn cloned_scenes_leave_pending_events_behind() {
+        let mut scene = Scene::new("test".into(), garden(), None);
+        let hero = scene.load_pawn(TextureId(0));
+        let mut engine = Engine::new(scene);
+        let scene = engine.get_active_scene_mut().unwrap();
+        scene.step_to(hero, Direction::Right).unwrap();
+
+        engine.push_scene(engine.get_active_scene().unwrap().clone());
+        let events = engine.drain_events();
+        assert_eq!(
+            events,
+            vec![
+                GameEvent::PawnMoved {
+                    pawn: hero,
+                    from: Coordinates::new(0, 0),
+                    to: Coordinates::new(1, 0),
+                },
+                GameEvent::ScenePushed { index: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn engine_keeps_events_in_order_across_scenes() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let mut scene = Scene::new("first".into(), garden(), None);
+        let hero = scene.load_pawn(TextureId(0));
+        let mut engine = Engine::new(scene);
+
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let subscription = engine.subscribe({
+            let seen = seen.clone();
+            move |event| seen.borrow_mut().push(event.clone())
+        });
+
+        let scene = engine.get_active_scene_mut().unwrap();
+        scene.step_to(hero, Direction::Right).unwrap();
+        engine.push_scene(Scene::new("second".into(), garden(), None));
+        engine.pop_scene();
+
+        let events = engine.drain_events();
+        assert!(matches!(events[0], GameEvent::PawnMoved { .. }));
+        assert_eq!(
+            events[1..],
+            [
+                GameEvent::ScenePushed { index: 1 },
+                GameEvent::ScenePopped { index: 1 }
+            ]
+        );
+        assert_eq!(*seen.borrow(), events);
+
+        assert!(engine.unsubscribe(subscription));
+        engine.push_scene(Scene::new("third".into(), garden(), None));
+        engine.drain_events();
+        assert_eq!(seen.borrow().len(), 3);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+
+use crate::{
+    prelude::{Coordinates, Map, Rect},
+    traits::Grid,
+};
+
+/// What a pawn knows about a tile, see [`Fog::state_at`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FogState {
+    /// The tile has never been seen.
+    #[default]
+    Unknown,
+    /// The tile has been seen before but is out of sight now.
+    Explored,
+    /// The tile is currently in sight.
+    Visible,
+}
+
+/// Fog of war: the tiles a pawn sees now and the tiles it has ever seen.
+///
+/// Sight is computed with [`Map::field_of_view`], so [`Effect::Opaque`] areas hide
+/// what lies behind them.
+///
+/// [`Effect::Opaque`]: crate::prelude::Effect::Opaque
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fog {
+    /// How far the pawn sees, in tiles.
+    pub radius: u32,
+    visible: HashSet<Coordinates>,
+    explored: HashSet<Coordinates>,
+}
+
+impl Fog {
+    /// Creates a fog where every tile is unknown.
+    pub fn new(radius: u32) -> Self {
+        Self {
+            radius,
+            ..Default::default()
+        }
+    }
+
+    /// Recomputes the visible tiles from `origin` and adds them to the explored ones.
+    pub fn update(&mut self, map: &Map, origin: &Coordinates) {
+        self.visible = map.field_of_view(origin, self.radius);
+        self.explored.extend(self.visible.iter().copied());
+    }
+
+    /// Forgets every tile seen so far.
+    pub fn reset(&mut self) {
+        self.visible.clear();
+        self.explored.clear();
+    }
+
+    /// Returns the state of `target`.
+    pub fn state_at(&self, target: &Coordinates) -> FogState {
+        if self.visible.contains(target) {
+            FogState::Visible
+        } else if self.explored.contains(target) {
+            FogState::Explored
+        } else {
+            FogState::Unknown
+        }
+    }
+
+    /// Returns the tiles currently in sight.
+    pub fn visible(&self) -> &HashSet<Coordinates> {
+        &self.visible
+    }
+
+    /// Returns every tile seen so far, the visible ones included.
+    pub fn explored(&self) -> &HashSet<Coordinates> {
+        &self.explored
+    }
+
+    /// Covers the tiles of `map` in the given `state` with as few rects as possible,
+    /// ready to be drawn over the map.
+    pub fn to_rects(&self, map: &Map, state: FogState) -> Vec<Rect> {
+        Rect::cover(
+            Rect::from_shape(map.index().shape())
+                .iter()
+                .filter(|coord| map.contains(coord) && self.state_at(coord) == state),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Effect, Layer, Mask};
+
+    /// Two 5x5 rooms side by side, split by an opaque wall at column 5 with a door at (5, 2).
+    fn rooms() -> Map {
+        Map::new(
+            "rooms".into(),
+            vec![
+                Layer::new(
+                    "ground".into(),
+                    vec![Mask::new(
+                        "floor".into(),
+                        vec![Rect::from_xywh(0, 0, 11, 5)],
+                        vec![],
+                    )],
+                    1,
+                ),
+                Layer::new(
+                    "walls".into(),
+                    vec![Mask::new(
+                        "wall".into(),
+                        vec![],
+                        vec![
+                            Effect::Opaque(Rect::from_xywh(5, 0, 1, 2)),
+                            Effect::Opaque(Rect::from_xywh(5, 3, 1, 2)),
+                        ],
+                    )],
+                    2,
+                ),
+            ],
+            Coordinates::new(0, 0),
+        )
+    }
+
+    #[test]
+    fn keeps_explored_tiles() {
+        let map = rooms();
+        let mut fog = Fog::new(20);
+
+        fog.update(&map, &Coordinates::new(0, 0));
+        assert_eq!(fog.state_at(&Coordinates::new(4, 4)), FogState::Visible);
+        assert_eq!(fog.state_at(&Coordinates::new(10, 0)), FogState::Unknown);
+
+        fog.update(&map, &Coordinates::new(10, 0));
+        assert_eq!(fog.state_at(&Coordinates::new(0, 0)), FogState::Explored);
+        assert_eq!(fog.state_at(&Coordinates::new(10, 4)), FogState::Visible);
+        assert!(fog.explored().is_superset(fog.visible()));
+
+        fog.reset();
+        assert_eq!(fog.state_at(&Coordinates::new(10, 4)), FogState::Unknown);
+    }
+
+    #[test]
+    fn covers_tiles_by_state() {
+        let map = rooms();
+        let mut fog = Fog::new(20);
+        fog.update(&map, &Coordinates::new(0, 0));
+
+        let area = |state| {
+            fog.to_rects(&map, state)
+                .iter()
+                .map(|rect| rect.shape.area())
+                .sum::<u32>()
+        };
+        let total = 11 * 5;
+        assert_eq!(area(FogState::Visible) + area(FogState::Unknown), total);
+        assert_eq!(area(FogState::Explored), 0);
+        assert!(area(FogState::Unknown) > 0);
+    }
+}
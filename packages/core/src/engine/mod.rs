@@ -1,23 +1,56 @@
-use crate::prelude::Scene;
+use std::rc::Rc;
 
+use crate::prelude::{Coordinates, GameEvent, PawnId, Scene};
+
+pub mod behavior;
+pub mod event;
+pub mod fog;
+pub mod motion;
 pub mod pawn;
 pub mod scene;
+pub mod trigger;
+pub mod turns;
 
 #[doc = include_str!("../../docs/engine.md")]
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Engine {
     /// Timeline of scene states over time.
     pub timeline: Vec<Scene>,
     /// Current index in the timeline (pointer to active scene).
     pub timenow: usize,
+    /// Length of one simulation step in seconds, see [`Engine::tick`].
+    pub timestep: f32,
+    /// Time received by [`Engine::tick`] and not simulated yet.
+    accumulator: f32,
+    /// Events collected from scenes and the timeline, see [`Engine::drain_events`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<GameEvent>,
+    /// Callbacks registered with [`Engine::subscribe`], keyed by subscription id.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    subscribers: Vec<(usize, Subscriber)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    next_subscription: usize,
 }
 
+/// Callback registered with [`Engine::subscribe`].
+type Subscriber = Rc<dyn Fn(&GameEvent)>;
+
+/// Most simulation steps run by a single [`Engine::tick`], so that a long pause
+/// does not freeze the caller while the simulation catches up.
+const MAX_STEPS_PER_TICK: u32 = 10;
+
 impl Engine {
     /// Create a new engine starting with an initial scene.
     pub fn new(scene: Scene) -> Self {
         Self {
             timeline: vec![scene],
             timenow: 0,
+            timestep: 1.0 / 60.0,
+            accumulator: 0.0,
+            events: Vec::new(),
+            subscribers: Vec::new(),
+            next_subscription: 0,
         }
     }
 
@@ -32,16 +65,26 @@ impl Engine {
     }
 
     /// Push a new scene to the timeline and move the pointer to it.
+    /// Emits [`GameEvent::ScenePushed`].
     pub fn push_scene(&mut self, scene: Scene) {
+        self.collect_events();
         self.timeline.push(scene);
         self.timenow = self.timeline.len() - 1;
+        self.events.push(GameEvent::ScenePushed {
+            index: self.timenow,
+        });
     }
 
     /// Pop the last scene from the timeline if there's more than one.
     /// Updates `timenow` to point to the new last scene.
+    /// Emits [`GameEvent::ScenePopped`].
     pub fn pop_scene(&mut self) {
         if self.timeline.len() > 1 {
+            self.collect_events();
             self.timeline.pop();
+            self.events.push(GameEvent::ScenePopped {
+                index: self.timeline.len(),
+            });
             self.timenow = self.timeline.len() - 1;
         }
     }
@@ -50,6 +93,7 @@ impl Engine {
     /// Updates `timenow` accordingly.
     pub fn rollback_to(&mut self, index: usize) {
         if index < self.timeline.len() {
+            self.collect_events();
             self.timeline.truncate(index + 1);
             self.timenow = self.timeline.len() - 1;
         }
@@ -59,6 +103,7 @@ impl Engine {
     /// Returns error if the index is out of range.
     pub fn rewind_to(&mut self, index: usize) -> Result<(), &'static str> {
         if index < self.timeline.len() {
+            self.collect_events();
             self.timenow = index;
             Ok(())
         } else {
@@ -70,4 +115,130 @@ impl Engine {
     pub fn get_scene_at(&self, index: usize) -> Option<&Scene> {
         self.timeline.get(index)
     }
+
+    /// Advance the active scene by `dt` seconds of real time.
+    ///
+    /// Time is simulated in fixed steps of [`Engine::timestep`] through
+    /// [`Scene::advance`], so the outcome does not depend on the frame rate.
+    /// Leftover time is kept for the next call, up to `MAX_STEPS_PER_TICK` (10)
+    /// steps: time beyond `10 * timestep`, as after a long pause, is discarded
+    /// rather than caught up.
+    ///
+    /// Returns the pawns that arrived on a tile along with that tile.
+    pub fn tick(&mut self, dt: f32) -> Vec<(PawnId, Coordinates)> {
+        let timestep = self.timestep;
+        let Some(scene) = self.timeline.get_mut(self.timenow) else {
+            return Vec::new();
+        };
+        if timestep <= 0.0 {
+            return Vec::new();
+        }
+
+        self.accumulator =
+            (self.accumulator + dt.max(0.0)).min(timestep * MAX_STEPS_PER_TICK as f32);
+        let mut arrivals = Vec::new();
+        while self.accumulator >= timestep {
+            self.accumulator -= timestep;
+            arrivals.extend(scene.advance(timestep));
+        }
+        arrivals
+    }
+
+    /// Returns the pawn whose turn it is in the active scene, if it is in
+    /// turn-based mode.
+    pub fn current_turn(&self) -> Option<PawnId> {
+        self.get_active_scene().and_then(Scene::current_turn)
+    }
+
+    /// Ends the current turn of the active scene, returning whose turn it is next.
+    pub fn end_turn(&mut self) -> Option<PawnId> {
+        self.get_active_scene_mut().and_then(Scene::end_turn)
+    }
+
+    /// Calls `callback` with every event returned by [`Engine::drain_events`].
+    ///
+    /// Returns an id to pass to [`Engine::unsubscribe`].
+    pub fn subscribe(&mut self, callback: impl Fn(&GameEvent) + 'static) -> usize {
+        let id = self.next_subscription;
+        self.next_subscription += 1;
+        self.subscribers.push((id, Rc::new(callback)));
+        id
+    }
+
+    /// Removes a callback added with [`Engine::subscribe`], returning false if it
+    /// was already removed.
+    pub fn unsubscribe(&mut self, id: usize) -> bool {
+        let count = self.subscribers.len();
+        self.subscribers
+            .retain(|(subscription, _)| *subscription != id);
+        self.subscribers.len() != count
+    }
+
+    /// Returns the events since the last call, oldest first, after passing each of
+    /// them to the subscribers.
+    ///
+    /// Events of a scene are collected while it is active, so events of scenes left
+    /// with [`Engine::push_scene`] or [`Engine::pop_scene`] come before the
+    /// [`GameEvent::ScenePushed`] or [`GameEvent::ScenePopped`] that followed them.
+    pub fn drain_events(&mut self) -> Vec<GameEvent> {
+        self.collect_events();
+        let events = std::mem::take(&mut self.events);
+        for event in &events {
+            for (_, subscriber) in &self.subscribers {
+                subscriber(event);
+            }
+        }
+        events
+    }
+
+    /// Moves the events of the active scene to the engine.
+    fn collect_events(&mut self) {
+        if let Some(scene) = self.timeline.get_mut(self.timenow) {
+            self.events.extend(scene.drain_events());
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::prelude::{Layer, Map, Mask, Rect};
+
+    #[test]
+    fn serde_round_trip_skips_events_and_subscribers() {
+        let map = Map::new(
+            "room".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 4, 4)],
+                    vec![],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        );
+        let mut engine = Engine::new(Scene::new("room".into(), map.clone(), None));
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        engine.subscribe(move |_| counter.set(counter.get() + 1));
+        engine.push_scene(Scene::new("cellar".into(), map, None));
+        assert!(!engine.events.is_empty());
+
+        let json = serde_json::to_string(&engine).unwrap();
+        assert!(!json.contains("events"));
+        assert!(!json.contains("subscri"));
+
+        let mut loaded: Engine = serde_json::from_str(&json).unwrap();
+        assert!(loaded.events.is_empty());
+        assert!(loaded.subscribers.is_empty());
+        assert_eq!(loaded.next_subscription, 0);
+        assert_eq!(loaded.timeline.len(), 2);
+        assert_eq!(loaded.timenow, 1);
+        assert!(loaded.drain_events().is_empty());
+        assert_eq!(calls.get(), 0);
+    }
 }
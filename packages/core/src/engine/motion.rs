@@ -0,0 +1,360 @@
+use crate::{
+    errors::RPGXError,
+    prelude::{Coordinates, PathOptions, PawnId, Planner, Scene},
+};
+
+/// Speed of pawns without [`Scene::set_speed`], in tiles per second.
+pub const DEFAULT_SPEED: f32 = 10.0;
+
+/// Most tiles a pawn covers in a single [`Scene::advance`], so that a very fast
+/// pawn with a behavior can't keep the call busy forever.
+pub const MAX_TILES_PER_ADVANCE: u32 = 16;
+
+/// A one-tile move of a pawn in progress, advanced by [`Scene::advance`].
+///
+/// The pawn already occupies `to`; the motion only tells renderers where to draw it
+/// and keeps the pawn busy until it is complete.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Motion {
+    pub from: Coordinates,
+    pub to: Coordinates,
+    /// Fraction of the move done, from `0.0` to `1.0`.
+    pub progress: f32,
+}
+
+impl Motion {
+    /// Returns the position between `from` and `to` reached so far, in tiles.
+    pub fn position(&self) -> (f32, f32) {
+        let lerp = |from: u32, to: u32| from as f32 + (to as f32 - from as f32) * self.progress;
+        (lerp(self.from.x, self.to.x), lerp(self.from.y, self.to.y))
+    }
+}
+
+impl Scene {
+    /// Sets how fast pawn `id` moves between tiles, in tiles per second. Negative
+    /// speeds stop the pawn.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id, or
+    /// `RPGXError::InvalidSpeed` if `speed` is infinite or not a number.
+    pub fn set_speed(&mut self, id: PawnId, speed: f32) -> Result<(), RPGXError> {
+        if !self.pawns.contains_key(&id) {
+            return Err(RPGXError::PawnNotFound);
+        }
+        if !speed.is_finite() {
+            return Err(RPGXError::InvalidSpeed);
+        }
+        self.speeds.insert(id, speed.max(0.0));
+        Ok(())
+    }
+
+    /// Returns the speed of pawn `id` in tiles per second, [`DEFAULT_SPEED`] unless set.
+    pub fn speed(&self, id: PawnId) -> f32 {
+        self.speeds.get(&id).copied().unwrap_or(DEFAULT_SPEED)
+    }
+
+    /// Orders pawn `id` to walk to `target` over the next calls to [`Scene::advance`].
+    ///
+    /// The route is kept by a [`Planner`], so the pawn goes around tiles blocked on
+    /// the way. The order replaces the previous one and ends when the target is
+    /// reached or becomes unreachable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id.
+    pub fn walk(&mut self, id: PawnId, target: Coordinates) -> Result<(), RPGXError> {
+        self.walk_with(id, target, &PathOptions::default())
+    }
+
+    /// Same as [`Scene::walk`], routing with `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id.
+    pub fn walk_with(
+        &mut self,
+        id: PawnId,
+        target: Coordinates,
+        options: &PathOptions,
+    ) -> Result<(), RPGXError> {
+        if !self.pawns.contains_key(&id) {
+            return Err(RPGXError::PawnNotFound);
+        }
+        self.orders.insert(id, Planner::new(target, options));
+        Ok(())
+    }
+
+    /// Cancels the walk order of pawn `id`. A move in progress still completes.
+    pub fn stop(&mut self, id: PawnId) {
+        self.orders.remove(&id);
+    }
+
+    /// Returns true while pawn `id` is between two tiles.
+    pub fn is_moving(&self, id: PawnId) -> bool {
+        self.motions.contains_key(&id)
+    }
+
+    /// Returns where pawn `id` should be drawn, in tiles, interpolating the move in
+    /// progress if any.
+    pub fn interpolated_position(&self, id: PawnId) -> Option<(f32, f32)> {
+        match self.motions.get(&id) {
+            Some(motion) => Some(motion.position()),
+            None => self
+                .pawn(id)
+                .map(|pawn| (pawn.pointer.x as f32, pawn.pointer.y as f32)),
+        }
+    }
+
+    /// Advances moves in progress by `dt` seconds and starts new ones.
+    ///
+    /// Idle pawns take their next step from their walk order if they have one, or
+    /// from their [`Behavior`](crate::prelude::Behavior) otherwise. In turn-based
+    /// mode only the pawn whose turn it is moves, and a pawn with a behavior ends its
+    /// turn when it runs out of action points or has nowhere to go. Time left over
+    /// after a step completes is carried into the next one, up to
+    /// [`MAX_TILES_PER_ADVANCE`] tiles per pawn: the time beyond is dropped, as in
+    /// [`Engine::tick`](crate::prelude::Engine::tick). Pawns standing still fire
+    /// the [`TriggerOn::Stand`](crate::prelude::TriggerOn::Stand) actions of their
+    /// tile every [`STAND_INTERVAL`](crate::engine::trigger::STAND_INTERVAL). Returns the pawns that
+    /// arrived on a tile along with that tile.
+    pub fn advance(&mut self, dt: f32) -> Vec<(PawnId, Coordinates)> {
+        let ids: Vec<PawnId> = self.pawns.keys().copied().collect();
+        let mut arrivals = Vec::new();
+
+        for id in ids {
+            let mut budget = dt * self.speed(id);
+            let mut tiles = 0;
+            loop {
+                if let Some(motion) = self.motions.get_mut(&id) {
+                    let needed = 1.0 - motion.progress;
+                    if budget < needed {
+                        motion.progress += budget;
+                        break;
+                    }
+                    budget -= needed;
+                    arrivals.push((id, motion.to));
+                    self.motions.remove(&id);
+                    tiles += 1;
+                }
+
+                if budget <= 0.0 || tiles >= MAX_TILES_PER_ADVANCE {
+                    break;
+                }
+                if !self.start_next_move(id) {
+                    // Pawns with a behavior end their turn once they can't move
+                    if self.current_turn() == Some(id) && self.behaviors.contains_key(&id) {
+                        self.end_turn();
+                    }
+                    break;
+                }
+            }
+
+            if !self.is_moving(id) {
+                self.fire_stand_triggers(id, dt);
+            }
+        }
+
+        arrivals
+    }
+
+    /// Starts the next move of an idle pawn, returning false if it stays put.
+    fn start_next_move(&mut self, id: PawnId) -> bool {
+        if self.check_turn(id).is_err() {
+            return false;
+        }
+        let next = if let Some(mut planner) = self.orders.remove(&id) {
+            let Some(current) = self.pawn(id).map(|pawn| pawn.pointer) else {
+                return false;
+            };
+            planner.set_occupied(self.occupied_by_others(id));
+            match planner.next_step(&self.map, current) {
+                Ok(next) if next != current => {
+                    self.orders.insert(id, planner);
+                    Some(next)
+                }
+                // Arrived, or the target became unreachable
+                _ => None,
+            }
+        } else {
+            self.behavior_step(id)
+        };
+
+        // A refused step, for example into another pawn, is retried later
+        next.is_some_and(|next| self.try_move(id, next).is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Behavior, Effect, Engine, Layer, Map, Mask, Pawn, Rect, TextureId};
+
+    fn corridor() -> Map {
+        Map::new(
+            "corridor".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 10, 3)],
+                    vec![],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 1),
+        )
+    }
+
+    #[test]
+    fn moves_at_pawn_speed() {
+        let mut scene = Scene::new("test".into(), corridor(), None);
+        let hero = scene.load_pawn(TextureId(0));
+        scene.set_speed(hero, 4.0).unwrap();
+        scene.walk(hero, Coordinates::new(9, 1)).unwrap();
+
+        // 4 tiles per second: after 0.125s the first step is half done
+        assert!(scene.advance(0.125).is_empty());
+        assert!(scene.is_moving(hero));
+        assert_eq!(scene.interpolated_position(hero), Some((0.5, 1.0)));
+
+        let arrivals = scene.advance(0.5);
+        assert_eq!(
+            arrivals,
+            vec![
+                (hero, Coordinates::new(1, 1)),
+                (hero, Coordinates::new(2, 1))
+            ]
+        );
+        assert_eq!(scene.interpolated_position(hero), Some((2.5, 1.0)));
+
+        for _ in 0..20 {
+            scene.advance(0.25);
+        }
+        assert_eq!(scene.pawn(hero).unwrap().pointer, Coordinates::new(9, 1));
+        assert!(!scene.is_moving(hero));
+        assert_eq!(scene.interpolated_position(hero), Some((9.0, 1.0)));
+    }
+
+    #[test]
+    fn walk_orders_reroute_around_new_blocks() {
+        let mut scene = Scene::new("test".into(), corridor(), None);
+        let hero = scene.load_pawn(TextureId(0));
+        scene.walk(hero, Coordinates::new(4, 1)).unwrap();
+        scene.advance(0.1);
+
+        scene.map.load_layer(Layer::new(
+            "door".into(),
+            vec![Mask::new(
+                "door".into(),
+                vec![],
+                vec![Effect::Block(Rect::from_xywh(2, 1, 1, 1))],
+            )],
+            2,
+        ));
+
+        let mut visited = Vec::new();
+        for _ in 0..20 {
+            visited.extend(scene.advance(0.1).into_iter().map(|(_, tile)| tile));
+        }
+        assert!(!visited.contains(&Coordinates::new(2, 1)));
+        assert_eq!(visited.last(), Some(&Coordinates::new(4, 1)));
+    }
+
+    #[test]
+    fn behaviors_move_at_their_own_pace() {
+        let mut scene = Scene::new("test".into(), corridor(), None);
+        let slow = scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(0, 0),
+            texture_id: TextureId(0),
+        });
+        let fast = scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(0, 2),
+            texture_id: TextureId(0),
+        });
+        scene.set_speed(slow, 1.0).unwrap();
+        scene.set_speed(fast, 3.0).unwrap();
+        for id in [slow, fast] {
+            scene
+                .set_behavior(id, Behavior::patrol(vec![Coordinates::new(9, 0)]))
+                .unwrap();
+        }
+
+        let arrivals = scene.advance(2.0);
+        let steps = |id| arrivals.iter().filter(|(pawn, _)| *pawn == id).count();
+        assert_eq!(steps(slow), 2);
+        assert_eq!(steps(fast), 6);
+        assert_eq!(scene.pawn(slow).unwrap().pointer, Coordinates::new(2, 0));
+    }
+
+    #[test]
+    fn huge_speeds_cover_a_bounded_distance() {
+        let mut scene = Scene::new("test".into(), corridor(), None);
+        let guard = scene.load_pawn(TextureId(0));
+        assert_eq!(
+            scene.set_speed(guard, f32::INFINITY),
+            Err(RPGXError::InvalidSpeed)
+        );
+        assert_eq!(
+            scene.set_speed(guard, f32::NAN),
+            Err(RPGXError::InvalidSpeed)
+        );
+
+        scene.set_speed(guard, f32::MAX).unwrap();
+        scene
+            .set_behavior(
+                guard,
+                Behavior::patrol(vec![Coordinates::new(9, 1), Coordinates::new(0, 1)]),
+            )
+            .unwrap();
+        let arrivals = scene.advance(1.0);
+        assert_eq!(arrivals.len(), MAX_TILES_PER_ADVANCE as usize);
+        assert!(!scene.is_moving(guard));
+    }
+
+    /// An engine whose hero walks right at 1 tile per second, a quarter tile per step.
+    fn walking_engine() -> (Engine, PawnId) {
+        let mut scene = Scene::new("test".into(), corridor(), None);
+        let hero = scene.load_pawn(TextureId(0));
+        scene.set_speed(hero, 1.0).unwrap();
+        scene.walk(hero, Coordinates::new(9, 1)).unwrap();
+        let mut engine = Engine::new(scene);
+        engine.timestep = 0.25;
+        (engine, hero)
+    }
+
+    #[test]
+    fn tick_carries_leftover_time() {
+        let (mut engine, hero) = walking_engine();
+        let position = |engine: &Engine| {
+            engine
+                .get_active_scene()
+                .unwrap()
+                .interpolated_position(hero)
+        };
+
+        engine.tick(0.15);
+        assert_eq!(position(&engine), Some((0.0, 1.0)));
+        // 0.3s received in total: one step, 0.05s kept
+        engine.tick(0.15);
+        assert_eq!(position(&engine), Some((0.25, 1.0)));
+        engine.tick(0.2);
+        assert_eq!(position(&engine), Some((0.5, 1.0)));
+    }
+
+    #[test]
+    fn tick_drops_time_beyond_max_steps() {
+        let (mut engine, hero) = walking_engine();
+
+        // A 10s pause runs 10 steps and drops the rest
+        let arrivals = engine.tick(10.0);
+        assert_eq!(arrivals.len(), 2);
+        let scene = engine.get_active_scene().unwrap();
+        assert_eq!(scene.interpolated_position(hero), Some((2.5, 1.0)));
+
+        engine.tick(0.0);
+        let scene = engine.get_active_scene().unwrap();
+        assert_eq!(scene.interpolated_position(hero), Some((2.5, 1.0)));
+    }
+}
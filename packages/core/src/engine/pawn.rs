@@ -1,11 +1,44 @@
-use crate::prelude::Coordinates;
+use crate::prelude::{Coordinates, TextureId};
+
+/// Stable identifier of a [`Pawn`] within a [`Scene`](crate::prelude::Scene).
+///
+/// Ids are given out in loading order and never reused after a pawn is removed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct PawnId(pub u32);
 
 /// A [`Pawn`] represents an entity or character on the map, positioned on a specific [`Tile`].
 ///
 /// It holds a reference to the [`Tile`] it currently occupies, along with a [`Asset`] used
 /// to render its visual representation in the UI.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pawn {
     pub pointer: Coordinates,
-    pub texture_id: u32,
+    pub texture_id: TextureId,
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        let pawn = Pawn {
+            pointer: Coordinates::new(2, 5),
+            texture_id: TextureId(9),
+        };
+
+        let json = serde_json::to_string(&pawn).unwrap();
+        assert_eq!(json, r#"{"pointer":{"x":2,"y":5},"texture_id":9}"#);
+        let loaded: Pawn = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.pointer, pawn.pointer);
+        assert_eq!(loaded.texture_id, pawn.texture_id);
+
+        assert_eq!(serde_json::to_string(&PawnId(4)).unwrap(), "4");
+    }
 }
@@ -1,19 +1,57 @@
+use std::collections::{HashMap, HashSet};
+
+use indexmap::IndexMap;
+
 use crate::{
+    engine::event::EventQueue,
     errors::RPGXError,
-    prelude::{Coordinates, Direction, Map, Pawn},
+    prelude::{
+        ActionId, Behavior, Combatant, Coordinates, Delta, Direction, Fog, GameEvent, Map, Motion,
+        PathOptions, Pawn, PawnId, Planner, Reachable, ResourceRemap, TextureId, Turns,
+    },
     traits::Grid,
 };
 
 #[doc = include_str!("../../docs/scene.md")]
 /// RPG scene providing [`Pawn`] movement computation across the [`Map`].
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Scene {
     /// Scene name identifier.
     pub name: String,
     /// The game map with multiple layers defining terrain and obstacles.
     pub map: Map,
-    /// Optional pawn currently active in the scene.
-    pub pawn: Option<Pawn>,
+    /// Pawns in the scene keyed by their id, in loading order.
+    pub pawns: IndexMap<PawnId, Pawn>,
+    /// Whether pawns block each other, in routing as well as in movement.
+    pub pawn_collision: bool,
+    /// Tiles seen by each pawn for which [`Scene::enable_fog`] was called.
+    pub fogs: HashMap<PawnId, Fog>,
+    /// Movement behaviors of non-player pawns, see [`Scene::run_behaviors`].
+    pub behaviors: HashMap<PawnId, Behavior>,
+    /// Movement speed of each pawn set with [`Scene::set_speed`], in tiles per second.
+    pub speeds: HashMap<PawnId, f32>,
+    /// Moves in progress, advanced by [`Scene::advance`].
+    pub motions: HashMap<PawnId, Motion>,
+    /// Walk orders given with [`Scene::walk`].
+    pub orders: HashMap<PawnId, Planner>,
+    /// Direction each pawn last moved or turned to, see [`Scene::facing`].
+    pub facings: HashMap<PawnId, Direction>,
+    /// Initiative and action points of each pawn set with [`Scene::set_combatant`].
+    pub combatants: HashMap<PawnId, Combatant>,
+    /// Turn scheduler, `None` unless [`Scene::start_turns`] was called.
+    pub turns: Option<Turns>,
+    /// Once-only actions already fired, by layer name, mask name, origin of the first
+    /// mask tile and action id, so they stay spent when layers are added or reordered
+    /// while copies of a mask merged elsewhere keep their own.
+    pub spent: HashSet<(String, String, Coordinates, ActionId)>,
+    /// Seconds each pawn has stood still since the last stand action fired.
+    pub(crate) standing: HashMap<PawnId, f32>,
+    /// Events not drained yet, see [`Scene::drain_events`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) events: EventQueue,
+    /// Id given to the next loaded pawn. Ids of removed pawns are never reused.
+    next_id: u32,
 }
 
 impl Scene {
@@ -23,18 +61,41 @@ impl Scene {
     ///
     /// * `name` - A string identifier for the scene.
     /// * `map` - The `Map` instance used in the scene.
-    /// * `pawn` - Optional initial `Pawn` to place in the scene.
+    /// * `pawn` - Optional initial `Pawn` to place in the scene, loaded as `PawnId(0)`.
     pub fn new(name: String, map: Map, pawn: Option<Pawn>) -> Self {
-        Self { map, pawn, name }
+        let mut scene = Self {
+            map,
+            name,
+            pawns: IndexMap::new(),
+            pawn_collision: false,
+            fogs: HashMap::new(),
+            behaviors: HashMap::new(),
+            speeds: HashMap::new(),
+            motions: HashMap::new(),
+            orders: HashMap::new(),
+            facings: HashMap::new(),
+            combatants: HashMap::new(),
+            turns: None,
+            spent: HashSet::new(),
+            standing: HashMap::new(),
+            events: EventQueue::default(),
+            next_id: 0,
+        };
+        if let Some(pawn) = pawn {
+            scene.load_pawn_at(pawn);
+        }
+        scene
     }
 
     /// Load a [`Pawn`] into the scene at the map's default spawn position.
     ///
+    /// Returns the id of the new pawn.
+    ///
     /// # Arguments
     ///
     /// * `texture_id` - Identifier for the pawn's texture/sprite.
-    pub fn load_pawn(&mut self, texture_id: u32) {
-        self.pawn = Some(Pawn {
+    pub fn load_pawn(&mut self, texture_id: TextureId) -> PawnId {
+        self.load_pawn_at(Pawn {
             texture_id,
             pointer: self.map.spawn,
         })
@@ -42,11 +103,130 @@ impl Scene {
 
     /// Load a [`Pawn`] into the scene at a specific location.
     ///
+    /// Returns the id of the new pawn.
+    ///
     /// # Arguments
     ///
     /// * `pawn` - The pawn instance with desired coordinates.
-    pub fn load_pawn_at(&mut self, pawn: Pawn) {
-        self.pawn = Some(pawn);
+    pub fn load_pawn_at(&mut self, pawn: Pawn) -> PawnId {
+        while self.pawns.contains_key(&PawnId(self.next_id)) {
+            self.next_id += 1;
+        }
+        let id = PawnId(self.next_id);
+        self.next_id += 1;
+        self.pawns.insert(id, pawn);
+        self.join_turns(id);
+        id
+    }
+
+    /// Removes a pawn along with its fog and behavior from the scene. Its id is not
+    /// given to later pawns.
+    pub fn remove_pawn(&mut self, id: PawnId) -> Option<Pawn> {
+        self.fogs.remove(&id);
+        self.behaviors.remove(&id);
+        self.speeds.remove(&id);
+        self.motions.remove(&id);
+        self.orders.remove(&id);
+        self.facings.remove(&id);
+        self.combatants.remove(&id);
+        self.standing.remove(&id);
+        self.leave_turns(id);
+        self.pawns.shift_remove(&id)
+    }
+
+    /// Replaces the resource ids of the map, the pawn textures and the spent actions
+    /// with their new ids in `remap`, see [`Library::merge`](crate::prelude::Library::merge).
+    pub fn remap(&mut self, remap: &ResourceRemap) {
+        self.map.remap(remap);
+        for pawn in self.pawns.values_mut() {
+            pawn.texture_id = remap.textures.apply(pawn.texture_id);
+        }
+        self.spent = self
+            .spent
+            .drain()
+            .map(|(layer, mask, origin, action)| (layer, mask, origin, remap.actions.apply(action)))
+            .collect();
+    }
+
+    /// Returns the pawn with the given id.
+    pub fn pawn(&self, id: PawnId) -> Option<&Pawn> {
+        self.pawns.get(&id)
+    }
+
+    /// Returns the id of the first pawn standing on `target`.
+    pub fn pawn_at(&self, target: &Coordinates) -> Option<PawnId> {
+        self.pawns
+            .iter()
+            .find(|(_, pawn)| pawn.pointer == *target)
+            .map(|(id, _)| *id)
+    }
+
+    /// Returns the tiles `id` may not enter because other pawns stand on them.
+    ///
+    /// Empty unless [`Scene::pawn_collision`] is enabled.
+    pub fn occupied_by_others(&self, id: PawnId) -> HashSet<Coordinates> {
+        if !self.pawn_collision {
+            return HashSet::new();
+        }
+        self.pawns
+            .iter()
+            .filter(|(other, _)| **other != id)
+            .map(|(_, pawn)| pawn.pointer)
+            .collect()
+    }
+
+    fn pointer_of(&self, id: PawnId) -> Result<Coordinates, RPGXError> {
+        self.pawn(id)
+            .map(|pawn| pawn.pointer)
+            .ok_or(RPGXError::PawnNotFound)
+    }
+
+    /// Adds the tiles taken by other pawns, except `target`, to the forbidden tiles.
+    fn options_for(
+        &self,
+        id: PawnId,
+        target: Option<&Coordinates>,
+        options: &PathOptions,
+    ) -> PathOptions {
+        let mut options = options.clone();
+        options.forbidden.extend(
+            self.occupied_by_others(id)
+                .into_iter()
+                .filter(|tile| Some(tile) != target),
+        );
+        options
+    }
+
+    /// Starts tracking the tiles pawn `id` sees within `radius` tiles.
+    ///
+    /// The fog is updated on every move of the pawn; call [`Scene::update_fog`] after
+    /// changing the map to refresh it in place.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id.
+    pub fn enable_fog(&mut self, id: PawnId, radius: u32) -> Result<(), RPGXError> {
+        self.pointer_of(id)?;
+        self.fogs.insert(id, Fog::new(radius));
+        self.update_fog(id);
+        Ok(())
+    }
+
+    /// Stops tracking the tiles seen by pawn `id` and forgets them.
+    pub fn disable_fog(&mut self, id: PawnId) {
+        self.fogs.remove(&id);
+    }
+
+    /// Returns the fog of pawn `id`, if enabled.
+    pub fn fog(&self, id: PawnId) -> Option<&Fog> {
+        self.fogs.get(&id)
+    }
+
+    /// Recomputes the tiles visible from pawn `id`, if its fog is enabled.
+    pub fn update_fog(&mut self, id: PawnId) {
+        if let (Some(fog), Some(pawn)) = (self.fogs.get_mut(&id), self.pawns.get(&id)) {
+            fog.update(&self.map, &pawn.pointer);
+        }
     }
 
     /// Walk asynchronously to the target coordinates along the best computed path.
@@ -58,27 +238,34 @@ impl Scene {
     /// Returns `RPGXError` if the pawn is missing, no path is found, or a step fails.
     pub async fn walk_to(
         &mut self,
+        id: PawnId,
+        target_position: Coordinates,
+    ) -> Result<Coordinates, RPGXError> {
+        self.walk_to_with(id, target_position, &PathOptions::default())
+            .await
+    }
+
+    /// Walk asynchronously to the target coordinates along a path computed with `options`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError` if the pawn is missing, no path is found, or a step fails.
+    pub async fn walk_to_with(
+        &mut self,
+        id: PawnId,
         target_position: Coordinates,
+        options: &PathOptions,
     ) -> Result<Coordinates, RPGXError> {
-        let start = self
-            .pawn
-            .as_ref()
-            .map(|p| p.pointer)
-            .ok_or_else(|| RPGXError::TileNotFound(target_position))?;
+        let start = self.pointer_of(id)?;
 
         // Find the best path from current to target coordinates
-        let path =
-            self.map
-                .find_path(&start, &target_position)
-                .ok_or(RPGXError::PathNotFround {
-                    from: start,
-                    to: target_position,
-                })?;
+        let path = self.steps_to_with(id, target_position, options)?;
 
-        let mut tile = None;
+        // The path starts on the current tile, which takes no step
+        let mut tile = path.first().copied();
         // Walk each step in the path, returning early if any step fails
-        for step_coords in path {
-            tile = Some(self.move_to(step_coords)?);
+        for step_coords in path.into_iter().skip(1) {
+            tile = Some(self.move_to(id, step_coords)?);
         }
 
         tile.ok_or(RPGXError::WalkFailed {
@@ -87,50 +274,137 @@ impl Scene {
         })
     }
 
+    /// Take one step along the route maintained by `planner`.
+    ///
+    /// The planner repairs its route whenever the map changed since the previous
+    /// step, so a pawn walking step by step goes around tiles that became blocked,
+    /// and around other pawns when [`Scene::pawn_collision`] is enabled.
+    /// Returns the pawn position, which stays put once the goal is reached.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError` if the pawn is missing or the goal is no longer reachable.
+    pub fn walk_step(
+        &mut self,
+        id: PawnId,
+        planner: &mut Planner,
+    ) -> Result<Coordinates, RPGXError> {
+        let current = self.pointer_of(id)?;
+
+        planner.set_occupied(self.occupied_by_others(id));
+        let next = planner.next_step(&self.map, current)?;
+        self.move_to(id, next)
+    }
+
     /// Take a single movement step in the specified direction.
     ///
+    /// Diagonal steps are refused when they would cut the corner of a blocking tile.
+    /// The pawn faces `direction` even when the step is refused, unless it is another
+    /// pawn's turn.
+    ///
     /// # Arguments
     ///
+    /// * `id` - The pawn to move.
     /// * `direction` - The direction to move.
     ///
     /// # Errors
     ///
-    /// Returns `RPGXError` if the pawn is missing, the target tile is invalid or blocked.
-    pub fn step_to(&mut self, direction: Direction) -> Result<Coordinates, RPGXError> {
+    /// Returns `RPGXError` if the pawn is missing, the target tile is invalid, blocked
+    /// or occupied.
+    pub fn step_to(&mut self, id: PawnId, direction: Direction) -> Result<Coordinates, RPGXError> {
         let delta = direction.to_delta();
-        let current = self
-            .pawn
-            .as_ref()
-            .map(|p| p.pointer)
-            .ok_or(RPGXError::PawnNotFound)?;
+        let current = self.pointer_of(id)?;
+        if !matches!(self.turns, Some(ref turns) if turns.current() != Some(id)) {
+            self.facings.insert(id, direction);
+        }
 
         // Calculate the target coordinates by applying the delta
-        if let Some(target_position) = current + delta {
-            self.move_to(target_position)
-        } else {
-            Err(RPGXError::StepFailed(direction))
+        match current + delta {
+            Some(target_position) if !self.map.cuts_corner(&current, direction) => {
+                self.move_to(id, target_position)
+            }
+            _ => {
+                let error = RPGXError::StepFailed(direction);
+                self.emit(GameEvent::Blocked { pawn: id, error });
+                Err(error)
+            }
         }
     }
 
     /// Move the pawn directly to the target coordinates if movement is allowed.
     ///
-    /// Checks map blocking and updates the pawn's position if possible.
+    /// Checks map blocking and, when [`Scene::pawn_collision`] is enabled, other
+    /// pawns. Updates the pawn's position if possible, along with its fog. Moving to
+    /// a neighbouring tile starts a [`Motion`] that [`Scene::advance`] plays out.
+    /// The pawn then faces the direction of the move, see [`Scene::facing`]. Moving
+    /// to the tile the pawn stands on does nothing and spends no action point.
+    ///
+    /// Emits [`GameEvent::PawnMoved`] along with the mask and action events of the
+    /// move, or [`GameEvent::Blocked`] if the move is refused.
     ///
     /// # Errors
     ///
-    /// Returns `RPGXError` if the pawn is missing or the target is blocked.
-    pub fn move_to(&mut self, target_position: Coordinates) -> Result<Coordinates, RPGXError> {
+    /// Returns `RPGXError` if the pawn is missing, the target is blocked or occupied.
+    pub fn move_to(
+        &mut self,
+        id: PawnId,
+        target_position: Coordinates,
+    ) -> Result<Coordinates, RPGXError> {
+        self.try_move(id, target_position).inspect_err(|error| {
+            self.emit(GameEvent::Blocked {
+                pawn: id,
+                error: *error,
+            })
+        })
+    }
+
+    /// Same as [`Scene::move_to`] without the [`GameEvent::Blocked`] event, for moves
+    /// decided by the scene itself that are retried until they succeed.
+    pub(crate) fn try_move(
+        &mut self,
+        id: PawnId,
+        target_position: Coordinates,
+    ) -> Result<Coordinates, RPGXError> {
+        let from = self.pointer_of(id)?;
         // Check if movement to the target is allowed by the map
-        if self.map.contains(&target_position) && !self.map.is_blocking_at(&target_position) {
-            if let Some(pawn) = self.pawn.as_mut() {
-                pawn.pointer = target_position;
-                Ok(target_position)
-            } else {
-                Err(RPGXError::PawnNotFound)
-            }
+        if !self.map.contains(&target_position) || self.map.is_blocking_at(&target_position) {
+            return Err(RPGXError::TileNotWalkable(target_position));
+        }
+        // Staying on the current tile is not a move
+        if from == target_position {
+            return Ok(target_position);
+        }
+        self.check_turn(id)?;
+        if self.occupied_by_others(id).contains(&target_position) {
+            return Err(RPGXError::TileOccupied(target_position));
+        }
+
+        let pawn = self.pawns.get_mut(&id).ok_or(RPGXError::PawnNotFound)?;
+        pawn.pointer = target_position;
+        if from.x.abs_diff(target_position.x) <= 1 && from.y.abs_diff(target_position.y) <= 1 {
+            self.motions.insert(
+                id,
+                Motion {
+                    from,
+                    to: target_position,
+                    progress: 0.0,
+                },
+            );
         } else {
-            Err(RPGXError::TileNotWalkable(target_position))
+            self.motions.remove(&id);
         }
+        let delta = Delta::new(
+            (target_position.x as i32 - from.x as i32).signum(),
+            (target_position.y as i32 - from.y as i32).signum(),
+        );
+        if let Some(direction) = Direction::from_delta(&delta) {
+            self.facings.insert(id, direction);
+        }
+        self.spend_action_point();
+        self.standing.remove(&id);
+        self.update_fog(id);
+        self.emit_move(id, from, target_position);
+        Ok(target_position)
     }
 
     /// Compute all the steps from the current pawn position to the target.
@@ -140,29 +414,56 @@ impl Scene {
     /// # Errors
     ///
     /// Returns `RPGXError` if the pawn is missing or no path is found.
-    pub fn steps_to(&self, target_position: Coordinates) -> Result<Vec<Coordinates>, RPGXError> {
-        let start = self
-            .pawn
-            .as_ref()
-            .map(|p| p.pointer)
-            .ok_or(RPGXError::PawnNotFound)?;
-
-        let path =
-            self.map
-                .find_path(&start, &target_position)
-                .ok_or(RPGXError::PathNotFround {
-                    from: start,
-                    to: target_position,
-                })?;
+    pub fn steps_to(
+        &self,
+        id: PawnId,
+        target_position: Coordinates,
+    ) -> Result<Vec<Coordinates>, RPGXError> {
+        self.steps_to_with(id, target_position, &PathOptions::default())
+    }
 
-        Ok(path)
+    /// Compute all the steps from the current pawn position to the target using `options`.
+    ///
+    /// When [`Scene::pawn_collision`] is enabled, tiles taken by other pawns are
+    /// forbidden, except the target.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError` if the pawn is missing or no path satisfies the options.
+    pub fn steps_to_with(
+        &self,
+        id: PawnId,
+        target_position: Coordinates,
+        options: &PathOptions,
+    ) -> Result<Vec<Coordinates>, RPGXError> {
+        let start = self.pointer_of(id)?;
+        let options = self.options_for(id, Some(&target_position), options);
+
+        self.map.find_path_with(&start, &target_position, &options)
+    }
+
+    /// Returns every tile pawn `id` can reach for a total cost of at most `max_cost`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id.
+    pub fn reachable(&self, id: PawnId, max_cost: u32) -> Result<Reachable, RPGXError> {
+        let start = self.pointer_of(id)?;
+        let options = PathOptions {
+            max_cost: Some(max_cost),
+            ..Default::default()
+        };
+
+        Ok(self
+            .map
+            .reachable_from_with(&start, &self.options_for(id, None, &options)))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::prelude::{Coordinates, Layer, Map};
+    use crate::prelude::{Coordinates, Effect, FogState, Layer, Map, Mask, Pawn, Rect};
 
     // Helper to build a minimal Map with no blocking tiles
     fn create_test_map() -> Map {
@@ -175,14 +476,336 @@ mod tests {
         let mut map = create_test_map();
         // Manually override map.move_allowed to simulate blocking
         // For this test, patch move_allowed to always return false
-        map.layers.clear(); // no layers, no tiles = blocked
+        map.layers_mut().clear(); // no layers, no tiles = blocked
         let mut scene = Scene::new("test".into(), map, None);
-        scene.load_pawn(1);
+        let pawn = scene.load_pawn(TextureId(1));
 
-        let result = scene.move_to(Coordinates::new(0, 0));
+        let result = scene.move_to(pawn, Coordinates::new(0, 0));
         assert_eq!(
             result,
             Err(RPGXError::TileNotWalkable(Coordinates::new(0, 0)))
         );
     }
+
+    #[test]
+    fn test_diagonal_step_respects_corners() {
+        let map = Map::new(
+            "test_map".into(),
+            vec![Layer::new(
+                "base".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 3, 3)],
+                    vec![Effect::Block(Rect::from_xywh(1, 0, 1, 1))],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        );
+        let mut scene = Scene::new("test".into(), map, None);
+        let pawn = scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(0, 0),
+            texture_id: TextureId(1),
+        });
+
+        // (1, 1) is free, but reaching it diagonally would clip the block at (1, 0).
+        assert_eq!(
+            scene.step_to(pawn, Direction::DownRight),
+            Err(RPGXError::StepFailed(Direction::DownRight))
+        );
+        assert_eq!(
+            scene.step_to(pawn, Direction::Down),
+            Ok(Coordinates::new(0, 1))
+        );
+        assert_eq!(
+            scene.step_to(pawn, Direction::DownRight),
+            Ok(Coordinates::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn test_steps_to_with_options() {
+        let map = Map::new(
+            "test_map".into(),
+            vec![Layer::new(
+                "base".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 4, 2)],
+                    vec![],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        );
+        let mut scene = Scene::new("test".into(), map, None);
+        let pawn = scene.load_pawn(TextureId(1));
+
+        let options = PathOptions {
+            forbidden: [Coordinates::new(1, 0)].into(),
+            ..Default::default()
+        };
+        let steps = scene
+            .steps_to_with(pawn, Coordinates::new(3, 0), &options)
+            .unwrap();
+        assert_eq!(steps.len(), 6);
+        assert!(!steps.contains(&Coordinates::new(1, 0)));
+
+        let options = PathOptions {
+            max_length: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            scene.steps_to_with(pawn, Coordinates::new(3, 0), &options),
+            Err(RPGXError::PathNotFround {
+                from: Coordinates::new(0, 0),
+                to: Coordinates::new(3, 0),
+            })
+        );
+    }
+
+    #[test]
+    fn test_reachable_uses_pawn_position() {
+        let map = Map::new(
+            "test_map".into(),
+            vec![Layer::new(
+                "base".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 5, 1)],
+                    vec![],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        );
+        let mut scene = Scene::new("test".into(), map, None);
+        assert_eq!(scene.reachable(PawnId(0), 2), Err(RPGXError::PawnNotFound));
+
+        let pawn = scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(2, 0),
+            texture_id: TextureId(1),
+        });
+        let reachable = scene.reachable(pawn, 1).unwrap();
+        assert_eq!(reachable.to_rects(), vec![Rect::from_xywh(1, 0, 3, 1)]);
+    }
+
+    #[test]
+    fn test_walk_step_reroutes_around_closed_door() {
+        let floor = Mask::new("floor".into(), vec![Rect::from_xywh(0, 0, 5, 3)], vec![]);
+        let door = Mask::new(
+            "door".into(),
+            vec![],
+            vec![Effect::Block(Rect::from_xywh(2, 0, 1, 1))],
+        );
+        let map = Map::new(
+            "test_map".into(),
+            vec![Layer::new("base".into(), vec![floor, door], 1)],
+            Coordinates::new(0, 1),
+        );
+        let mut scene = Scene::new("test".into(), map, None);
+        let pawn = scene.load_pawn(TextureId(1));
+
+        let goal = Coordinates::new(4, 1);
+        let mut planner = Planner::new(goal, &PathOptions::default());
+        assert_eq!(
+            scene.walk_step(pawn, &mut planner),
+            Ok(Coordinates::new(1, 1))
+        );
+
+        // Another door closes the straight corridor
+        scene.map.layers_mut()[0].masks[1].effects =
+            vec![Effect::Block(Rect::from_xywh(2, 1, 1, 1))];
+
+        let mut position = Coordinates::new(1, 1);
+        while position != goal {
+            position = scene.walk_step(pawn, &mut planner).unwrap();
+            assert_ne!(position, Coordinates::new(2, 1));
+        }
+        assert_eq!(scene.walk_step(pawn, &mut planner), Ok(goal));
+    }
+
+    #[test]
+    fn test_fog_follows_pawn_moves() {
+        let floor = Mask::new("floor".into(), vec![Rect::from_xywh(0, 0, 7, 3)], vec![]);
+        let wall = Mask::new(
+            "wall".into(),
+            vec![],
+            vec![Effect::Opaque(Rect::from_xywh(3, 0, 1, 3))],
+        );
+        let map = Map::new(
+            "test_map".into(),
+            vec![Layer::new("base".into(), vec![floor, wall], 1)],
+            Coordinates::new(0, 1),
+        );
+        let mut scene = Scene::new("test".into(), map, None);
+        let pawn = scene.load_pawn(TextureId(1));
+        scene.enable_fog(pawn, 10).unwrap();
+
+        let fog = scene.fog(pawn).unwrap();
+        assert_eq!(fog.state_at(&Coordinates::new(3, 1)), FogState::Visible);
+        assert_eq!(fog.state_at(&Coordinates::new(5, 1)), FogState::Unknown);
+
+        // Opaque tiles do not block movement, walk through the wall
+        scene.move_to(pawn, Coordinates::new(3, 1)).unwrap();
+        scene.move_to(pawn, Coordinates::new(5, 1)).unwrap();
+
+        let fog = scene.fog(pawn).unwrap();
+        assert_eq!(fog.state_at(&Coordinates::new(6, 2)), FogState::Visible);
+        assert_eq!(fog.state_at(&Coordinates::new(0, 1)), FogState::Explored);
+    }
+
+    fn corridor() -> Map {
+        Map::new(
+            "test_map".into(),
+            vec![Layer::new(
+                "base".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 5, 2)],
+                    vec![],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        )
+    }
+
+    #[test]
+    fn test_pawn_ids_are_stable() {
+        let mut scene = Scene::new("test".into(), corridor(), None);
+        let hero = scene.load_pawn(TextureId(1));
+        let npc = scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(4, 0),
+            texture_id: TextureId(2),
+        });
+        assert_ne!(hero, npc);
+
+        assert!(scene.remove_pawn(hero).is_some());
+        let other = scene.load_pawn(TextureId(3));
+        assert_ne!(other, hero);
+        assert_eq!(scene.pawn_at(&Coordinates::new(4, 0)), Some(npc));
+        assert_eq!(
+            scene.move_to(hero, Coordinates::new(1, 0)),
+            Err(RPGXError::PawnNotFound)
+        );
+        assert_eq!(
+            scene.move_to(npc, Coordinates::new(3, 0)),
+            Ok(Coordinates::new(3, 0))
+        );
+        assert_eq!(scene.pawn(other).unwrap().pointer, Coordinates::new(0, 0));
+    }
+
+    #[test]
+    fn test_pawns_block_each_other_when_enabled() {
+        let mut scene = Scene::new("test".into(), corridor(), None);
+        let hero = scene.load_pawn(TextureId(1));
+        let npc = scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(2, 0),
+            texture_id: TextureId(2),
+        });
+
+        let steps = scene.steps_to(hero, Coordinates::new(4, 0)).unwrap();
+        assert!(steps.contains(&Coordinates::new(2, 0)));
+
+        scene.pawn_collision = true;
+        let steps = scene.steps_to(hero, Coordinates::new(4, 0)).unwrap();
+        assert!(!steps.contains(&Coordinates::new(2, 0)));
+        assert!(scene.steps_to(hero, Coordinates::new(2, 0)).is_ok());
+        assert!(
+            !scene
+                .reachable(hero, 2)
+                .unwrap()
+                .contains(&Coordinates::new(2, 0))
+        );
+
+        scene.move_to(hero, Coordinates::new(1, 0)).unwrap();
+        assert_eq!(
+            scene.step_to(hero, Direction::Right),
+            Err(RPGXError::TileOccupied(Coordinates::new(2, 0)))
+        );
+        assert_eq!(
+            scene.step_to(npc, Direction::Right),
+            Ok(Coordinates::new(3, 0))
+        );
+        assert_eq!(
+            scene.step_to(hero, Direction::Right),
+            Ok(Coordinates::new(2, 0))
+        );
+    }
+
+    #[test]
+    fn test_walk_step_goes_around_pawns() {
+        let mut scene = Scene::new("test".into(), corridor(), None);
+        scene.pawn_collision = true;
+        let hero = scene.load_pawn(TextureId(1));
+        scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(2, 0),
+            texture_id: TextureId(2),
+        });
+
+        let goal = Coordinates::new(4, 0);
+        let mut planner = Planner::new(goal, &PathOptions::default());
+        let mut position = Coordinates::new(0, 0);
+        while position != goal {
+            position = scene.walk_step(hero, &mut planner).unwrap();
+            assert_ne!(position, Coordinates::new(2, 0));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip_resumes_the_game() {
+        use crate::prelude::{Behavior, Combatant, Engine};
+
+        let map = Map::new(
+            "field".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![Mask::new(
+                    "grass".into(),
+                    vec![Rect::from_xywh(0, 0, 8, 8)],
+                    vec![],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        );
+        let mut scene = Scene::new("field".into(), map, None);
+        let hero = scene.load_pawn(TextureId(1));
+        let guard = scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(7, 7),
+            texture_id: TextureId(2),
+        });
+        scene.enable_fog(hero, 2).unwrap();
+        scene.set_combatant(guard, Combatant::default()).unwrap();
+        scene
+            .set_behavior(guard, Behavior::patrol(vec![Coordinates::new(7, 0)]))
+            .unwrap();
+        scene.walk(hero, Coordinates::new(5, 3)).unwrap();
+
+        let mut engine = Engine::new(scene);
+        engine.tick(0.15);
+
+        let json = serde_json::to_string(&engine).unwrap();
+        let mut loaded: Engine = serde_json::from_str(&json).unwrap();
+        for _ in 0..20 {
+            engine.tick(0.1);
+            loaded.tick(0.1);
+        }
+
+        let (scene, loaded) = (
+            engine.get_active_scene().unwrap(),
+            loaded.get_active_scene().unwrap(),
+        );
+        for id in [hero, guard] {
+            assert_eq!(
+                loaded.pawn(id).unwrap().pointer,
+                scene.pawn(id).unwrap().pointer
+            );
+        }
+        assert_eq!(loaded.pawn(hero).unwrap().pointer, Coordinates::new(5, 3));
+        assert_eq!(loaded.fog(hero), scene.fog(hero));
+        assert_eq!(loaded.combatant(guard), scene.combatant(guard));
+    }
 }
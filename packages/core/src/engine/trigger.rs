@@ -0,0 +1,334 @@
+use crate::{
+    errors::RPGXError,
+    prelude::{ActionId, Coordinates, Direction, GameEvent, PawnId, Scene, TileTrigger, TriggerOn},
+};
+
+/// Seconds a pawn stands still between two firings of a [`TriggerOn::Stand`] action.
+pub const STAND_INTERVAL: f32 = 1.0;
+
+impl Scene {
+    /// Returns the direction pawn `id` faces, [`Direction::Down`] until it first
+    /// moves or turns.
+    pub fn facing(&self, id: PawnId) -> Option<Direction> {
+        self.pawns
+            .contains_key(&id)
+            .then(|| self.facings.get(&id).copied().unwrap_or(Direction::Down))
+    }
+
+    /// Turns pawn `id` toward `direction` without moving it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id.
+    pub fn face(&mut self, id: PawnId, direction: Direction) -> Result<(), RPGXError> {
+        if !self.pawns.contains_key(&id) {
+            return Err(RPGXError::PawnNotFound);
+        }
+        self.facings.insert(id, direction);
+        Ok(())
+    }
+
+    /// Returns the tile in front of pawn `id`, `None` past the top or left edge of
+    /// the map.
+    pub fn facing_tile(&self, id: PawnId) -> Option<Coordinates> {
+        let pointer = self.pawn(id)?.pointer;
+        pointer + self.facing(id)?.to_delta()
+    }
+
+    /// Fires the [`TriggerOn::Interact`] actions of the tile in front of pawn `id`.
+    ///
+    /// Interacting costs no action point. Returns the ids of the actions fired, see
+    /// [`Scene::interact_at`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id, or
+    /// `RPGXError::NotYourTurn` if it is another pawn's turn.
+    pub fn interact(&mut self, id: PawnId) -> Result<Vec<ActionId>, RPGXError> {
+        if !self.pawns.contains_key(&id) {
+            return Err(RPGXError::PawnNotFound);
+        }
+        if matches!(self.turns, Some(ref turns) if turns.current() != Some(id)) {
+            return Err(RPGXError::NotYourTurn(id));
+        }
+        match self.facing_tile(id) {
+            Some(target) => self.interact_at(id, target),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Fires the [`TriggerOn::Interact`] actions of `target` for pawn `id`.
+    ///
+    /// The distance between the pawn and `target` is not checked. Returns the ids of
+    /// the actions fired, also emitted as [`GameEvent::ActionTriggered`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id.
+    pub fn interact_at(
+        &mut self,
+        id: PawnId,
+        target: Coordinates,
+    ) -> Result<Vec<ActionId>, RPGXError> {
+        if !self.pawns.contains_key(&id) {
+            return Err(RPGXError::PawnNotFound);
+        }
+        let triggers = self.triggers_at(&target);
+        Ok(self.fire(id, target, triggers, TriggerOn::Interact))
+    }
+
+    /// Fires the exit actions of `from` and the enter actions of `to` for a move of
+    /// pawn `id`. Actions of a mask covering both tiles don't fire.
+    pub(crate) fn fire_move_triggers(&mut self, id: PawnId, from: Coordinates, to: Coordinates) {
+        let before = self.triggers_at(&from);
+        let after = self.triggers_at(&to);
+
+        let exited = before.iter().filter(|t| !after.contains(t)).copied();
+        self.fire(id, from, exited.collect(), TriggerOn::Exit);
+        let entered = after.iter().filter(|t| !before.contains(t)).copied();
+        self.fire(id, to, entered.collect(), TriggerOn::Enter);
+    }
+
+    /// Counts the time pawn `id` stood still and fires the stand actions of its tile
+    /// once every [`STAND_INTERVAL`].
+    pub(crate) fn fire_stand_triggers(&mut self, id: PawnId, dt: f32) {
+        let Some(pointer) = self.pawn(id).map(|pawn| pawn.pointer) else {
+            return;
+        };
+        let standing = self.standing.entry(id).or_default();
+        *standing += dt;
+        let count = (*standing / STAND_INTERVAL).floor();
+        *standing -= count * STAND_INTERVAL;
+
+        for _ in 0..count as u32 {
+            let triggers = self.triggers_at(&pointer);
+            self.fire(id, pointer, triggers, TriggerOn::Stand);
+        }
+    }
+
+    fn triggers_at(&self, target: &Coordinates) -> Vec<TileTrigger> {
+        self.map
+            .tile_at(target)
+            .map(|tile| tile.triggers.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the layer name, mask name and origin of the first tile of the mask at
+    /// position `mask` among the masks of every layer, see [`TileTrigger::mask`].
+    ///
+    /// The origin tells apart the copies of a mask merged at other positions.
+    fn mask_key(&self, mask: usize) -> (String, String, Coordinates) {
+        self.map
+            .layers()
+            .iter()
+            .flat_map(|layer| layer.masks.iter().map(move |m| (&layer.name, m)))
+            .nth(mask)
+            .map(|(layer, mask)| {
+                let origin = mask.tiles.first().map(|rect| rect.origin);
+                (layer.clone(), mask.name.clone(), origin.unwrap_or_default())
+            })
+            .unwrap_or_default()
+    }
+
+    /// Fires the `on` actions among `triggers`, skipping spent once-only ones.
+    fn fire(
+        &mut self,
+        id: PawnId,
+        at: Coordinates,
+        triggers: Vec<TileTrigger>,
+        on: TriggerOn,
+    ) -> Vec<ActionId> {
+        let mut fired = Vec::new();
+        for trigger in triggers.into_iter().filter(|t| t.trigger.on == on) {
+            if trigger.trigger.once {
+                let (layer, mask, origin) = self.mask_key(trigger.mask);
+                if !self.spent.insert((layer, mask, origin, trigger.action)) {
+                    continue;
+                }
+            }
+            self.emit(GameEvent::ActionTriggered {
+                pawn: id,
+                action: trigger.action,
+                at,
+            });
+            fired.push(trigger.action);
+        }
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Effect, Layer, Map, Mask, Rect, TextureId, Trigger};
+
+    /// A 6x1 hall with a 2-tile carpet on (1, 0) and (2, 0) and a chest on (5, 0).
+    fn hall(carpet: Trigger) -> Map {
+        Map::new(
+            "hall".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![
+                    Mask::new("floor".into(), vec![Rect::from_xywh(0, 0, 6, 1)], vec![]),
+                    Mask::new(
+                        "carpet".into(),
+                        vec![Rect::from_xywh(1, 0, 2, 1)],
+                        vec![Effect::Trigger(ActionId(1), carpet)],
+                    ),
+                    Mask::new(
+                        "chest".into(),
+                        vec![Rect::from_xywh(5, 0, 1, 1)],
+                        vec![
+                            Effect::Trigger(ActionId(2), Trigger::once(TriggerOn::Interact)),
+                            Effect::Block(Rect::from_xywh(5, 0, 1, 1)),
+                        ],
+                    ),
+                ],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        )
+    }
+
+    fn fired(scene: &mut Scene) -> Vec<(ActionId, Coordinates)> {
+        scene
+            .drain_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                GameEvent::ActionTriggered { action, at, .. } => Some((action, at)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn enter_and_exit_fire_at_the_area_edges() {
+        for (on, expected) in [
+            (TriggerOn::Enter, Coordinates::new(1, 0)),
+            (TriggerOn::Exit, Coordinates::new(2, 0)),
+        ] {
+            let mut scene = Scene::new("test".into(), hall(Trigger::on(on)), None);
+            let hero = scene.load_pawn(TextureId(0));
+            for _ in 0..4 {
+                scene.step_to(hero, Direction::Right).unwrap();
+            }
+            assert_eq!(fired(&mut scene), vec![(ActionId(1), expected)]);
+        }
+    }
+
+    #[test]
+    fn walks_fire_like_steps() {
+        let mut scene = Scene::new("test".into(), hall(Trigger::default()), None);
+        let hero = scene.load_pawn(TextureId(0));
+        scene.walk(hero, Coordinates::new(4, 0)).unwrap();
+        for _ in 0..10 {
+            scene.advance(0.1);
+        }
+        scene.walk(hero, Coordinates::new(0, 0)).unwrap();
+        for _ in 0..10 {
+            scene.advance(0.1);
+        }
+        assert_eq!(
+            fired(&mut scene),
+            vec![
+                (ActionId(1), Coordinates::new(1, 0)),
+                (ActionId(1), Coordinates::new(2, 0))
+            ]
+        );
+    }
+
+    #[test]
+    fn once_actions_fire_a_single_time() {
+        let mut scene = Scene::new("test".into(), hall(Trigger::default()), None);
+        let hero = scene.load_pawn(TextureId(0));
+        let chest = Coordinates::new(5, 0);
+
+        assert_eq!(scene.interact_at(hero, chest), Ok(vec![ActionId(2)]));
+        assert_eq!(scene.interact_at(hero, chest), Ok(vec![]));
+        assert_eq!(scene.interact_at(hero, Coordinates::new(1, 0)), Ok(vec![]));
+        assert_eq!(fired(&mut scene), vec![(ActionId(2), chest)]);
+    }
+
+    #[test]
+    fn once_actions_stay_spent_when_layers_change() {
+        let mut scene = Scene::new("test".into(), hall(Trigger::default()), None);
+        let hero = scene.load_pawn(TextureId(0));
+        let chest = Coordinates::new(5, 0);
+        assert_eq!(scene.interact_at(hero, chest), Ok(vec![ActionId(2)]));
+
+        // A layer put in front shifts the position of every mask of the hall
+        let rug = Layer::new(
+            "decor".into(),
+            vec![Mask::new(
+                "rug".into(),
+                vec![Rect::from_xywh(0, 0, 1, 1)],
+                vec![],
+            )],
+            0,
+        );
+        let mut layers = vec![rug];
+        layers.extend(scene.map.layers().to_vec());
+        scene.map = Map::new("hall".into(), layers, Coordinates::new(0, 0));
+
+        assert_eq!(scene.interact_at(hero, chest), Ok(vec![]));
+    }
+
+    #[test]
+    fn once_actions_of_duplicated_maps_fire_once_each() {
+        let mut map = hall(Trigger::default());
+        map.duplicate_to_the(Direction::Right, None);
+        let mut scene = Scene::new("test".into(), map, None);
+        let hero = scene.load_pawn(TextureId(0));
+
+        for chest in [Coordinates::new(5, 0), Coordinates::new(11, 0)] {
+            assert_eq!(scene.interact_at(hero, chest), Ok(vec![ActionId(2)]));
+            assert_eq!(scene.interact_at(hero, chest), Ok(vec![]));
+        }
+    }
+
+    #[test]
+    fn interact_uses_the_facing_tile() {
+        let mut scene = Scene::new("test".into(), hall(Trigger::default()), None);
+        let hero = scene.load_pawn(TextureId(0));
+        assert_eq!(scene.facing(hero), Some(Direction::Down));
+        assert_eq!(scene.facing_tile(hero), Some(Coordinates::new(0, 1)));
+
+        for _ in 0..4 {
+            scene.step_to(hero, Direction::Right).unwrap();
+        }
+        scene.face(hero, Direction::Left).unwrap();
+        assert_eq!(scene.interact(hero), Ok(vec![]));
+
+        // Pushing against the chest turns the pawn toward it
+        assert!(scene.step_to(hero, Direction::Right).is_err());
+        assert_eq!(scene.facing(hero), Some(Direction::Right));
+        assert_eq!(scene.interact(hero), Ok(vec![ActionId(2)]));
+
+        scene.move_to(hero, Coordinates::new(0, 0)).unwrap();
+        assert_eq!(scene.facing(hero), Some(Direction::Left));
+        assert_eq!(scene.facing_tile(hero), None);
+        assert_eq!(scene.interact(hero), Ok(vec![]));
+    }
+
+    #[test]
+    fn stand_fires_while_standing_still() {
+        let mut scene = Scene::new("test".into(), hall(Trigger::on(TriggerOn::Stand)), None);
+        let hero = scene.load_pawn(TextureId(0));
+        scene.step_to(hero, Direction::Right).unwrap();
+
+        // The step itself takes 0.1s at the default speed
+        scene.advance(0.1);
+        scene.advance(2.5);
+        assert_eq!(fired(&mut scene).len(), 2);
+
+        scene.step_to(hero, Direction::Right).unwrap();
+        scene.advance(0.1);
+        scene.advance(0.6);
+        assert!(fired(&mut scene).is_empty());
+        scene.advance(0.6);
+        assert_eq!(
+            fired(&mut scene),
+            vec![(ActionId(1), Coordinates::new(2, 0))]
+        );
+    }
+}
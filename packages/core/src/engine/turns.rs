@@ -0,0 +1,498 @@
+use std::collections::HashSet;
+
+use crate::{
+    errors::RPGXError,
+    prelude::{GameEvent, PawnId, Scene},
+};
+
+/// Action points of pawns without [`Scene::set_combatant`].
+pub const DEFAULT_ACTION_POINTS: u32 = 4;
+
+/// Turn-based stats of a pawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Combatant {
+    /// Pawns with a higher initiative play first.
+    pub initiative: i32,
+    /// Points given at the start of each turn. Every move spends one.
+    pub action_points: u32,
+}
+
+impl Default for Combatant {
+    fn default() -> Self {
+        Self {
+            initiative: 0,
+            action_points: DEFAULT_ACTION_POINTS,
+        }
+    }
+}
+
+/// Notification of the turn scheduler, emitted as [`GameEvent::Turn`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TurnEvent {
+    Started {
+        pawn: PawnId,
+        round: u32,
+    },
+    Ended {
+        pawn: PawnId,
+        round: u32,
+    },
+    /// The turn was lost because of [`Scene::skip_turn`].
+    Skipped {
+        pawn: PawnId,
+        round: u32,
+    },
+}
+
+/// State of the turn scheduler of a [`Scene`], started with [`Scene::start_turns`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Turns {
+    /// Number of the current round, starting at 1.
+    pub round: u32,
+    order: Vec<PawnId>,
+    current: usize,
+    action_points: u32,
+    skipping: HashSet<PawnId>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    events: Vec<TurnEvent>,
+}
+
+impl Turns {
+    /// Returns the pawns in the order they play each round.
+    pub fn order(&self) -> &[PawnId] {
+        &self.order
+    }
+
+    /// Returns the pawn whose turn it is.
+    pub fn current(&self) -> Option<PawnId> {
+        self.order.get(self.current).copied()
+    }
+
+    /// Returns the action points left in the current turn.
+    pub fn action_points(&self) -> u32 {
+        self.action_points
+    }
+
+    /// Starts the turn of the pawn at `current`, passing over the skipped ones.
+    fn begin(&mut self, points: impl Fn(PawnId) -> u32) {
+        while let Some(pawn) = self.current() {
+            if !self.skipping.remove(&pawn) {
+                self.action_points = points(pawn);
+                self.events.push(TurnEvent::Started {
+                    pawn,
+                    round: self.round,
+                });
+                return;
+            }
+            self.events.push(TurnEvent::Skipped {
+                pawn,
+                round: self.round,
+            });
+            self.next();
+        }
+    }
+
+    fn next(&mut self) {
+        self.current += 1;
+        if self.current >= self.order.len() {
+            self.current = 0;
+            self.round += 1;
+        }
+    }
+
+    fn end(&mut self, points: impl Fn(PawnId) -> u32) {
+        if let Some(pawn) = self.current() {
+            self.events.push(TurnEvent::Ended {
+                pawn,
+                round: self.round,
+            });
+            self.next();
+            self.begin(points);
+        }
+    }
+
+    /// Ends the current turn as skipped rather than ended.
+    fn skip(&mut self, points: impl Fn(PawnId) -> u32) {
+        if let Some(pawn) = self.current() {
+            self.events.push(TurnEvent::Skipped {
+                pawn,
+                round: self.round,
+            });
+            self.next();
+            self.begin(points);
+        }
+    }
+}
+
+impl Scene {
+    /// Sets the initiative and action points of pawn `id`, used from the next
+    /// [`Scene::start_turns`] and the next turn of the pawn respectively.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id.
+    pub fn set_combatant(&mut self, id: PawnId, combatant: Combatant) -> Result<(), RPGXError> {
+        if !self.pawns.contains_key(&id) {
+            return Err(RPGXError::PawnNotFound);
+        }
+        self.combatants.insert(id, combatant);
+        Ok(())
+    }
+
+    /// Returns the turn-based stats of pawn `id`, the default ones unless set.
+    pub fn combatant(&self, id: PawnId) -> Combatant {
+        self.combatants.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Switches the scene to turn-based mode, starting the first round.
+    ///
+    /// Every pawn takes part, by decreasing initiative then loading order. Pawns
+    /// loaded later join at the end of the order. While turns are on, only the pawn
+    /// whose turn it is can move, and each move spends one of its action points.
+    pub fn start_turns(&mut self) {
+        let mut order: Vec<PawnId> = self.pawns.keys().copied().collect();
+        // Stable, so equal initiatives keep the loading order
+        order.sort_by_key(|id| std::cmp::Reverse(self.combatant(*id).initiative));
+
+        let mut turns = Turns {
+            round: 1,
+            order,
+            ..Default::default()
+        };
+        turns.begin(|id| self.combatant(id).action_points);
+        self.turns = Some(turns);
+        self.flush_turn_events();
+    }
+
+    /// Returns the scene to real-time mode.
+    pub fn stop_turns(&mut self) {
+        self.turns = None;
+    }
+
+    /// Returns the pawn whose turn it is, or `None` outside turn-based mode.
+    pub fn current_turn(&self) -> Option<PawnId> {
+        self.turns.as_ref().and_then(Turns::current)
+    }
+
+    /// Returns the action points left in the current turn, or `None` outside
+    /// turn-based mode.
+    pub fn action_points(&self) -> Option<u32> {
+        self.turns.as_ref().map(Turns::action_points)
+    }
+
+    /// Ends the current turn and starts the next one, returning whose turn it is.
+    pub fn end_turn(&mut self) -> Option<PawnId> {
+        let combatants = &self.combatants;
+        let turns = self.turns.as_mut()?;
+        turns.end(|id| {
+            combatants
+                .get(&id)
+                .copied()
+                .unwrap_or_default()
+                .action_points
+        });
+        let current = turns.current();
+        self.flush_turn_events();
+        current
+    }
+
+    /// Makes pawn `id` lose its next turn, or the current one if it is playing.
+    ///
+    /// The lost turn emits [`TurnEvent::Skipped`] instead of [`TurnEvent::Ended`], and
+    /// instead of [`TurnEvent::Started`] as well when it has not started yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns `RPGXError::PawnNotFound` if no pawn has this id.
+    pub fn skip_turn(&mut self, id: PawnId) -> Result<(), RPGXError> {
+        if !self.pawns.contains_key(&id) {
+            return Err(RPGXError::PawnNotFound);
+        }
+        let combatants = &self.combatants;
+        if let Some(turns) = self.turns.as_mut() {
+            if turns.current() == Some(id) {
+                turns.skip(|pawn| {
+                    combatants
+                        .get(&pawn)
+                        .copied()
+                        .unwrap_or_default()
+                        .action_points
+                });
+            } else {
+                turns.skipping.insert(id);
+            }
+        }
+        self.flush_turn_events();
+        Ok(())
+    }
+
+    /// Moves the notifications of the scheduler to the events of the scene.
+    fn flush_turn_events(&mut self) {
+        if let Some(turns) = self.turns.as_mut() {
+            let events: Vec<TurnEvent> = turns.events.drain(..).collect();
+            events
+                .into_iter()
+                .for_each(|event| self.emit(GameEvent::Turn(event)));
+        }
+    }
+
+    /// Checks that pawn `id` may move now.
+    pub(crate) fn check_turn(&self, id: PawnId) -> Result<(), RPGXError> {
+        match &self.turns {
+            Some(turns) if turns.current() != Some(id) => Err(RPGXError::NotYourTurn(id)),
+            Some(turns) if turns.action_points == 0 => Err(RPGXError::NoActionPoints(id)),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn spend_action_point(&mut self) {
+        if let Some(turns) = self.turns.as_mut() {
+            turns.action_points = turns.action_points.saturating_sub(1);
+        }
+    }
+
+    /// Adds a pawn loaded in turn-based mode at the end of the order.
+    pub(crate) fn join_turns(&mut self, id: PawnId) {
+        if let Some(turns) = self.turns.as_mut() {
+            turns.order.push(id);
+            if turns.order.len() == 1 {
+                turns.current = 0;
+                let combatants = &self.combatants;
+                turns.begin(|pawn| {
+                    combatants
+                        .get(&pawn)
+                        .copied()
+                        .unwrap_or_default()
+                        .action_points
+                });
+            }
+        }
+        self.flush_turn_events();
+    }
+
+    /// Takes a removed pawn out of the order, ending its turn if it was playing.
+    pub(crate) fn leave_turns(&mut self, id: PawnId) {
+        let Some(turns) = self.turns.as_mut() else {
+            return;
+        };
+        turns.skipping.remove(&id);
+        let Some(index) = turns.order.iter().position(|pawn| *pawn == id) else {
+            return;
+        };
+
+        let playing = index == turns.current;
+        if playing {
+            turns.events.push(TurnEvent::Ended {
+                pawn: id,
+                round: turns.round,
+            });
+        }
+        turns.order.remove(index);
+        if index < turns.current {
+            turns.current -= 1;
+        }
+        if turns.current >= turns.order.len() {
+            turns.current = 0;
+            turns.round += 1;
+        }
+        if playing {
+            let combatants = &self.combatants;
+            turns.begin(|pawn| {
+                combatants
+                    .get(&pawn)
+                    .copied()
+                    .unwrap_or_default()
+                    .action_points
+            });
+        }
+        self.flush_turn_events();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+
+    use crate::prelude::{
+        Behavior, Coordinates, Direction, Layer, Map, Mask, Pawn, Rect, TextureId,
+    };
+
+    fn arena() -> Map {
+        Map::new(
+            "arena".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 8, 8)],
+                    vec![],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        )
+    }
+
+    fn turn_events(scene: &mut Scene) -> Vec<TurnEvent> {
+        scene
+            .drain_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                GameEvent::Turn(event) => Some(event),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn spawn(scene: &mut Scene, x: u32, initiative: i32) -> PawnId {
+        let id = scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(x, 0),
+            texture_id: TextureId(0),
+        });
+        scene
+            .set_combatant(
+                id,
+                Combatant {
+                    initiative,
+                    action_points: 2,
+                },
+            )
+            .unwrap();
+        id
+    }
+
+    #[test]
+    fn plays_by_initiative_and_spends_action_points() {
+        let mut scene = Scene::new("test".into(), arena(), None);
+        let slow = spawn(&mut scene, 0, 1);
+        let fast = spawn(&mut scene, 4, 5);
+        scene.start_turns();
+
+        assert_eq!(scene.current_turn(), Some(fast));
+        assert_eq!(
+            scene.step_to(slow, Direction::Down),
+            Err(RPGXError::NotYourTurn(slow))
+        );
+
+        scene.step_to(fast, Direction::Down).unwrap();
+        scene.step_to(fast, Direction::Down).unwrap();
+        assert_eq!(scene.action_points(), Some(0));
+        assert_eq!(
+            scene.step_to(fast, Direction::Down),
+            Err(RPGXError::NoActionPoints(fast))
+        );
+
+        assert_eq!(scene.end_turn(), Some(slow));
+        assert_eq!(scene.action_points(), Some(2));
+        assert_eq!(scene.end_turn(), Some(fast));
+        assert_eq!(scene.turns.as_ref().unwrap().round, 2);
+
+        assert_eq!(
+            turn_events(&mut scene),
+            vec![
+                TurnEvent::Started {
+                    pawn: fast,
+                    round: 1
+                },
+                TurnEvent::Ended {
+                    pawn: fast,
+                    round: 1
+                },
+                TurnEvent::Started {
+                    pawn: slow,
+                    round: 1
+                },
+                TurnEvent::Ended {
+                    pawn: slow,
+                    round: 1
+                },
+                TurnEvent::Started {
+                    pawn: fast,
+                    round: 2
+                },
+            ]
+        );
+        assert!(turn_events(&mut scene).is_empty());
+
+        scene.stop_turns();
+        assert!(scene.step_to(slow, Direction::Down).is_ok());
+    }
+
+    #[test]
+    fn skipped_pawns_lose_their_turn() {
+        let mut scene = Scene::new("test".into(), arena(), None);
+        let a = spawn(&mut scene, 0, 0);
+        let b = spawn(&mut scene, 2, 0);
+        let c = spawn(&mut scene, 4, 0);
+        scene.start_turns();
+        scene.skip_turn(b).unwrap();
+
+        assert_eq!(scene.end_turn(), Some(c));
+        scene.skip_turn(c).unwrap();
+        assert_eq!(scene.current_turn(), Some(a));
+        assert_eq!(
+            turn_events(&mut scene),
+            vec![
+                TurnEvent::Started { pawn: a, round: 1 },
+                TurnEvent::Ended { pawn: a, round: 1 },
+                TurnEvent::Skipped { pawn: b, round: 1 },
+                TurnEvent::Started { pawn: c, round: 1 },
+                TurnEvent::Skipped { pawn: c, round: 1 },
+                TurnEvent::Started { pawn: a, round: 2 },
+            ]
+        );
+
+        scene.remove_pawn(a);
+        assert_eq!(scene.current_turn(), Some(b));
+        assert_eq!(scene.turns.as_ref().unwrap().order(), &[b, c]);
+    }
+
+    #[test]
+    fn walks_spend_one_action_point_per_step() {
+        let mut scene = Scene::new("test".into(), arena(), None);
+        let hero = scene.load_pawn_at(Pawn {
+            pointer: Coordinates::new(0, 0),
+            texture_id: TextureId(0),
+        });
+        scene
+            .set_combatant(
+                hero,
+                Combatant {
+                    initiative: 0,
+                    action_points: 3,
+                },
+            )
+            .unwrap();
+        scene.start_turns();
+
+        let target = Coordinates::new(3, 0);
+        assert_eq!(block_on(scene.walk_to(hero, target)), Ok(target));
+        assert_eq!(scene.action_points(), Some(0));
+
+        // Staying put is free, even without action points left
+        assert_eq!(scene.move_to(hero, target), Ok(target));
+    }
+
+    #[test]
+    fn behaviors_end_their_turn_when_done() {
+        let mut scene = Scene::new("test".into(), arena(), None);
+        let hero = spawn(&mut scene, 0, 0);
+        let guard = spawn(&mut scene, 7, 9);
+        scene
+            .set_behavior(guard, Behavior::patrol(vec![Coordinates::new(0, 7)]))
+            .unwrap();
+        scene.start_turns();
+
+        for _ in 0..10 {
+            scene.advance(0.1);
+        }
+        assert_eq!(scene.current_turn(), Some(hero));
+        // Two action points, so two steps towards the waypoint
+        let at = scene.pawn(guard).unwrap().pointer;
+        assert_eq!(7 - at.x + at.y, 2);
+    }
+}
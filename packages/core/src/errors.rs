@@ -1,11 +1,17 @@
-use crate::prelude::{Coordinates, Direction};
+use crate::prelude::{Coordinates, Direction, PawnId};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RPGXError {
     TileNotWalkable(Coordinates),
+    TileOccupied(Coordinates),
     TileNotFound(Coordinates),
     PathNotFround { from: Coordinates, to: Coordinates },
     PawnNotFound,
     WalkFailed { from: Coordinates, to: Coordinates },
     StepFailed(Direction),
+    NotYourTurn(PawnId),
+    NoActionPoints(PawnId),
+    /// A pawn speed was infinite or not a number.
+    InvalidSpeed,
 }
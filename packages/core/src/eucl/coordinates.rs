@@ -4,6 +4,7 @@ use std::ops::{Add, AddAssign, Sub};
 #[doc = include_str!("../../docs/coordinates.md")]
 /// Represents a 2D grid coordinate with `x` and `y` components.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coordinates {
     pub x: u32,
     pub y: u32,
@@ -324,4 +325,13 @@ mod tests {
         let (_, max) = Coordinates::bounding_box(&coords).unwrap();
         assert_eq!(max, Coordinates { x: 5, y: 6 }); // Exclusive upper bounds
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let coord = Coordinates::new(3, 7);
+        let json = serde_json::to_string(&coord).unwrap();
+        assert_eq!(json, r#"{"x":3,"y":7}"#);
+        assert_eq!(serde_json::from_str::<Coordinates>(&json).unwrap(), coord);
+    }
 }
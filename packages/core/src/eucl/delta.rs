@@ -1,6 +1,7 @@
 #[doc = include_str!("../../docs/delta.md")]
 /// Represents a 2D movement or directional offset with signed deltas.
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Delta {
     pub dx: i32,
     pub dy: i32,
@@ -161,4 +162,13 @@ mod tests {
         d -= Delta::new(1, 2);
         assert_eq!(d, Delta::new(2, 2));
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let delta = Delta::new(-2, 5);
+        let json = serde_json::to_string(&delta).unwrap();
+        assert_eq!(json, r#"{"dx":-2,"dy":5}"#);
+        assert_eq!(serde_json::from_str::<Delta>(&json).unwrap(), delta);
+    }
 }
@@ -1,14 +1,17 @@
 use crate::prelude::Delta;
 
 #[doc = include_str!("../../docs/direction.md")]
-/// Represents a cardinal direction in a 2D grid.
+/// Represents a cardinal or diagonal direction in a 2D grid.
 ///
 /// Directions correspond to unit moves along the X and Y axes.
 /// - `Up` moves along negative Y
 /// - `Down` moves along positive Y
 /// - `Left` moves along negative X
 /// - `Right` moves along positive X
-#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+///
+/// Diagonal directions combine one vertical and one horizontal unit move.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     /// Upward direction (0, -1)
     Up,
@@ -18,24 +21,78 @@ pub enum Direction {
     Left,
     /// Rightward direction (1, 0)
     Right,
+    /// Up-left diagonal direction (-1, -1)
+    UpLeft,
+    /// Up-right diagonal direction (1, -1)
+    UpRight,
+    /// Down-left diagonal direction (-1, 1)
+    DownLeft,
+    /// Down-right diagonal direction (1, 1)
+    DownRight,
 }
 
 impl Direction {
+    /// The four cardinal directions.
+    pub const CARDINAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// The four diagonal directions.
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
+    /// All eight directions, cardinal first.
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
     /// Attempts to create a `Direction` from a given `Delta`.
     ///
     /// Returns `Some(Direction)` if the delta corresponds exactly to one of
-    /// the four cardinal directions (unit steps), otherwise returns `None`.
+    /// the eight unit steps, otherwise returns `None`.
     pub fn from_delta(delta: &Delta) -> Option<Self> {
-        if delta.dx == 0 && delta.dy == -1 {
-            Some(Direction::Up)
-        } else if delta.dx == 0 && delta.dy == 1 {
-            Some(Direction::Down)
-        } else if delta.dx == -1 && delta.dy == 0 {
-            Some(Direction::Left)
-        } else if delta.dx == 1 && delta.dy == 0 {
-            Some(Direction::Right)
-        } else {
-            None
+        match (delta.dx, delta.dy) {
+            (0, -1) => Some(Direction::Up),
+            (0, 1) => Some(Direction::Down),
+            (-1, 0) => Some(Direction::Left),
+            (1, 0) => Some(Direction::Right),
+            (-1, -1) => Some(Direction::UpLeft),
+            (1, -1) => Some(Direction::UpRight),
+            (-1, 1) => Some(Direction::DownLeft),
+            (1, 1) => Some(Direction::DownRight),
+            _ => None,
+        }
+    }
+
+    /// Returns true for the four diagonal directions.
+    pub fn is_diagonal(&self) -> bool {
+        Self::DIAGONAL.contains(self)
+    }
+
+    /// Splits a diagonal direction into its vertical and horizontal components.
+    ///
+    /// Returns `None` for cardinal directions.
+    pub fn components(&self) -> Option<(Direction, Direction)> {
+        match self {
+            Direction::UpLeft => Some((Direction::Up, Direction::Left)),
+            Direction::UpRight => Some((Direction::Up, Direction::Right)),
+            Direction::DownLeft => Some((Direction::Down, Direction::Left)),
+            Direction::DownRight => Some((Direction::Down, Direction::Right)),
+            _ => None,
         }
     }
 
@@ -49,6 +106,10 @@ impl Direction {
             Direction::Down => Delta { dx: 0, dy: 1 },
             Direction::Left => Delta { dx: -1, dy: 0 },
             Direction::Right => Delta { dx: 1, dy: 0 },
+            Direction::UpLeft => Delta { dx: -1, dy: -1 },
+            Direction::UpRight => Delta { dx: 1, dy: -1 },
+            Direction::DownLeft => Delta { dx: -1, dy: 1 },
+            Direction::DownRight => Delta { dx: 1, dy: 1 },
         }
     }
 }
@@ -78,13 +139,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_from_delta_returns_direction_for_diagonal_steps() {
+        assert_eq!(
+            Direction::from_delta(&Delta { dx: 1, dy: 1 }),
+            Some(Direction::DownRight)
+        );
+        assert_eq!(
+            Direction::from_delta(&Delta { dx: -1, dy: -1 }),
+            Some(Direction::UpLeft)
+        );
+    }
+
     #[test]
     fn test_from_delta_returns_none_for_non_unit_steps() {
         assert_eq!(Direction::from_delta(&Delta { dx: 0, dy: 0 }), None);
-        assert_eq!(Direction::from_delta(&Delta { dx: 1, dy: 1 }), None);
-        assert_eq!(Direction::from_delta(&Delta { dx: -1, dy: -1 }), None);
         assert_eq!(Direction::from_delta(&Delta { dx: 2, dy: 0 }), None);
         assert_eq!(Direction::from_delta(&Delta { dx: 0, dy: 2 }), None);
+        assert_eq!(Direction::from_delta(&Delta { dx: 2, dy: 1 }), None);
+    }
+
+    #[test]
+    fn test_delta_round_trips_for_all_directions() {
+        for direction in Direction::ALL {
+            assert_eq!(
+                Direction::from_delta(&direction.to_delta()),
+                Some(direction)
+            );
+        }
+    }
+
+    #[test]
+    fn test_components_compose_diagonal_delta() {
+        for direction in Direction::DIAGONAL {
+            let (vertical, horizontal) = direction.components().unwrap();
+            assert_eq!(
+                vertical.to_delta() + horizontal.to_delta(),
+                direction.to_delta()
+            );
+        }
+        assert_eq!(Direction::Up.components(), None);
     }
 
     #[test]
@@ -94,4 +188,14 @@ mod tests {
         assert_eq!(Direction::Left.to_delta(), Delta { dx: -1, dy: 0 });
         assert_eq!(Direction::Right.to_delta(), Delta { dx: 1, dy: 0 });
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        assert_eq!(serde_json::to_string(&Direction::Up).unwrap(), r#""Up""#);
+        for direction in Direction::ALL {
+            let json = serde_json::to_string(&direction).unwrap();
+            assert_eq!(serde_json::from_str::<Direction>(&json).unwrap(), direction);
+        }
+    }
 }
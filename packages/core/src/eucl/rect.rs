@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     fmt,
     ops::{Add, Sub},
 };
@@ -18,6 +19,7 @@ pub enum RectError {
 /// Represented by a top-left origin [`Coordinates`] and a [`Shape`] defining its width and height.
 /// All dimensions and coordinates are unsigned and non-negative.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rect {
     /// Top-left corner of the rectangle.
     pub origin: Coordinates,
@@ -81,6 +83,56 @@ impl Rect {
         Ok(Rect { origin, shape })
     }
 
+    /// Covers a set of tiles with non-overlapping rects.
+    ///
+    /// Horizontal runs of tiles are joined first, then runs spanning the same
+    /// columns in consecutive rows are stacked into a single rect.
+    pub fn cover(tiles: impl IntoIterator<Item = Coordinates>) -> Vec<Self> {
+        let mut tiles: Vec<Coordinates> = tiles.into_iter().collect();
+        tiles.sort_by_key(|c| (c.y, c.x));
+        tiles.dedup();
+
+        let mut rects: Vec<Rect> = Vec::new();
+        // Rects that ended on the previous row, keyed by (x, width)
+        let mut open: HashMap<(u32, u32), usize> = HashMap::new();
+        let mut row = Vec::new();
+        let mut y = tiles.first().map_or(0, |c| c.y);
+
+        let mut runs = Vec::new();
+        let mut i = 0;
+        while i < tiles.len() {
+            let start = tiles[i];
+            let mut width = 1;
+            while i + (width as usize) < tiles.len()
+                && tiles[i + width as usize] == Coordinates::new(start.x + width, start.y)
+            {
+                width += 1;
+            }
+            runs.push(Rect::from_xywh(start.x, start.y, width, 1));
+            i += width as usize;
+        }
+
+        for run in runs {
+            if run.origin.y != y {
+                if run.origin.y != y + 1 {
+                    row.clear();
+                }
+                open = row.drain(..).collect();
+                y = run.origin.y;
+            }
+            let key = (run.origin.x, run.shape.width);
+            if let Some(&index) = open.get(&key) {
+                rects[index].shape.height += 1;
+                row.push((key, index));
+            } else {
+                rects.push(run);
+                row.push((key, rects.len() - 1));
+            }
+        }
+
+        rects
+    }
+
     /// Creates a new `Rect` from origin `(x, y)` and dimensions `(width, height)`.
     pub fn from_xywh(x: u32, y: u32, width: u32, height: u32) -> Self {
         Self {
@@ -399,6 +451,20 @@ impl Rect {
         x >= ox && x < ox + w && y >= oy && y < oy + h
     }
 
+    /// Returns the overlapping area between this rectangle and `other`, if any.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let left = self.origin.x.max(other.origin.x);
+        let top = self.origin.y.max(other.origin.y);
+        let right = (self.origin.x + self.shape.width).min(other.origin.x + other.shape.width);
+        let bottom = (self.origin.y + self.shape.height).min(other.origin.y + other.shape.height);
+
+        if left < right && top < bottom {
+            Some(Rect::from_xywh(left, top, right - left, bottom - top))
+        } else {
+            None
+        }
+    }
+
     /// Returns an iterator over all coordinates contained in this rectangle.
     ///
     /// Iteration order is row-major (left to right, top to bottom).
@@ -623,6 +689,34 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    pub fn computes_intersection() {
+        let a = Rect::from_xywh(0, 0, 4, 4);
+        let b = Rect::from_xywh(2, 3, 5, 5);
+
+        assert_eq!(a.intersection(&b), Some(Rect::from_xywh(2, 3, 2, 1)));
+        assert_eq!(b.intersection(&a), Some(Rect::from_xywh(2, 3, 2, 1)));
+        assert_eq!(a.intersection(&Rect::from_xywh(4, 0, 2, 2)), None);
+    }
+
+    #[test]
+    pub fn covers_tiles_with_merged_rects() {
+        let mut tiles: Vec<_> = Rect::from_xywh(1, 1, 3, 2).iter().collect();
+        tiles.push(Coordinates::new(0, 3));
+        tiles.push(Coordinates::new(2, 3));
+        tiles.push(Coordinates::new(1, 1));
+
+        assert_eq!(
+            Rect::cover(tiles),
+            vec![
+                Rect::from_xywh(1, 1, 3, 2),
+                Rect::from_xywh(0, 3, 1, 1),
+                Rect::from_xywh(2, 3, 1, 1),
+            ]
+        );
+        assert_eq!(Rect::cover(Vec::new()), Vec::new());
+    }
+
     #[test]
     pub fn translate_produces_offset_rect() {
         let base = Rect::new(
@@ -670,4 +764,16 @@ mod tests {
             assert_eq!(tile.origin.y % 2, 1);
         }
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let rect = Rect::from_xywh(1, 2, 3, 4);
+        let json = serde_json::to_string(&rect).unwrap();
+        assert_eq!(
+            json,
+            r#"{"origin":{"x":1,"y":2},"shape":{"width":3,"height":4}}"#
+        );
+        assert_eq!(serde_json::from_str::<Rect>(&json).unwrap(), rect);
+    }
 }
@@ -5,6 +5,7 @@ use crate::prelude::{Coordinates, Delta};
 #[doc = include_str!("../../docs/shape.md")]
 /// Represents a rectangular area by its width and height.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Shape {
     pub width: u32,
     pub height: u32,
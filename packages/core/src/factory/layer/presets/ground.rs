@@ -1,7 +1,7 @@
-use crate::prelude::{Coordinates, Effect, Layer, Mask, Rect, Shape};
+use crate::prelude::{Coordinates, Effect, Layer, Mask, Rect, Shape, TextureId};
 
 /// Generates a street `Layer` that surrounds the area defined by `shape`.
-pub fn ground_layer(shape: Shape, texture_id: u32) -> Layer {
+pub fn ground_layer(shape: Shape, texture_id: TextureId) -> Layer {
     let mask = Mask::new(
         "street_border".to_string(),
         // Selector::Block(Rect::new(Coordinates { x: 0, y: 0 }, shape)),
@@ -1,6 +1,6 @@
 use crate::prelude::*;
 
-pub fn street_around(map: &mut Map, texture_id: u32) {
+pub fn street_around(map: &mut Map, texture_id: TextureId) {
     let inner_shape = map.get_shape();
     let outer_width = inner_shape.width + 2;
     let outer_height = inner_shape.height + 2;
@@ -40,7 +40,7 @@ pub fn street_around(map: &mut Map, texture_id: u32) {
 }
 
 /// Generates a street `Layer` that surrounds the area defined by `shape`.
-pub fn street_layer_around(shape: Shape, texture_id: u32) -> Layer {
+pub fn street_layer_around(shape: Shape, texture_id: TextureId) -> Layer {
     // Outer shape is 2 tiles bigger in both width and height
     let outer_shape = Shape {
         width: shape.width + 2,
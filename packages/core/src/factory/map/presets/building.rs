@@ -1,8 +1,8 @@
 use std::vec;
 
-use crate::prelude::{Coordinates, Effect, Layer, Map, Mask, Rect, Shape};
+use crate::prelude::{ActionId, Coordinates, Effect, Layer, Map, Mask, Rect, Shape, TextureId};
 
-pub fn building_2x3(shape: Shape, texture_id: u32, action_id: u32) -> Map {
+pub fn building_2x3(shape: Shape, texture_id: TextureId, action_id: ActionId) -> Map {
     let building_layer = Layer::new(
         "buildings".to_string(),
         vec![Mask::new(
@@ -63,7 +63,7 @@ pub fn building_2x3(shape: Shape, texture_id: u32, action_id: u32) -> Map {
     )
 }
 
-pub fn building_1x1(shape: Shape, texture_id: u32, action_id: u32) -> Map {
+pub fn building_1x1(shape: Shape, texture_id: TextureId, action_id: ActionId) -> Map {
     let building_layer = Layer::new(
         "buildings".to_string(),
         vec![Mask::new(
@@ -112,7 +112,7 @@ pub fn building_1x1(shape: Shape, texture_id: u32, action_id: u32) -> Map {
     )
 }
 
-pub fn building_3x2(shape: Shape, texture_id: u32, action_id: u32) -> Map {
+pub fn building_3x2(shape: Shape, texture_id: TextureId, action_id: ActionId) -> Map {
     let building_layer = Layer::new(
         "buildings".to_string(),
         vec![Mask::new(
@@ -1,4 +1,5 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(feature = "serde", doc = include_str!("../docs/serde.md"))]
 
 pub mod engine;
 pub mod errors;
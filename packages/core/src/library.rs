@@ -1,52 +1,424 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, hash::Hash};
 
+use indexmap::IndexMap;
+
+/// Id of a texture in a [`Textures`] registry, used by [`Effect::Texture`] and
+/// [`Pawn::texture_id`].
+///
+/// [`Effect::Texture`]: crate::prelude::Effect::Texture
+/// [`Pawn::texture_id`]: crate::prelude::Pawn::texture_id
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct TextureId(pub u32);
+
+/// Id of an action in an [`Actions`] registry, used by [`Effect::Action`] and
+/// [`Effect::Trigger`].
+///
+/// [`Effect::Action`]: crate::prelude::Effect::Action
+/// [`Effect::Trigger`]: crate::prelude::Effect::Trigger
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct ActionId(pub u32);
+
+/// Id of a render callback in a [`Renders`] registry, used by [`Effect::Render`].
+///
+/// [`Effect::Render`]: crate::prelude::Effect::Render
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct RenderId(pub u32);
+
+/// Typed id of the entries of a [`Library`].
+pub trait LibraryId: Copy + Eq + Hash {
+    fn from_u32(id: u32) -> Self;
+}
+
+impl LibraryId for TextureId {
+    fn from_u32(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl LibraryId for ActionId {
+    fn from_u32(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl LibraryId for RenderId {
+    fn from_u32(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+/// Untyped ids, for registries shared by every kind of resource such as those of
+/// script drivers.
+impl LibraryId for u32 {
+    fn from_u32(id: u32) -> Self {
+        id
+    }
+}
+
+/// Registry of textures, usually their URLs.
+pub type Textures<T> = Library<TextureId, T>;
+/// Registry of actions, usually callbacks run by the driver.
+pub type Actions<A> = Library<ActionId, A>;
+/// Registry of render callbacks.
+pub type Renders<R> = Library<RenderId, R>;
+
+/// Separates the namespace of a key from its name, as in `dungeon/floor_1`.
+pub const NAMESPACE_SEPARATOR: char = '/';
+
+/// Returns `key` within `namespace`, such as `dungeon/floor_1`.
+pub fn namespaced(namespace: &str, key: &str) -> String {
+    format!("{namespace}{NAMESPACE_SEPARATOR}{key}")
+}
+
+#[doc = include_str!("../docs/library.md")]
+/// Resources keyed by name, each given a typed id of kind `I` on insertion.
 #[derive(Clone, Debug)]
-pub struct Library<V> {
-    data: HashMap<String, V>,
-    key_to_id: HashMap<String, u32>,
-    id_to_key: HashMap<u32, String>,
+pub struct Library<I, V> {
+    /// Keys and values by id, in id order.
+    entries: IndexMap<I, (String, V)>,
+    key_to_id: HashMap<String, I>,
+    /// Id given to the next new key. Ids of removed keys are never reused.
     next_id: u32,
 }
 
-impl<V> Library<V> {
+impl<I: LibraryId, V> Library<I, V> {
     pub fn new() -> Self {
         Self {
-            data: HashMap::new(),
+            entries: IndexMap::new(),
             key_to_id: HashMap::new(),
-            id_to_key: HashMap::new(),
             next_id: 1,
         }
     }
 
-    /// Insert a key-value pair and assign a unique ID if the key is new
-    pub fn insert(&mut self, key: impl Into<String>, value: V) {
+    /// Insert a key-value pair and assign a unique ID if the key is new.
+    ///
+    /// Returns the ID of the key.
+    pub fn insert(&mut self, key: impl Into<String>, value: V) -> I {
         let key = key.into();
-        if !self.key_to_id.contains_key(&key) {
-            let id = self.next_id;
-            self.key_to_id.insert(key.clone(), id);
-            self.id_to_key.insert(id, key.clone());
-            self.next_id += 1;
+        if let Some(id) = self.key_to_id.get(&key).copied() {
+            self.entries.insert(id, (key, value));
+            return id;
         }
-        self.data.insert(key, value);
+        let id = I::from_u32(self.next_id);
+        self.next_id += 1;
+        self.key_to_id.insert(key.clone(), id);
+        self.entries.insert(id, (key, value));
+        id
+    }
+
+    /// Removes the entry with this ID and returns its value.
+    ///
+    /// The ID is retired: inserting the key again gives it a new ID.
+    pub fn remove(&mut self, id: I) -> Option<V> {
+        let (key, value) = self.entries.shift_remove(&id)?;
+        self.key_to_id.remove(&key);
+        Some(value)
     }
 
     /// Get value by key
     pub fn get_by_key(&self, key: impl Into<String>) -> Option<&V> {
-        self.data.get(&key.into())
+        self.get_id(key).and_then(|id| self.get_by_id(id))
     }
 
     /// Get value by ID
-    pub fn get_by_id(&self, id: u32) -> Option<&V> {
-        self.id_to_key.get(&id).and_then(|key| self.data.get(key))
+    pub fn get_by_id(&self, id: I) -> Option<&V> {
+        self.entries.get(&id).map(|(_, value)| value)
     }
 
     /// Get ID by key
-    pub fn get_id(&self, key: impl Into<String>) -> Option<u32> {
+    pub fn get_id(&self, key: impl Into<String>) -> Option<I> {
         self.key_to_id.get(&key.into()).copied()
     }
 
     /// Get key by ID
-    pub fn get_key(&self, id: u32) -> Option<impl Into<String>> {
-        self.id_to_key.get(&id).map(|s| s.as_str())
+    pub fn get_key(&self, id: I) -> Option<&str> {
+        self.entries.get(&id).map(|(key, _)| key.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries as `(id, key, value)`, in ID order.
+    pub fn iter(&self) -> impl Iterator<Item = (I, &str, &V)> {
+        self.entries
+            .iter()
+            .map(|(id, (key, value))| (*id, key.as_str(), value))
+    }
+
+    /// Iterates over the entries whose key is within `namespace`, in ID order.
+    pub fn iter_namespace(&self, namespace: &str) -> impl Iterator<Item = (I, &str, &V)> {
+        self.iter().filter(move |(_, key, _)| {
+            key.strip_prefix(namespace)
+                .is_some_and(|rest| rest.starts_with(NAMESPACE_SEPARATOR))
+        })
+    }
+
+    /// Moves the entries of `other` into this library, within `namespace` if given.
+    ///
+    /// Entries of `other` get new IDs, except those whose key is already in this
+    /// library: they replace its value and keep its ID. Returns the new ID of every
+    /// entry of `other`, to apply to maps built against it with
+    /// [`Map::remap`](crate::prelude::Map::remap).
+    pub fn merge(&mut self, other: Library<I, V>, namespace: Option<&str>) -> Remap<I> {
+        let mut remap = Remap::default();
+        for (id, (key, value)) in other.entries {
+            let key = match namespace {
+                Some(namespace) => namespaced(namespace, &key),
+                None => key,
+            };
+            remap.ids.insert(id, self.insert(key, value));
+        }
+        remap
+    }
+}
+
+impl<I: LibraryId, V> Default for Library<I, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A library is stored as its entries in id order, as `[id, key, value]` triples,
+/// and the id given to the next new key.
+#[cfg(feature = "serde")]
+impl<I, V> serde::Serialize for Library<I, V>
+where
+    I: LibraryId + serde::Serialize,
+    V: serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let entries: Vec<(I, &str, &V)> = self.iter().collect();
+        let mut library = serializer.serialize_struct("Library", 2)?;
+        library.serialize_field("entries", &entries)?;
+        library.serialize_field("next_id", &self.next_id)?;
+        library.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, I, V> serde::Deserialize<'de> for Library<I, V>
+where
+    I: LibraryId + serde::Deserialize<'de>,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(rename = "Library")]
+        struct Stored<I, V> {
+            entries: Vec<(I, String, V)>,
+            next_id: u32,
+        }
+
+        let stored = Stored::<I, V>::deserialize(deserializer)?;
+        let mut library = Self {
+            entries: IndexMap::new(),
+            key_to_id: HashMap::new(),
+            next_id: stored.next_id,
+        };
+        for (id, key, value) in stored.entries {
+            library.key_to_id.insert(key.clone(), id);
+            library.entries.insert(id, (key, value));
+        }
+        Ok(library)
+    }
+}
+
+/// New IDs of the entries of a merged [`Library`], see [`Library::merge`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Remap<I: LibraryId> {
+    pub ids: HashMap<I, I>,
+}
+
+impl<I: LibraryId> Remap<I> {
+    /// Returns the new ID of `id`, or `id` itself if it wasn't remapped.
+    pub fn apply(&self, id: I) -> I {
+        self.ids.get(&id).copied().unwrap_or(id)
+    }
+}
+
+impl<I: LibraryId> Default for Remap<I> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::new(),
+        }
+    }
+}
+
+/// The [`Remap`] of each registry of merged [`Resources`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResourceRemap {
+    pub textures: Remap<TextureId>,
+    pub actions: Remap<ActionId>,
+    pub renders: Remap<RenderId>,
+}
+
+/// The texture, action and render registries of a game.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Resources<T, A, R> {
+    pub textures: Textures<T>,
+    pub actions: Actions<A>,
+    pub renders: Renders<R>,
+}
+
+impl<T, A, R> Resources<T, A, R> {
+    pub fn new() -> Self {
+        Self {
+            textures: Library::new(),
+            actions: Library::new(),
+            renders: Library::new(),
+        }
+    }
+
+    /// Merges every registry of `other`, see [`Library::merge`].
+    pub fn merge(&mut self, other: Resources<T, A, R>, namespace: Option<&str>) -> ResourceRemap {
+        ResourceRemap {
+            textures: self.textures.merge(other.textures, namespace),
+            actions: self.actions.merge(other.actions, namespace),
+            renders: self.renders.merge(other.renders, namespace),
+        }
+    }
+}
+
+impl<T, A, R> Default for Resources<T, A, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registries_give_ids_per_kind() {
+        let mut resources: Resources<&str, (), ()> = Resources::new();
+        let floor = resources.textures.insert("floor", "floor.webp");
+        let open = resources.actions.insert("open", ());
+
+        assert_eq!(floor, TextureId(1));
+        assert_eq!(open, ActionId(1));
+        assert_eq!(resources.textures.insert("floor", "floor_2.webp"), floor);
+        assert_eq!(resources.textures.get_by_id(floor), Some(&"floor_2.webp"));
+        assert_eq!(resources.textures.get_key(floor), Some("floor"));
+        assert_eq!(resources.renders.get_id("floor"), None);
+    }
+
+    #[test]
+    fn removed_ids_are_retired() {
+        let mut textures: Textures<&str> = Library::new();
+        let grass = textures.insert("overworld/grass", "grass.webp");
+        let floor = textures.insert("dungeon/floor_1", "floor.webp");
+        textures.insert("dungeon/wall", "wall.webp");
+
+        assert_eq!(textures.remove(grass), Some("grass.webp"));
+        assert_eq!(textures.remove(grass), None);
+        assert_eq!(
+            textures.insert("overworld/grass", "grass.webp"),
+            TextureId(4)
+        );
+
+        let dungeon: Vec<_> = textures.iter_namespace("dungeon").map(|e| e.1).collect();
+        assert_eq!(dungeon, ["dungeon/floor_1", "dungeon/wall"]);
+        assert_eq!(
+            textures.iter().next(),
+            Some((floor, "dungeon/floor_1", &"floor.webp"))
+        );
+        assert_eq!(textures.len(), 3);
+    }
+
+    #[test]
+    fn merges_remap_maps_and_scenes() {
+        use crate::prelude::{Coordinates, Effect, Layer, Map, Mask, Rect, Scene};
+
+        let mut base: Resources<&str, (), ()> = Resources::new();
+        base.textures.insert("hero", "hero.webp");
+        base.textures.insert("dungeon/floor", "floor.webp");
+
+        let mut level: Resources<&str, (), ()> = Resources::new();
+        let floor = level.textures.insert("floor", "floor_2.webp");
+        let lava = level.textures.insert("lava", "lava.webp");
+        let burn = level.actions.insert("burn", ());
+
+        let map = Map::new(
+            "level".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![Mask::new(
+                    "lava".into(),
+                    vec![Rect::from_xywh(0, 0, 2, 1)],
+                    vec![Effect::Texture(lava), Effect::Action(burn)],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        );
+        let mut scene = Scene::new("level".into(), map, None);
+        let pawn = scene.load_pawn(floor);
+
+        let remap = base.merge(level, Some("dungeon"));
+        assert_eq!(remap.textures.apply(floor), TextureId(2));
+        assert_eq!(base.textures.get_by_id(TextureId(2)), Some(&"floor_2.webp"));
+
+        scene.remap(&remap);
+        let lava = base.textures.get_id("dungeon/lava").unwrap();
+        let burn = base.actions.get_id("dungeon/burn").unwrap();
+        assert_eq!(
+            scene.map.get_textures_at(&Coordinates::new(1, 0)),
+            vec![lava]
+        );
+        assert_eq!(
+            scene.map.get_actions_at(&Coordinates::new(1, 0)),
+            vec![burn]
+        );
+        assert_eq!(scene.pawn(pawn).unwrap().texture_id, TextureId(2));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let mut textures: Textures<String> = Library::new();
+        let grass = textures.insert("grass", "grass.webp".to_string());
+        let wall = textures.insert("dungeon/wall", "wall.webp".to_string());
+        textures.insert("dirt", "dirt.webp".to_string());
+        textures.remove(grass);
+
+        let json = serde_json::to_string(&textures).unwrap();
+        assert_eq!(
+            json,
+            r#"{"entries":[[2,"dungeon/wall","wall.webp"],[3,"dirt","dirt.webp"]],"next_id":4}"#
+        );
+
+        let mut loaded: Textures<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get_id("dungeon/wall"), Some(wall));
+        assert_eq!(loaded.get_by_key("dirt"), Some(&"dirt.webp".to_string()));
+        assert_eq!(loaded.get_id("grass"), None);
+        // Retired ids stay retired
+        assert_eq!(
+            loaded.insert("grass", "grass.webp".to_string()),
+            TextureId(4)
+        );
     }
 }
@@ -1,31 +1,125 @@
-use crate::prelude::{Delta, Rect};
+use crate::prelude::{ActionId, Delta, Rect, RenderId, ResourceRemap, TextureId};
 
 #[doc = include_str!("../../docs/effect.md")]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Effect {
     /// Represents a tile with no special effects.
     None,
 
-    /// Represents a tile with an action effect.
-    Action(u32),
+    /// Represents a tile with an action effect, fired when a pawn enters its area.
+    ///
+    /// Same as `Effect::Trigger(id, Trigger::default())`.
+    Action(ActionId),
+
+    /// Represents a tile with an action effect fired as described by the [`Trigger`].
+    Trigger(ActionId, Trigger),
 
     /// Represents a tile with a texture effect.
-    Texture(u32),
+    Texture(TextureId),
 
     /// Represents a tile with a render callback effect.
-    Render(u32),
+    Render(RenderId),
 
     /// Represents a tile with a blocking area effect.
     Block(Rect),
-    // Allows a tile to have different texture effects stacked on top of each other.
-    // By default only the texture at the top of the stack is rendered.
-    // Opaque,
+
+    /// Represents a tile with a movement cost used by routing (default is `1`).
+    Cost(u32),
+
+    /// Represents a tile with a sight-blocking area effect.
+    Opaque(Rect),
+}
+
+/// When the action of an [`Effect::Trigger`] fires.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TriggerOn {
+    /// When a pawn steps into the area of the mask from outside of it.
+    #[default]
+    Enter,
+    /// When a pawn steps out of the area of the mask.
+    Exit,
+    /// When a pawn interacts with a tile of the mask.
+    Interact,
+    /// Periodically while a pawn stands still on a tile of the mask.
+    Stand,
+}
+
+/// Timing of an action effect, evaluated by the [`Scene`](crate::prelude::Scene).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trigger {
+    pub on: TriggerOn,
+    /// Whether the action fires only the first time, whichever pawn triggers it.
+    pub once: bool,
+}
+
+impl Trigger {
+    /// Fires every time `on` happens.
+    pub fn on(on: TriggerOn) -> Self {
+        Self { on, once: false }
+    }
+
+    /// Fires the first time `on` happens only.
+    pub fn once(on: TriggerOn) -> Self {
+        Self { on, once: true }
+    }
 }
 
 impl Effect {
     pub fn offset(&mut self, delta: Delta) {
-        if let Effect::Block(rect) = self {
+        if let Effect::Block(rect) | Effect::Opaque(rect) = self {
             rect.offset(delta);
         }
     }
+
+    /// Replaces the action, texture or render id of this effect with its new id in
+    /// `remap`.
+    pub fn remap(&mut self, remap: &ResourceRemap) {
+        match self {
+            Effect::Action(id) | Effect::Trigger(id, _) => *id = remap.actions.apply(*id),
+            Effect::Texture(id) => *id = remap.textures.apply(*id),
+            Effect::Render(id) => *id = remap.renders.apply(*id),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_round_trip() {
+        let effects = [
+            (Effect::None, r#""None""#),
+            (Effect::Action(ActionId(1)), r#"{"Action":1}"#),
+            (
+                Effect::Trigger(ActionId(2), Trigger::once(TriggerOn::Interact)),
+                r#"{"Trigger":[2,{"on":"Interact","once":true}]}"#,
+            ),
+            (
+                Effect::Trigger(ActionId(3), Trigger::on(TriggerOn::Stand)),
+                r#"{"Trigger":[3,{"on":"Stand","once":false}]}"#,
+            ),
+            (Effect::Texture(TextureId(4)), r#"{"Texture":4}"#),
+            (Effect::Render(RenderId(5)), r#"{"Render":5}"#),
+            (
+                Effect::Block(Rect::from_xywh(0, 1, 2, 3)),
+                r#"{"Block":{"origin":{"x":0,"y":1},"shape":{"width":2,"height":3}}}"#,
+            ),
+            (Effect::Cost(6), r#"{"Cost":6}"#),
+            (
+                Effect::Opaque(Rect::from_xywh(4, 5, 1, 1)),
+                r#"{"Opaque":{"origin":{"x":4,"y":5},"shape":{"width":1,"height":1}}}"#,
+            ),
+        ];
+
+        for (effect, expected) in effects {
+            let json = serde_json::to_string(&effect).unwrap();
+            assert_eq!(json, expected);
+            assert_eq!(serde_json::from_str::<Effect>(&json).unwrap(), effect);
+        }
+    }
 }
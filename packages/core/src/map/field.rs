@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+
+use crate::prelude::{Coordinates, Map, PathOptions};
+
+use super::routing::{Label, search, trace, unscale};
+
+/// Cost of reaching the nearest of a set of goals from every walkable tile, also known as a Dijkstra map.
+///
+/// A field is computed once and shared by any number of agents heading to the same goals:
+/// each of them follows [`DistanceField::next_step`] instead of running its own search.
+/// Call [`DistanceField::refresh`] after the map changes.
+#[derive(Clone, Debug, Default)]
+pub struct DistanceField {
+    goals: Vec<Coordinates>,
+    options: PathOptions,
+    /// Route of every tile to its goal, pointing back towards the goal.
+    tiles: HashMap<Coordinates, Label<Coordinates>>,
+}
+
+impl DistanceField {
+    /// Computes the field of `goals` over `map`.
+    ///
+    /// Tiles are walkable under the same rules as [`Map::find_path_with`]: the field
+    /// honours movement, bounds, forbidden tiles, the action policy and the
+    /// `max_cost`, `max_length` and `max_nodes` budgets of `options`.
+    pub fn new(map: &Map, goals: &[Coordinates], options: &PathOptions) -> Self {
+        let mut field = Self {
+            goals: goals.to_vec(),
+            options: options.clone(),
+            tiles: HashMap::new(),
+        };
+        field.refresh(map);
+        field
+    }
+
+    /// Returns the goals the field leads to.
+    pub fn goals(&self) -> &[Coordinates] {
+        &self.goals
+    }
+
+    /// Returns the options the field was computed with.
+    pub fn options(&self) -> &PathOptions {
+        &self.options
+    }
+
+    /// Recomputes the field over `map`, keeping the same goals and options.
+    pub fn refresh(&mut self, map: &Map) {
+        let options = &self.options;
+        let goals: Vec<Coordinates> = self
+            .goals
+            .iter()
+            .filter(|goal| {
+                map.is_passable(goal)
+                    && options.in_bounds(goal)
+                    && !options.forbidden.contains(goal)
+            })
+            .copied()
+            .collect();
+
+        // The search runs backwards, from the goals to every tile an agent may start on
+        self.tiles = search(
+            goals.iter().copied(),
+            options,
+            |current, _| {
+                // Agents never step onto avoided tiles, so nothing can route through them
+                if !goals.contains(&current) && options.avoids(map, &current) {
+                    return Vec::new();
+                }
+                map.tile_edges(current, options, true)
+            },
+            |_| 0,
+            |_| false,
+        )
+        .labels;
+    }
+
+    /// Returns the cost of walking from `from` to the nearest goal, or `None` if no
+    /// goal can be reached from there.
+    ///
+    /// Costs are in the units of [`PathOptions::max_cost`], the same as
+    /// [`Reachable::cost_to`](crate::prelude::Reachable::cost_to): the [`Map::path_cost`]
+    /// of the path under cardinal movement, with diagonal steps rounded up otherwise.
+    pub fn cost_at(&self, from: &Coordinates) -> Option<u32> {
+        self.tiles.get(from).map(|tile| unscale(tile.cost))
+    }
+
+    /// Returns the cost from `from` to the nearest goal in the scaled units used by the search.
+    pub(crate) fn scaled_cost_at(&self, from: &Coordinates) -> Option<i32> {
+        self.tiles.get(from).map(|tile| tile.cost)
+    }
+
+    /// Returns the tile to step onto from `from` to get closer to the nearest goal.
+    ///
+    /// `None` if `from` is a goal or no goal can be reached from there.
+    pub fn next_step(&self, from: &Coordinates) -> Option<Coordinates> {
+        self.tiles.get(from).and_then(|tile| tile.previous)
+    }
+
+    /// Returns the full path from `from` to the nearest goal, both included.
+    pub fn path_from(&self, from: &Coordinates) -> Option<Vec<Coordinates>> {
+        let mut path = trace(&self.tiles, *from)?;
+        path.reverse();
+        Some(path)
+    }
+
+    /// Iterates every tile of the field along with its cost.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, u32)> + '_ {
+        self.tiles
+            .keys()
+            .filter_map(|coord| Some((*coord, self.cost_at(coord)?)))
+    }
+}
+
+impl Map {
+    /// Computes a [`DistanceField`] leading to the nearest of `goals` with default options.
+    pub fn distance_field(&self, goals: &[Coordinates]) -> DistanceField {
+        DistanceField::new(self, goals, &PathOptions::default())
+    }
+
+    /// Computes a [`DistanceField`] leading to the nearest of `goals` under `options`.
+    pub fn distance_field_with(
+        &self,
+        goals: &[Coordinates],
+        options: &PathOptions,
+    ) -> DistanceField {
+        DistanceField::new(self, goals, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::routing::tests::{get_block_map, get_open_map};
+    use crate::prelude::{Effect, Layer, Mask, Movement, Rect};
+
+    #[test]
+    fn measures_distance_to_nearest_goal() {
+        let map = get_open_map();
+        let field = map.distance_field(&[Coordinates::new(0, 0), Coordinates::new(9, 0)]);
+
+        assert_eq!(field.cost_at(&Coordinates::new(0, 0)), Some(0));
+        assert_eq!(field.cost_at(&Coordinates::new(2, 1)), Some(3));
+        assert_eq!(field.cost_at(&Coordinates::new(7, 1)), Some(3));
+        assert_eq!(field.cost_at(&Coordinates::new(4, 9)), Some(13));
+        assert_eq!(field.cost_at(&Coordinates::new(10, 0)), None);
+    }
+
+    #[test]
+    fn follows_gradient_to_goal() {
+        let map = get_block_map();
+        let goal = Coordinates::new(3, 1);
+        let field = map.distance_field(&[goal]);
+        let start = Coordinates::new(0, 1);
+
+        let path = field.path_from(&start).unwrap();
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(field.next_step(&goal), None);
+
+        let expected = map.find_path(&start, &goal).unwrap();
+        assert_eq!(path.len(), expected.len());
+        assert_eq!(field.cost_at(&start), Some(map.path_cost(&expected)));
+    }
+
+    #[test]
+    fn skips_blocked_tiles() {
+        let map = get_block_map();
+        let field = map.distance_field(&[Coordinates::new(0, 0)]);
+
+        assert_eq!(field.cost_at(&Coordinates::new(1, 1)), None);
+        assert_eq!(field.path_from(&Coordinates::new(2, 2)), None);
+        assert!(field.iter().all(|(coord, _)| !map.is_blocking_at(&coord)));
+    }
+
+    #[test]
+    fn prices_entering_tiles() {
+        let mut map = get_open_map();
+        map.push_layer(Layer::new(
+            "terrain".into(),
+            vec![Mask::new(
+                "mud".into(),
+                vec![Rect::from_xywh(1, 0, 1, 1)],
+                vec![Effect::Cost(5)],
+            )],
+            2,
+        ));
+        let field = map.distance_field(&[Coordinates::new(1, 0)]);
+
+        // Leaving the mud is cheap, entering it is not
+        assert_eq!(field.cost_at(&Coordinates::new(2, 0)), Some(5));
+        assert_eq!(field.cost_at(&Coordinates::new(3, 0)), Some(6));
+    }
+
+    #[test]
+    fn refreshes_after_map_changes() {
+        let mut map = get_open_map();
+        let mut field = map.distance_field_with(
+            &[Coordinates::new(0, 0)],
+            &PathOptions {
+                movement: Movement::Octile,
+                ..Default::default()
+            },
+        );
+        // Three diagonal steps cost 4.2, rounded up
+        assert_eq!(field.cost_at(&Coordinates::new(3, 3)), Some(5));
+
+        map.push_layer(Layer::new(
+            "walls".into(),
+            vec![Mask::new(
+                "wall".into(),
+                vec![],
+                vec![Effect::Block(Rect::from_xywh(0, 2, 10, 1))],
+            )],
+            2,
+        ));
+        field.refresh(&map);
+
+        assert_eq!(field.cost_at(&Coordinates::new(3, 3)), None);
+        assert_eq!(field.cost_at(&Coordinates::new(1, 1)), Some(2));
+    }
+}
@@ -0,0 +1,603 @@
+use std::{fmt, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::{
+    Coordinates, Effect, Layer, Library, LibraryId, Map, Mask, Rect, Resources, Trigger,
+};
+
+/// Version written by [`Map::to_string`], the only one [`Map::parse`] reads so far.
+pub const MAP_FILE_VERSION: u32 = 1;
+
+/// Text formats of map files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapFormat {
+    #[cfg(feature = "json")]
+    Json,
+    #[cfg(feature = "ron")]
+    Ron,
+}
+
+impl MapFormat {
+    /// Returns the format of a file from its extension, `json` or `ron`.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            #[cfg(feature = "json")]
+            "json" => Some(MapFormat::Json),
+            #[cfg(feature = "ron")]
+            "ron" => Some(MapFormat::Ron),
+            _ => None,
+        }
+    }
+}
+
+/// The registry a key of a map file is looked up in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceKind {
+    Texture,
+    Action,
+    Render,
+}
+
+/// Errors raised while reading or writing map files.
+///
+/// `at` locates the faulty item, as in `layers[0] "ground" > masks[1] "door" > effects[2]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MapFileError {
+    /// The file could not be read or written.
+    Io { path: String, message: String },
+    /// The extension of the file is not one of an enabled [`MapFormat`].
+    UnknownFormat(String),
+    /// The source is not valid JSON or RON, or doesn't follow the schema.
+    Parse(String),
+    /// The map file could not be written as JSON or RON.
+    Serialize(String),
+    /// The file was written in a version this crate doesn't read.
+    UnsupportedVersion(u32),
+    /// A key of the file is not in the library.
+    UnknownKey {
+        kind: ResourceKind,
+        key: String,
+        at: String,
+    },
+    /// An id of the map is not in the library, so it has no key to be written with.
+    UnknownId {
+        kind: ResourceKind,
+        id: u32,
+        at: String,
+    },
+    /// A rect of the file, or of the map being written, is not `[x, y, width, height]`
+    /// with a non-zero size.
+    MalformedRect { rect: Vec<u32>, at: String },
+}
+
+impl fmt::Display for MapFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapFileError::Io { path, message } => write!(f, "{path}: {message}"),
+            MapFileError::UnknownFormat(path) => {
+                write!(f, "{path}: unknown map format, expected .json or .ron")
+            }
+            MapFileError::Parse(message) => write!(f, "{message}"),
+            MapFileError::Serialize(message) => write!(f, "could not write map file: {message}"),
+            MapFileError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported map file version {version}, expected {MAP_FILE_VERSION}"
+            ),
+            MapFileError::UnknownKey { kind, key, at } => {
+                write!(f, "{at}: unknown {kind:?} key \"{key}\"")
+            }
+            MapFileError::UnknownId { kind, id, at } => {
+                write!(f, "{at}: unknown {kind:?} id {id}")
+            }
+            MapFileError::MalformedRect { rect, at } => write!(
+                f,
+                "{at}: malformed rect {rect:?}, expected [x, y, width, height] with a non-zero size"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MapFileError {}
+
+#[cfg_attr(feature = "json", doc = include_str!("../../docs/map_file.md"))]
+/// A map as stored on disk, with resources referenced by key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MapFile {
+    pub version: u32,
+    pub name: String,
+    pub spawn: Coordinates,
+    pub layers: Vec<LayerFile>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerFile {
+    pub name: String,
+    pub z: u32,
+    pub masks: Vec<MaskFile>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaskFile {
+    pub name: String,
+    /// Areas of the mask as `[x, y, width, height]`.
+    pub tiles: Vec<Vec<u32>>,
+    #[serde(default)]
+    pub effects: Vec<EffectFile>,
+}
+
+/// An [`Effect`] with its texture, action or render given by library key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EffectFile {
+    None,
+    Action(String),
+    Trigger(String, Trigger),
+    Texture(String),
+    Render(String),
+    Block(Vec<u32>),
+    Cost(u32),
+    Opaque(Vec<u32>),
+}
+
+fn rect_from_file(rect: &[u32], at: impl FnOnce() -> String) -> Result<Rect, MapFileError> {
+    match *rect {
+        [x, y, width, height]
+            if width > 0
+                && height > 0
+                && x.checked_add(width).is_some()
+                && y.checked_add(height).is_some() =>
+        {
+            Ok(Rect::from_xywh(x, y, width, height))
+        }
+        _ => Err(MapFileError::MalformedRect {
+            rect: rect.to_vec(),
+            at: at(),
+        }),
+    }
+}
+
+/// Checks `rect` against the rules of [`rect_from_file`], so that every written file
+/// can be read back.
+fn rect_to_file(rect: &Rect, at: impl FnOnce() -> String) -> Result<Vec<u32>, MapFileError> {
+    let rect = vec![
+        rect.origin.x,
+        rect.origin.y,
+        rect.shape.width,
+        rect.shape.height,
+    ];
+    rect_from_file(&rect, at)?;
+    Ok(rect)
+}
+
+fn resolve_key<I: LibraryId, V>(
+    library: &Library<I, V>,
+    kind: ResourceKind,
+    key: &str,
+    at: impl FnOnce() -> String,
+) -> Result<I, MapFileError> {
+    library.get_id(key).ok_or_else(|| MapFileError::UnknownKey {
+        kind,
+        key: key.to_string(),
+        at: at(),
+    })
+}
+
+fn resolve_id<I: LibraryId, V>(
+    library: &Library<I, V>,
+    kind: ResourceKind,
+    id: I,
+    raw: u32,
+    at: impl FnOnce() -> String,
+) -> Result<String, MapFileError> {
+    library
+        .get_key(id)
+        .map(str::to_string)
+        .ok_or_else(|| MapFileError::UnknownId {
+            kind,
+            id: raw,
+            at: at(),
+        })
+}
+
+fn location(layer: (usize, &str), mask: (usize, &str), item: &str, index: usize) -> String {
+    format!(
+        "layers[{}] \"{}\" > masks[{}] \"{}\" > {item}[{index}]",
+        layer.0, layer.1, mask.0, mask.1
+    )
+}
+
+impl MapFile {
+    /// Builds the map, looking the keys of its effects up in `resources`.
+    pub fn to_map<T, A, R>(&self, resources: &Resources<T, A, R>) -> Result<Map, MapFileError> {
+        if self.version != MAP_FILE_VERSION {
+            return Err(MapFileError::UnsupportedVersion(self.version));
+        }
+
+        let mut layers = Vec::with_capacity(self.layers.len());
+        for (l, layer) in self.layers.iter().enumerate() {
+            let mut masks = Vec::with_capacity(layer.masks.len());
+            for (m, mask) in layer.masks.iter().enumerate() {
+                let at = |item: &str, index: usize| {
+                    location((l, &layer.name), (m, &mask.name), item, index)
+                };
+
+                let tiles = mask
+                    .tiles
+                    .iter()
+                    .enumerate()
+                    .map(|(i, rect)| rect_from_file(rect, || at("tiles", i)))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut effects = Vec::with_capacity(mask.effects.len());
+                for (i, effect) in mask.effects.iter().enumerate() {
+                    let at = || at("effects", i);
+                    effects.push(match effect {
+                        EffectFile::None => Effect::None,
+                        EffectFile::Action(key) => Effect::Action(resolve_key(
+                            &resources.actions,
+                            ResourceKind::Action,
+                            key,
+                            at,
+                        )?),
+                        EffectFile::Trigger(key, trigger) => Effect::Trigger(
+                            resolve_key(&resources.actions, ResourceKind::Action, key, at)?,
+                            *trigger,
+                        ),
+                        EffectFile::Texture(key) => Effect::Texture(resolve_key(
+                            &resources.textures,
+                            ResourceKind::Texture,
+                            key,
+                            at,
+                        )?),
+                        EffectFile::Render(key) => Effect::Render(resolve_key(
+                            &resources.renders,
+                            ResourceKind::Render,
+                            key,
+                            at,
+                        )?),
+                        EffectFile::Block(rect) => Effect::Block(rect_from_file(rect, at)?),
+                        EffectFile::Cost(cost) => Effect::Cost(*cost),
+                        EffectFile::Opaque(rect) => Effect::Opaque(rect_from_file(rect, at)?),
+                    });
+                }
+
+                masks.push(Mask::new(mask.name.clone(), tiles, effects));
+            }
+            layers.push(Layer::new(layer.name.clone(), masks, layer.z));
+        }
+
+        Ok(Map::new(self.name.clone(), layers, self.spawn))
+    }
+
+    /// Describes `map`, looking the keys of its resource ids up in `resources`.
+    ///
+    /// Rects with a zero width or height are rejected as [`MapFileError::MalformedRect`],
+    /// as [`MapFile::to_map`] would not read them back.
+    pub fn from_map<T, A, R>(
+        map: &Map,
+        resources: &Resources<T, A, R>,
+    ) -> Result<Self, MapFileError> {
+        let mut layers = Vec::with_capacity(map.layers().len());
+        for (l, layer) in map.layers().iter().enumerate() {
+            let mut masks = Vec::with_capacity(layer.masks.len());
+            for (m, mask) in layer.masks.iter().enumerate() {
+                let at = |item: &str, index: usize| {
+                    location((l, &layer.name), (m, &mask.name), item, index)
+                };
+
+                let tiles = mask
+                    .tiles
+                    .iter()
+                    .enumerate()
+                    .map(|(i, rect)| rect_to_file(rect, || at("tiles", i)))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let mut effects = Vec::with_capacity(mask.effects.len());
+                for (i, effect) in mask.effects.iter().enumerate() {
+                    let at = || at("effects", i);
+                    effects.push(match *effect {
+                        Effect::None => EffectFile::None,
+                        Effect::Action(id) => EffectFile::Action(resolve_id(
+                            &resources.actions,
+                            ResourceKind::Action,
+                            id,
+                            id.0,
+                            at,
+                        )?),
+                        Effect::Trigger(id, trigger) => EffectFile::Trigger(
+                            resolve_id(&resources.actions, ResourceKind::Action, id, id.0, at)?,
+                            trigger,
+                        ),
+                        Effect::Texture(id) => EffectFile::Texture(resolve_id(
+                            &resources.textures,
+                            ResourceKind::Texture,
+                            id,
+                            id.0,
+                            at,
+                        )?),
+                        Effect::Render(id) => EffectFile::Render(resolve_id(
+                            &resources.renders,
+                            ResourceKind::Render,
+                            id,
+                            id.0,
+                            at,
+                        )?),
+                        Effect::Block(rect) => EffectFile::Block(rect_to_file(&rect, at)?),
+                        Effect::Cost(cost) => EffectFile::Cost(cost),
+                        Effect::Opaque(rect) => EffectFile::Opaque(rect_to_file(&rect, at)?),
+                    });
+                }
+
+                masks.push(MaskFile {
+                    name: mask.name.clone(),
+                    tiles,
+                    effects,
+                });
+            }
+            layers.push(LayerFile {
+                name: layer.name.clone(),
+                z: layer.z,
+                masks,
+            });
+        }
+
+        Ok(MapFile {
+            version: MAP_FILE_VERSION,
+            name: map.name.clone(),
+            spawn: map.spawn,
+            layers,
+        })
+    }
+
+    /// Parses a map file written in `format`.
+    #[cfg_attr(not(any(feature = "json", feature = "ron")), allow(unused_variables))]
+    pub fn parse(source: &str, format: MapFormat) -> Result<Self, MapFileError> {
+        match format {
+            #[cfg(feature = "json")]
+            MapFormat::Json => {
+                serde_json::from_str(source).map_err(|e| MapFileError::Parse(e.to_string()))
+            }
+            #[cfg(feature = "ron")]
+            MapFormat::Ron => ron::from_str(source).map_err(|e| MapFileError::Parse(e.to_string())),
+        }
+    }
+
+    /// Writes the map file in `format`, pretty-printed.
+    pub fn to_string(&self, format: MapFormat) -> Result<String, MapFileError> {
+        match format {
+            #[cfg(feature = "json")]
+            MapFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| MapFileError::Serialize(e.to_string())),
+            #[cfg(feature = "ron")]
+            MapFormat::Ron => ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+                .map_err(|e| MapFileError::Serialize(e.to_string())),
+        }
+    }
+}
+
+impl Map {
+    /// Loads a map file, in the format given by its extension, resolving its keys
+    /// through `resources`.
+    pub fn from_file<T, A, R>(
+        path: impl AsRef<Path>,
+        resources: &Resources<T, A, R>,
+    ) -> Result<Self, MapFileError> {
+        let path = path.as_ref();
+        let format = MapFormat::from_path(path)
+            .ok_or_else(|| MapFileError::UnknownFormat(path.display().to_string()))?;
+        let source = std::fs::read_to_string(path).map_err(|e| MapFileError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::parse(&source, format, resources)
+    }
+
+    /// Parses a map file written in `format`, resolving its keys through `resources`.
+    pub fn parse<T, A, R>(
+        source: &str,
+        format: MapFormat,
+        resources: &Resources<T, A, R>,
+    ) -> Result<Self, MapFileError> {
+        MapFile::parse(source, format)?.to_map(resources)
+    }
+
+    /// Writes the map as a map file in `format`, with its resources given by their
+    /// key in `resources`.
+    pub fn to_string<T, A, R>(
+        &self,
+        format: MapFormat,
+        resources: &Resources<T, A, R>,
+    ) -> Result<String, MapFileError> {
+        MapFile::from_map(self, resources)?.to_string(format)
+    }
+
+    /// Writes the map to a file, in the format given by its extension.
+    pub fn to_file<T, A, R>(
+        &self,
+        path: impl AsRef<Path>,
+        resources: &Resources<T, A, R>,
+    ) -> Result<(), MapFileError> {
+        let path = path.as_ref();
+        let format = MapFormat::from_path(path)
+            .ok_or_else(|| MapFileError::UnknownFormat(path.display().to_string()))?;
+        std::fs::write(path, self.to_string(format, resources)?).map_err(|e| MapFileError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resources() -> Resources<&'static str, (), ()> {
+        let mut resources = Resources::new();
+        resources.textures.insert("floor", "floor.webp");
+        resources.textures.insert("table", "table.webp");
+        resources.actions.insert("open_chest", ());
+        resources
+    }
+
+    #[cfg(any(feature = "json", feature = "ron"))]
+    fn fixture(name: &str) -> String {
+        format!("{}/fixtures/maps/{name}", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    #[cfg(any(feature = "json", feature = "ron"))]
+    fn assert_room(map: &Map) {
+        use crate::prelude::{ActionId, TextureId};
+
+        assert_eq!(map.spawn, Coordinates::new(1, 1));
+        assert!(map.is_blocking_at(&Coordinates::new(4, 1)));
+        assert_eq!(
+            map.get_textures_at(&Coordinates::new(3, 1)),
+            vec![TextureId(1), TextureId(2)]
+        );
+        assert_eq!(
+            map.tile_at(&Coordinates::new(5, 3)).unwrap().triggers[0].action,
+            ActionId(1)
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn loads_json_fixture() {
+        assert_room(&Map::from_file(fixture("room.json"), &resources()).unwrap());
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn loads_ron_fixture() {
+        assert_room(&Map::from_file(fixture("room.ron"), &resources()).unwrap());
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn writes_ron_maps_back() {
+        let resources = resources();
+        let map = Map::from_file(fixture("room.ron"), &resources).unwrap();
+        let written = map.to_string(MapFormat::Ron, &resources).unwrap();
+
+        let loaded = Map::parse(&written, MapFormat::Ron, &resources).unwrap();
+        assert_room(&loaded);
+        assert_eq!(
+            MapFile::from_map(&loaded, &resources).unwrap(),
+            MapFile::from_map(&map, &resources).unwrap()
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn writes_maps_back_by_key() {
+        use crate::prelude::{ActionId, TextureId, TriggerOn};
+
+        let resources = resources();
+        let map = Map::from_file(fixture("room.json"), &resources).unwrap();
+        let written = map.to_string(MapFormat::Json, &resources).unwrap();
+        assert!(written.contains(r#""Texture": "table""#));
+
+        let loaded = Map::parse(&written, MapFormat::Json, &resources).unwrap();
+        assert_eq!(
+            MapFile::from_map(&loaded, &resources).unwrap(),
+            MapFile::from_map(&map, &resources).unwrap()
+        );
+        let chest = &loaded.layers()[1].masks[1];
+        assert_eq!(
+            chest.effects,
+            vec![Effect::Trigger(
+                ActionId(1),
+                Trigger::once(TriggerOn::Interact)
+            )]
+        );
+
+        let mut unknown = map.clone();
+        unknown.layers_mut()[0].masks[0].effects[0] = Effect::Texture(TextureId(9));
+        assert_eq!(
+            unknown.to_string(MapFormat::Json, &resources).unwrap_err(),
+            MapFileError::UnknownId {
+                kind: ResourceKind::Texture,
+                id: 9,
+                at: r#"layers[0] "ground" > masks[0] "floor" > effects[0]"#.into(),
+            }
+        );
+    }
+
+    #[test]
+    fn reports_unknown_keys_and_malformed_rects() {
+        let resources = resources();
+        let mut file = MapFile {
+            version: MAP_FILE_VERSION,
+            name: "room".into(),
+            spawn: Coordinates::new(0, 0),
+            layers: vec![LayerFile {
+                name: "ground".into(),
+                z: 1,
+                masks: vec![MaskFile {
+                    name: "floor".into(),
+                    tiles: vec![vec![0, 0, 2, 2], vec![2, 0, 0, 1]],
+                    effects: vec![EffectFile::Texture("lava".into())],
+                }],
+            }],
+        };
+
+        let error = file.to_map(&resources).err().unwrap();
+        assert_eq!(
+            error,
+            MapFileError::MalformedRect {
+                rect: vec![2, 0, 0, 1],
+                at: r#"layers[0] "ground" > masks[0] "floor" > tiles[1]"#.into(),
+            }
+        );
+
+        file.layers[0].masks[0].tiles.pop();
+        assert_eq!(
+            file.to_map(&resources).err().unwrap().to_string(),
+            r#"layers[0] "ground" > masks[0] "floor" > effects[0]: unknown Texture key "lava""#
+        );
+
+        file.version = 2;
+        assert_eq!(
+            file.to_map(&resources).err().unwrap(),
+            MapFileError::UnsupportedVersion(2)
+        );
+    }
+
+    #[test]
+    fn rejects_rects_it_could_not_read_back() {
+        let resources = resources();
+        let mut map = Map::new(
+            "room".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 2, 2)],
+                    vec![Effect::Block(Rect::from_xywh(1, 0, 0, 2))],
+                )],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        );
+
+        assert_eq!(
+            MapFile::from_map(&map, &resources).unwrap_err(),
+            MapFileError::MalformedRect {
+                rect: vec![1, 0, 0, 2],
+                at: r#"layers[0] "ground" > masks[0] "floor" > effects[0]"#.into(),
+            }
+        );
+
+        map.layers_mut()[0].masks[0].effects.clear();
+        map.layers_mut()[0].masks[0]
+            .tiles
+            .push(Rect::from_xywh(3, 3, 1, 0));
+        assert_eq!(
+            MapFile::from_map(&map, &resources).unwrap_err(),
+            MapFileError::MalformedRect {
+                rect: vec![3, 3, 1, 0],
+                at: r#"layers[0] "ground" > masks[0] "floor" > tiles[1]"#.into(),
+            }
+        );
+    }
+}
@@ -0,0 +1,531 @@
+use std::collections::HashMap;
+
+use crate::{
+    errors::RPGXError,
+    prelude::{Coordinates, Delta, Direction, Map, Movement, PathOptions, Rect, Shape},
+};
+
+use super::routing::{Edge, search, trace};
+
+/// Entrances shorter than this get a single portal in their middle, longer ones get
+/// one portal at each end.
+const LONG_ENTRANCE: u32 = 6;
+
+/// Precomputed abstraction of a [`Map`] for hierarchical pathfinding (HPA*).
+///
+/// The map is split into square clusters. Wherever two neighbouring clusters share
+/// walkable border tiles, portals are placed on both sides, and the cheapest route
+/// between every pair of portals inside a cluster is cached. A query then searches
+/// the small graph of portals and only refines the chosen route inside the clusters
+/// it crosses.
+///
+/// Paths are always valid, and a path is found whenever [`Map::find_path`] finds one.
+/// Since routes are forced through portals they may be longer than the optimal ones:
+/// on maps where every tile costs `1`, a path costs at most twice the cluster size
+/// more than the optimal path for each cluster border the optimal path crosses.
+#[derive(Clone, Debug)]
+pub struct Hierarchy {
+    cluster_size: u32,
+    movement: Movement,
+    shape: Shape,
+    /// Portal pairs `(inside, outside)` of every cluster, keyed by cluster.
+    entrances: HashMap<usize, Vec<(Coordinates, Coordinates)>>,
+    /// Cached routes between portals of the same cluster, keyed by cluster then portal.
+    routes: HashMap<usize, HashMap<Coordinates, Vec<(Coordinates, i32)>>>,
+}
+
+impl Hierarchy {
+    /// Builds the hierarchy of `map` with square clusters of `cluster_size` tiles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cluster_size` is zero.
+    pub fn new(map: &Map, cluster_size: u32, movement: Movement) -> Self {
+        assert!(cluster_size > 0, "cluster size must be positive");
+        let mut hierarchy = Self {
+            cluster_size,
+            movement,
+            shape: Shape::default(),
+            entrances: HashMap::new(),
+            routes: HashMap::new(),
+        };
+        hierarchy.rebuild(map);
+        hierarchy
+    }
+
+    /// Returns the side of a cluster, in tiles.
+    pub fn cluster_size(&self) -> u32 {
+        self.cluster_size
+    }
+
+    /// Returns the area of the cluster containing `coord`.
+    pub fn cluster_rect(&self, coord: &Coordinates) -> Rect {
+        let size = self.cluster_size;
+        let origin = Coordinates::new(coord.x / size * size, coord.y / size * size);
+        Rect::new(origin, Shape::from_square(size))
+    }
+
+    /// Returns every portal tile of the hierarchy.
+    pub fn portals(&self) -> impl Iterator<Item = Coordinates> + '_ {
+        self.entrances
+            .values()
+            .flat_map(|entrances| entrances.iter().map(|(inside, _)| *inside))
+    }
+
+    /// Rebuilds the whole hierarchy.
+    pub fn rebuild(&mut self, map: &Map) {
+        self.shape = map.index().shape();
+        self.entrances.clear();
+        self.routes.clear();
+        let all: Vec<usize> = (0..(self.columns() * self.rows()) as usize).collect();
+        self.rebuild_clusters(map, &all);
+    }
+
+    /// Rebuilds only the clusters overlapping `area`, e.g. the rects of a layer or mask
+    /// that was added, removed or edited, and the cached routes of their neighbours.
+    ///
+    /// Falls back to a full rebuild if the map bounds changed.
+    pub fn rebuild_area(&mut self, map: &Map, area: &Rect) {
+        if map.index().shape() != self.shape {
+            return self.rebuild(map);
+        }
+        let Some(area) = Rect::from_shape(self.shape).intersection(area) else {
+            return;
+        };
+
+        // Borders and routes only depend on the tiles of the clusters they belong to;
+        // the neighbours whose portals move are rebuilt along with them.
+        let size = self.cluster_size;
+        let columns = self.columns();
+        let mut touched = Vec::new();
+        for cy in area.origin.y / size..=(area.origin.y + area.shape.height - 1) / size {
+            for cx in area.origin.x / size..=(area.origin.x + area.shape.width - 1) / size {
+                touched.push((cy * columns + cx) as usize);
+            }
+        }
+        self.rebuild_clusters(map, &touched);
+    }
+
+    /// Finds a path from `start` to `goal` through the hierarchy.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RPGXError::PathNotFround`] if the goal cannot be reached.
+    pub fn find_path(
+        &self,
+        map: &Map,
+        start: &Coordinates,
+        goal: &Coordinates,
+    ) -> Result<Vec<Coordinates>, RPGXError> {
+        let not_found = RPGXError::PathNotFround {
+            from: *start,
+            to: *goal,
+        };
+        if !map.is_passable(start) || !map.is_passable(goal) {
+            return Err(not_found);
+        }
+
+        let start_cluster = self.cluster_of(start);
+        let goal_cluster = self.cluster_of(goal);
+        let from_start = map.reachable_from_with(start, &self.cluster_options(start));
+        let to_goal = map.distance_field_with(&[*goal], &self.cluster_options(goal));
+
+        // A* over the portal graph, with the start and goal linked to their cluster portals
+        let edges = |node: Coordinates, _: &_| -> Vec<Edge<Coordinates>> {
+            let mut edges = Vec::new();
+            let cluster = self.cluster_of(&node);
+            if node == *start {
+                edges.extend(
+                    self.cluster_portals(start_cluster)
+                        .filter_map(|portal| Some((portal, from_start.scaled_cost_to(&portal)?))),
+                );
+            }
+            if let Some(routes) = self.routes.get(&cluster).and_then(|r| r.get(&node)) {
+                edges.extend(routes.iter().copied());
+            }
+            if let Some(entrances) = self.entrances.get(&cluster) {
+                edges.extend(entrances.iter().filter(|(inside, _)| *inside == node).map(
+                    |(inside, outside)| {
+                        (*outside, map.step_cost(direction(inside, outside), outside))
+                    },
+                ));
+            }
+            if cluster == goal_cluster
+                && let Some(cost) = to_goal.scaled_cost_at(&node)
+            {
+                edges.push((*goal, cost));
+            }
+            edges
+                .into_iter()
+                .map(|(to, cost)| Edge {
+                    to,
+                    cost,
+                    direction: None,
+                })
+                .collect()
+        };
+
+        let search = search(
+            [*start],
+            &PathOptions::default(),
+            edges,
+            |node| Map::estimate(self.movement, node, *goal),
+            |node| node == *goal,
+        );
+        let abstract_path = search
+            .goal
+            .and_then(|reached| trace(&search.labels, reached))
+            .ok_or(not_found)?;
+
+        // Refine every hop of the abstract path into tiles
+        let mut path = vec![*start];
+        for hop in abstract_path.windows(2) {
+            let (from, to) = (hop[0], hop[1]);
+            let segment = if self.cluster_of(&from) != self.cluster_of(&to) {
+                vec![from, to]
+            } else if from == *start {
+                from_start.path_to(&to).ok_or(not_found)?
+            } else if to == *goal {
+                to_goal.path_from(&from).ok_or(not_found)?
+            } else {
+                map.find_path_with(&from, &to, &self.cluster_options(&from))?
+            };
+            path.extend(segment.into_iter().skip(1));
+        }
+        Ok(path)
+    }
+
+    fn columns(&self) -> u32 {
+        self.shape.width.div_ceil(self.cluster_size)
+    }
+
+    fn rows(&self) -> u32 {
+        self.shape.height.div_ceil(self.cluster_size)
+    }
+
+    fn cluster_of(&self, coord: &Coordinates) -> usize {
+        let size = self.cluster_size;
+        ((coord.y / size) * self.columns() + coord.x / size) as usize
+    }
+
+    fn cluster_origin(&self, cluster: usize) -> Coordinates {
+        let columns = self.columns() as usize;
+        Coordinates::new(
+            (cluster % columns) as u32 * self.cluster_size,
+            (cluster / columns) as u32 * self.cluster_size,
+        )
+    }
+
+    fn cluster_portals(&self, cluster: usize) -> impl Iterator<Item = Coordinates> + '_ {
+        self.entrances
+            .get(&cluster)
+            .into_iter()
+            .flat_map(|entrances| entrances.iter().map(|(inside, _)| *inside))
+    }
+
+    /// Search options confining a search to the cluster containing `coord`.
+    fn cluster_options(&self, coord: &Coordinates) -> PathOptions {
+        PathOptions {
+            movement: self.movement,
+            bounds: Some(self.cluster_rect(coord)),
+            ..Default::default()
+        }
+    }
+
+    /// Recomputes the portals of `clusters` and the cached routes of every cluster whose
+    /// portals may have changed as a result.
+    fn rebuild_clusters(&mut self, map: &Map, clusters: &[usize]) {
+        let mut dirty: Vec<usize> = clusters.to_vec();
+
+        for &cluster in clusters {
+            let origin = self.cluster_origin(cluster);
+            for direction in Direction::CARDINAL {
+                let Some(neighbor_origin) = self.neighbor_origin(origin, direction) else {
+                    continue;
+                };
+                let neighbor = self.cluster_of(&neighbor_origin);
+                // Every border is scanned from the cluster on its left or top side
+                match direction {
+                    Direction::Right | Direction::Down => {
+                        let pairs = self.border_entrances(map, origin, direction);
+                        self.replace_border(cluster, neighbor, &pairs);
+                    }
+                    Direction::Left => {
+                        let pairs = self.border_entrances(map, neighbor_origin, Direction::Right);
+                        self.replace_border(neighbor, cluster, &pairs);
+                    }
+                    _ => {
+                        let pairs = self.border_entrances(map, neighbor_origin, Direction::Down);
+                        self.replace_border(neighbor, cluster, &pairs);
+                    }
+                }
+                dirty.push(neighbor);
+            }
+        }
+
+        dirty.sort_unstable();
+        dirty.dedup();
+        for cluster in dirty {
+            self.rebuild_routes(map, cluster);
+        }
+    }
+
+    /// Returns the origin of the cluster next to the one at `origin`, if any.
+    fn neighbor_origin(&self, origin: Coordinates, direction: Direction) -> Option<Coordinates> {
+        let size = self.cluster_size as i32;
+        let delta = direction.to_delta();
+        let neighbor = (origin
+            + Delta {
+                dx: delta.dx * size,
+                dy: delta.dy * size,
+            })?;
+        self.shape.in_bounds(neighbor).then_some(neighbor)
+    }
+
+    /// Replaces the portals on the border between `owner` and `neighbor` with `pairs`,
+    /// given as `(tile in owner, tile in neighbor)`.
+    fn replace_border(
+        &mut self,
+        owner: usize,
+        neighbor: usize,
+        pairs: &[(Coordinates, Coordinates)],
+    ) {
+        let kept = |this: &Self, cluster: usize, other: usize| -> Vec<(Coordinates, Coordinates)> {
+            this.entrances
+                .get(&cluster)
+                .into_iter()
+                .flatten()
+                .filter(|(_, outside)| this.cluster_of(outside) != other)
+                .copied()
+                .collect()
+        };
+        let mut owner_entrances = kept(self, owner, neighbor);
+        let mut neighbor_entrances = kept(self, neighbor, owner);
+
+        owner_entrances.extend(pairs.iter().copied());
+        neighbor_entrances.extend(pairs.iter().map(|(inside, outside)| (*outside, *inside)));
+        self.entrances.insert(owner, owner_entrances);
+        self.entrances.insert(neighbor, neighbor_entrances);
+    }
+
+    /// Finds the portal pairs on the right or bottom border of the cluster at `origin`,
+    /// which must have a neighbour on that side.
+    fn border_entrances(
+        &self,
+        map: &Map,
+        origin: Coordinates,
+        direction: Direction,
+    ) -> Vec<(Coordinates, Coordinates)> {
+        let size = self.cluster_size;
+        let edge: Vec<Coordinates> = match direction {
+            Direction::Right => (0..size.min(self.shape.height - origin.y))
+                .map(|dy| Coordinates::new(origin.x + size - 1, origin.y + dy))
+                .collect(),
+            _ => (0..size.min(self.shape.width - origin.x))
+                .map(|dx| Coordinates::new(origin.x + dx, origin.y + size - 1))
+                .collect(),
+        };
+        let delta = direction.to_delta();
+        let open: Vec<bool> = edge
+            .iter()
+            .map(|inside| {
+                (*inside + delta)
+                    .is_some_and(|outside| map.is_passable(inside) && map.is_passable(&outside))
+            })
+            .collect();
+
+        let mut pairs = Vec::new();
+        let mut i = 0;
+        while i < edge.len() {
+            if !open[i] {
+                i += 1;
+                continue;
+            }
+            let run_start = i;
+            while i < edge.len() && open[i] {
+                i += 1;
+            }
+            let run = &edge[run_start..i];
+            let picks = if run.len() as u32 >= LONG_ENTRANCE {
+                vec![run[0], run[run.len() - 1]]
+            } else {
+                vec![run[run.len() / 2]]
+            };
+            pairs.extend(
+                picks
+                    .into_iter()
+                    .filter_map(|inside| Some((inside, (inside + delta)?))),
+            );
+        }
+        pairs
+    }
+
+    /// Caches the cheapest in-cluster route between every pair of portals of `cluster`.
+    fn rebuild_routes(&mut self, map: &Map, cluster: usize) {
+        let mut portals: Vec<Coordinates> = self.cluster_portals(cluster).collect();
+        portals.sort_by_key(|portal| (portal.y, portal.x));
+        portals.dedup();
+        let mut routes: HashMap<Coordinates, Vec<(Coordinates, i32)>> = HashMap::new();
+        for portal in &portals {
+            let reachable = map.reachable_from_with(portal, &self.cluster_options(portal));
+            routes.insert(
+                *portal,
+                portals
+                    .iter()
+                    .filter(|other| *other != portal)
+                    .filter_map(|other| Some((*other, reachable.scaled_cost_to(other)?)))
+                    .collect(),
+            );
+        }
+        self.routes.insert(cluster, routes);
+    }
+}
+
+/// Direction of a single step from `from` to the adjacent tile `to`.
+fn direction(from: &Coordinates, to: &Coordinates) -> Direction {
+    if to.x > from.x {
+        Direction::Right
+    } else if to.x < from.x {
+        Direction::Left
+    } else if to.y > from.y {
+        Direction::Down
+    } else {
+        Direction::Up
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Effect, Layer, Mask};
+    use crate::traits::Grid;
+
+    /// 30x30 map crossed by two walls with gaps, plus a pillar.
+    fn maze() -> Map {
+        Map::new(
+            "maze".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![
+                    Mask::new("floor".into(), vec![Rect::from_xywh(0, 0, 30, 30)], vec![]),
+                    Mask::new(
+                        "walls".into(),
+                        vec![],
+                        vec![
+                            Effect::Block(Rect::from_xywh(10, 0, 1, 12)),
+                            Effect::Block(Rect::from_xywh(10, 14, 1, 16)),
+                            Effect::Block(Rect::from_xywh(0, 20, 4, 1)),
+                            Effect::Block(Rect::from_xywh(6, 20, 19, 1)),
+                            Effect::Block(Rect::from_xywh(27, 20, 3, 1)),
+                            Effect::Block(Rect::from_xywh(22, 3, 3, 9)),
+                        ],
+                    ),
+                ],
+                1,
+            )],
+            Coordinates::new(0, 0),
+        )
+    }
+
+    fn assert_valid(map: &Map, path: &[Coordinates], start: Coordinates, goal: Coordinates) {
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        for pair in path.windows(2) {
+            assert_eq!(
+                pair[0].x.abs_diff(pair[1].x) + pair[0].y.abs_diff(pair[1].y),
+                1
+            );
+            assert!(map.contains(&pair[1]) && !map.is_blocking_at(&pair[1]));
+        }
+    }
+
+    #[test]
+    fn finds_near_optimal_paths() {
+        let map = maze();
+        let hierarchy = Hierarchy::new(&map, 8, Movement::Cardinal);
+        let queries = [
+            (Coordinates::new(0, 0), Coordinates::new(29, 29)),
+            (Coordinates::new(2, 25), Coordinates::new(28, 1)),
+            (Coordinates::new(15, 15), Coordinates::new(23, 2)),
+            (Coordinates::new(1, 1), Coordinates::new(3, 5)),
+        ];
+
+        for (start, goal) in queries {
+            let path = hierarchy.find_path(&map, &start, &goal).unwrap();
+            let optimal = map.find_path(&start, &goal).unwrap();
+            assert_valid(&map, &path, start, goal);
+
+            let crossings = optimal
+                .windows(2)
+                .filter(|pair| hierarchy.cluster_of(&pair[0]) != hierarchy.cluster_of(&pair[1]))
+                .count() as u32;
+            assert!(
+                map.path_cost(&path)
+                    <= map.path_cost(&optimal) + 2 * hierarchy.cluster_size() * crossings
+            );
+        }
+    }
+
+    #[test]
+    fn reports_unreachable_goals() {
+        let mut map = maze();
+        map.layers_mut()[0].masks[1]
+            .effects
+            .push(Effect::Block(Rect::from_xywh(10, 12, 1, 2)));
+        let hierarchy = Hierarchy::new(&map, 8, Movement::Cardinal);
+
+        let start = Coordinates::new(0, 0);
+        let goal = Coordinates::new(29, 0);
+        assert_eq!(map.find_path(&start, &goal), None);
+        assert_eq!(
+            hierarchy.find_path(&map, &start, &goal),
+            Err(RPGXError::PathNotFround {
+                from: start,
+                to: goal,
+            })
+        );
+    }
+
+    #[test]
+    fn rebuilds_only_changed_area() {
+        let mut map = maze();
+        let mut hierarchy = Hierarchy::new(&map, 8, Movement::Cardinal);
+
+        // Mark every cluster to see which ones get their routes rebuilt
+        let marker = Coordinates::new(u32::MAX, u32::MAX);
+        for routes in hierarchy.routes.values_mut() {
+            routes.insert(marker, Vec::new());
+        }
+
+        // Knock down the bottom of the vertical wall, inside cluster 13
+        let opening = Rect::from_xywh(10, 24, 1, 6);
+        map.layers_mut()[0].masks[1].effects[1] = Effect::Block(Rect::from_xywh(10, 14, 1, 10));
+        hierarchy.rebuild_area(&map, &opening);
+
+        let mut rebuilt: Vec<usize> = hierarchy
+            .routes
+            .iter()
+            .filter(|(_, routes)| !routes.contains_key(&marker))
+            .map(|(cluster, _)| *cluster)
+            .collect();
+        rebuilt.sort_unstable();
+        assert_eq!(rebuilt, vec![9, 12, 13, 14]);
+        for routes in hierarchy.routes.values_mut() {
+            routes.remove(&marker);
+        }
+
+        let fresh = Hierarchy::new(&map, 8, Movement::Cardinal);
+        assert_eq!(hierarchy.routes, fresh.routes);
+        let mut portals: Vec<_> = hierarchy.portals().collect();
+        let mut fresh_portals: Vec<_> = fresh.portals().collect();
+        portals.sort_by_key(|c| (c.y, c.x));
+        fresh_portals.sort_by_key(|c| (c.y, c.x));
+        assert_eq!(portals, fresh_portals);
+
+        let start = Coordinates::new(0, 29);
+        let goal = Coordinates::new(29, 29);
+        let path = hierarchy.find_path(&map, &start, &goal).unwrap();
+        assert_valid(&map, &path, start, goal);
+        assert!(path.iter().any(|step| opening.contains(step)));
+    }
+}
@@ -0,0 +1,309 @@
+use crate::prelude::{
+    ActionId, Coordinates, Effect, Layer, Rect, RenderId, Shape, TextureId, Trigger,
+};
+
+/// Effects resolved for a single tile of a [`TileIndex`].
+///
+/// Ids are listed in layer order first and mask order second, matching the
+/// order used by [`Layer::get_actions_at`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TileInfo {
+    /// Whether any mask covers this tile.
+    pub present: bool,
+    /// Whether any [`Effect::Block`] area covers this tile.
+    pub blocking: bool,
+    /// Whether any [`Effect::Opaque`] area covers this tile.
+    pub opaque: bool,
+    /// Action ids of every mask covering this tile.
+    pub actions: Vec<ActionId>,
+    /// The same actions with when they fire and the mask they come from.
+    pub triggers: Vec<TileTrigger>,
+    /// Texture ids of every mask covering this tile.
+    pub textures: Vec<TextureId>,
+    /// Render ids of every mask covering this tile.
+    pub renders: Vec<RenderId>,
+    /// Movement cost of entering this tile, set by the last [`Effect::Cost`] covering it.
+    ///
+    /// Never lower than `1`, which keeps the A* heuristic admissible.
+    pub cost: u32,
+}
+
+impl Default for TileInfo {
+    fn default() -> Self {
+        Self {
+            present: false,
+            blocking: false,
+            opaque: false,
+            actions: Vec::new(),
+            triggers: Vec::new(),
+            textures: Vec::new(),
+            renders: Vec::new(),
+            cost: 1,
+        }
+    }
+}
+
+/// An action effect covering a tile, see [`TileInfo::triggers`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TileTrigger {
+    /// Position of the mask among the masks of every layer, in layer order.
+    ///
+    /// Tiles of the same mask share it, which tells when a pawn enters or leaves
+    /// the area of the mask.
+    pub mask: usize,
+    pub action: ActionId,
+    pub trigger: Trigger,
+}
+
+/// A dense raster of [`TileInfo`] built from a stack of [`Layer`]s.
+///
+/// The index spans from `(0, 0)` to the furthest tile or blocking area of any mask,
+/// so every query is a single bounds check followed by a vector lookup.
+#[derive(Clone, Debug, Default)]
+pub struct TileIndex {
+    shape: Shape,
+    tiles: Vec<TileInfo>,
+}
+
+impl TileIndex {
+    /// Rasterizes every mask of the given layers.
+    pub fn new(layers: &[Layer]) -> Self {
+        let shape = Self::bounds(layers);
+        let mut index = Self {
+            shape,
+            tiles: vec![TileInfo::default(); shape.area() as usize],
+        };
+        index.rasterize(layers, &Rect::from_shape(shape));
+        index
+    }
+
+    /// Returns the area covered by the index.
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    /// Returns the resolved effects at `coord`, or `None` if it lies outside the index.
+    pub fn get(&self, coord: &Coordinates) -> Option<&TileInfo> {
+        if self.shape.in_bounds(*coord) {
+            self.tiles.get(self.offset_of(coord))
+        } else {
+            None
+        }
+    }
+
+    /// Re-rasterizes the tiles inside `area` from the given layers.
+    ///
+    /// If the layers no longer fit the current bounds, the whole index is rebuilt.
+    pub fn patch(&mut self, layers: &[Layer], area: &Rect) {
+        if Self::bounds(layers) != self.shape {
+            *self = Self::new(layers);
+        } else if let Some(area) = Rect::from_shape(self.shape).intersection(area) {
+            self.rasterize(layers, &area);
+        }
+    }
+
+    /// Computes the smallest shape anchored at `(0, 0)` enclosing every mask tile,
+    /// blocking area and opaque area.
+    fn bounds(layers: &[Layer]) -> Shape {
+        let mut shape = Shape::default();
+        for mask in layers.iter().flat_map(|layer| layer.masks.iter()) {
+            let blocks = mask.effects.iter().filter_map(|effect| match effect {
+                Effect::Block(rect) | Effect::Opaque(rect) => Some(rect),
+                _ => None,
+            });
+            for rect in mask.tiles.iter().chain(blocks) {
+                shape.expand_to_include(rect.origin, rect.shape);
+            }
+        }
+        shape
+    }
+
+    fn offset_of(&self, coord: &Coordinates) -> usize {
+        (coord.y * self.shape.width + coord.x) as usize
+    }
+
+    /// Clears and recomputes every tile inside `area`, which must lie within the index bounds.
+    fn rasterize(&mut self, layers: &[Layer], area: &Rect) {
+        for coord in area.iter() {
+            let offset = self.offset_of(&coord);
+            self.tiles[offset] = TileInfo::default();
+        }
+
+        // Tracks the last mask written to each tile so overlapping rects of the
+        // same mask only contribute their effects once.
+        let mut stamps = vec![usize::MAX; area.shape.area() as usize];
+
+        for (stamp, mask) in layers.iter().flat_map(|l| l.masks.iter()).enumerate() {
+            let triggers: Vec<TileTrigger> = mask
+                .get_triggers()
+                .into_iter()
+                .map(|(action, trigger)| TileTrigger {
+                    mask: stamp,
+                    action,
+                    trigger,
+                })
+                .collect();
+            let actions: Vec<ActionId> = triggers.iter().map(|t| t.action).collect();
+            let texture = mask.get_texture();
+            let render = mask.get_render();
+            let cost = mask.get_cost();
+
+            for rect in mask.tiles.iter().filter_map(|rect| rect.intersection(area)) {
+                for coord in rect.iter() {
+                    let local = ((coord.y - area.origin.y) * area.shape.width + coord.x
+                        - area.origin.x) as usize;
+                    if stamps[local] == stamp {
+                        continue;
+                    }
+                    stamps[local] = stamp;
+
+                    let offset = self.offset_of(&coord);
+                    let tile = &mut self.tiles[offset];
+                    tile.present = true;
+                    tile.actions.extend_from_slice(&actions);
+                    tile.triggers.extend_from_slice(&triggers);
+                    tile.textures.extend(texture);
+                    tile.renders.extend(render);
+                    if let Some(cost) = cost {
+                        tile.cost = cost.max(1);
+                    }
+                }
+            }
+
+            for effect in &mask.effects {
+                let (Some(rect), opaque) = (match effect {
+                    Effect::Block(rect) => (rect.intersection(area), false),
+                    Effect::Opaque(rect) => (rect.intersection(area), true),
+                    _ => continue,
+                }) else {
+                    continue;
+                };
+                for coord in rect.iter() {
+                    let offset = self.offset_of(&coord);
+                    if opaque {
+                        self.tiles[offset].opaque = true;
+                    } else {
+                        self.tiles[offset].blocking = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::Mask;
+
+    fn layers() -> Vec<Layer> {
+        vec![
+            Layer::new(
+                "ground".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 4, 4), Rect::from_xywh(1, 1, 2, 2)],
+                    vec![Effect::Texture(TextureId(1))],
+                )],
+                1,
+            ),
+            Layer::new(
+                "objects".into(),
+                vec![Mask::new(
+                    "chest".into(),
+                    vec![Rect::from_xywh(2, 2, 1, 1)],
+                    vec![
+                        Effect::Action(ActionId(7)),
+                        Effect::Render(RenderId(3)),
+                        Effect::Block(Rect::from_xywh(3, 3, 2, 2)),
+                    ],
+                )],
+                2,
+            ),
+        ]
+    }
+
+    #[test]
+    fn bounds_include_blocking_areas() {
+        let index = TileIndex::new(&layers());
+        assert_eq!(index.shape(), Shape::from_square(5));
+        assert!(index.get(&Coordinates::new(4, 4)).unwrap().blocking);
+        assert!(!index.get(&Coordinates::new(4, 4)).unwrap().present);
+        assert!(index.get(&Coordinates::new(5, 0)).is_none());
+    }
+
+    #[test]
+    fn opaque_areas_do_not_block() {
+        let mut layers = layers();
+        layers[1].masks[0]
+            .effects
+            .push(Effect::Opaque(Rect::from_xywh(0, 5, 1, 1)));
+        let index = TileIndex::new(&layers);
+
+        assert_eq!(index.shape(), Shape::new(5, 6));
+        let tile = index.get(&Coordinates::new(0, 5)).unwrap();
+        assert!(tile.opaque);
+        assert!(!tile.blocking);
+        assert!(!index.get(&Coordinates::new(4, 4)).unwrap().opaque);
+    }
+
+    #[test]
+    fn rasterizes_effects_once_per_mask() {
+        let index = TileIndex::new(&layers());
+        let tile = index.get(&Coordinates::new(2, 2)).unwrap();
+
+        assert!(tile.present);
+        assert!(!tile.blocking);
+        assert_eq!(tile.textures, vec![TextureId(1)]);
+        assert_eq!(tile.actions, vec![ActionId(7)]);
+        assert_eq!(tile.renders, vec![RenderId(3)]);
+    }
+
+    #[test]
+    fn later_costs_override_earlier_ones() {
+        let mut layers = layers();
+        layers[0].masks[0].effects.push(Effect::Cost(5));
+        layers[1].masks[0].effects.push(Effect::Cost(0));
+        let index = TileIndex::new(&layers);
+
+        assert_eq!(index.get(&Coordinates::new(0, 0)).unwrap().cost, 5);
+        assert_eq!(index.get(&Coordinates::new(2, 2)).unwrap().cost, 1);
+        assert_eq!(index.get(&Coordinates::new(4, 4)).unwrap().cost, 1);
+    }
+
+    #[test]
+    fn patches_changed_area() {
+        let mut layers = layers();
+        let mut index = TileIndex::new(&layers);
+
+        layers[1].masks[0].effects = vec![
+            Effect::Action(ActionId(9)),
+            Effect::Block(Rect::from_xywh(3, 3, 2, 2)),
+        ];
+        index.patch(&layers, &Rect::from_xywh(2, 2, 1, 1));
+
+        assert_eq!(
+            index.get(&Coordinates::new(2, 2)).unwrap().actions,
+            vec![ActionId(9)]
+        );
+        assert!(
+            index
+                .get(&Coordinates::new(2, 2))
+                .unwrap()
+                .renders
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn rebuilds_when_bounds_change() {
+        let mut layers = layers();
+        let mut index = TileIndex::new(&layers);
+
+        layers[0].masks[0].tiles.push(Rect::from_xywh(6, 0, 1, 1));
+        index.patch(&layers, &Rect::from_xywh(6, 0, 1, 1));
+
+        assert_eq!(index.shape(), Shape::new(7, 5));
+        assert!(index.get(&Coordinates::new(6, 0)).unwrap().present);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    prelude::{Coordinates, Delta, Mask, Shape},
+    prelude::{ActionId, Coordinates, Delta, Mask, Shape},
     traits::{Grid, Shaped, Shiftable},
 };
 
@@ -9,6 +9,7 @@ use crate::{
 /// Layers allow grouped application of tile-based modifications (e.g. collision, decoration, triggers)
 /// without altering the original base grid. Layers are rendered or processed in Z-order, determined by `z`.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Layer {
     /// The name of the layer (e.g., `"collision"`, `"visuals"`)
     pub name: String,
@@ -60,7 +61,7 @@ impl Layer {
         self.masks.iter().any(|mask| mask.is_blocking_at(target))
     }
 
-    pub fn get_actions_at(&self, target: &Coordinates) -> Vec<u32> {
+    pub fn get_actions_at(&self, target: &Coordinates) -> Vec<ActionId> {
         self.masks
             .iter()
             .flat_map(|mask| {
@@ -103,4 +104,40 @@ mod tests {
         assert!(shape.width >= 2);
         assert!(shape.height >= 2);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use crate::prelude::{Effect, TextureId};
+
+        let layer = Layer::new(
+            "ground".into(),
+            vec![
+                Mask::new(
+                    "grass".into(),
+                    vec![Rect::from_xywh(0, 0, 4, 4)],
+                    vec![Effect::Texture(TextureId(1))],
+                ),
+                Mask::new(
+                    "mud".into(),
+                    vec![Rect::from_xywh(1, 1, 2, 1)],
+                    vec![Effect::Cost(3)],
+                ),
+            ],
+            2,
+        );
+
+        let json = serde_json::to_string(&layer).unwrap();
+        assert!(json.starts_with(r#"{"name":"ground","masks":[{"name":"grass""#));
+        assert!(json.ends_with(r#""z":2}"#));
+        let loaded: Layer = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.name, layer.name);
+        assert_eq!(loaded.z, layer.z);
+        assert_eq!(loaded.masks.len(), 2);
+        for (loaded, mask) in loaded.masks.iter().zip(&layer.masks) {
+            assert_eq!(loaded.name, mask.name);
+            assert_eq!(loaded.tiles, mask.tiles);
+            assert_eq!(loaded.effects, mask.effects);
+        }
+    }
 }
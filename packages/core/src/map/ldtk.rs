@@ -0,0 +1,559 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::prelude::{
+    Coordinates, Effect, Layer, Map, Mask, Rect, ResourceKind, Resources, Textures, namespaced,
+};
+
+/// Identifier of the entity fields holding action keys.
+const ACTION_FIELD: &str = "action";
+
+/// A tile of an LDtk tileset, handed to [`LdtkProject::register_tilesets`] to
+/// build its texture.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TileSource {
+    /// Identifier of the tileset.
+    pub tileset: String,
+    /// Path of the tileset image, relative to the project.
+    pub image: String,
+    /// Pixel position of the tile in the image.
+    pub x: u32,
+    pub y: u32,
+    /// Width and height of the tile, in pixels.
+    pub size: u32,
+}
+
+/// Errors raised while importing LDtk projects.
+///
+/// `level` and `layer` are the identifiers of the LDtk level and layer holding the
+/// faulty item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LdtkError {
+    /// The file could not be read.
+    Io { path: String, message: String },
+    /// The source is not a valid LDtk project.
+    Parse(String),
+    /// The project uses an LDtk feature that can't be imported, such as external levels.
+    Unsupported(String),
+    /// A layer uses a tileset the project doesn't define.
+    UnknownTileset {
+        uid: i64,
+        level: String,
+        layer: String,
+    },
+    /// An entity field names an action that is not in the library.
+    UnknownKey {
+        kind: ResourceKind,
+        key: String,
+        level: String,
+        layer: String,
+    },
+}
+
+impl fmt::Display for LdtkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LdtkError::Io { path, message } => write!(f, "{path}: {message}"),
+            LdtkError::Parse(message) => write!(f, "{message}"),
+            LdtkError::Unsupported(feature) => write!(f, "unsupported LDtk feature: {feature}"),
+            LdtkError::UnknownTileset { uid, level, layer } => {
+                write!(
+                    f,
+                    "level \"{level}\" > layer \"{layer}\": unknown tileset {uid}"
+                )
+            }
+            LdtkError::UnknownKey {
+                kind,
+                key,
+                level,
+                layer,
+            } => write!(
+                f,
+                "level \"{level}\" > layer \"{layer}\": unknown {kind:?} key \"{key}\""
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LdtkError {}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Project {
+    #[serde(default)]
+    external_levels: bool,
+    defs: Defs,
+    levels: Vec<Level>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Defs {
+    #[serde(default)]
+    layers: Vec<LayerDef>,
+    #[serde(default)]
+    tilesets: Vec<TilesetDef>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LayerDef {
+    uid: i64,
+    #[serde(default)]
+    int_grid_values: Vec<IntGridValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IntGridValue {
+    value: u32,
+    #[serde(default)]
+    identifier: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TilesetDef {
+    uid: i64,
+    identifier: String,
+    /// Missing for internal tilesets such as LDtk icons.
+    #[serde(default)]
+    rel_path: Option<String>,
+    tile_grid_size: u32,
+    #[serde(default)]
+    spacing: u32,
+    #[serde(default)]
+    padding: u32,
+    #[serde(rename = "__cWid")]
+    columns: u32,
+    #[serde(rename = "__cHei")]
+    rows: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Level {
+    identifier: String,
+    #[serde(default)]
+    layer_instances: Option<Vec<LayerInstance>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LayerInstance {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__cWid")]
+    columns: u32,
+    #[serde(rename = "__gridSize")]
+    grid_size: u32,
+    #[serde(rename = "__tilesetDefUid", default)]
+    tileset: Option<i64>,
+    layer_def_uid: i64,
+    #[serde(default)]
+    int_grid_csv: Vec<u32>,
+    #[serde(default)]
+    auto_layer_tiles: Vec<TileInstance>,
+    #[serde(default)]
+    grid_tiles: Vec<TileInstance>,
+    #[serde(default)]
+    entity_instances: Vec<EntityInstance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TileInstance {
+    /// Pixel position in the layer.
+    px: [u32; 2],
+    /// Tile id in the tileset.
+    t: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EntityInstance {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    px: [f64; 2],
+    #[serde(rename = "__pivot", default)]
+    pivot: [f64; 2],
+    width: f64,
+    height: f64,
+    #[serde(default)]
+    field_instances: Vec<FieldInstance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FieldInstance {
+    #[serde(rename = "__identifier")]
+    identifier: String,
+    #[serde(rename = "__value")]
+    value: Value,
+}
+
+/// A level of an LDtk project, see [`LdtkProject`].
+#[derive(Clone)]
+pub struct LdtkLevel {
+    pub map: Map,
+    /// Entities without an action, as `(identifier, position)`, where pawns are
+    /// meant to be placed.
+    pub spawns: Vec<(String, Coordinates)>,
+}
+
+#[doc = include_str!("../../docs/ldtk.md")]
+/// An LDtk project, imported as one [`LdtkLevel`] per level.
+#[derive(Debug)]
+pub struct LdtkProject {
+    project: Project,
+}
+
+impl LdtkProject {
+    /// Reads an `.ldtk` project file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LdtkError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path).map_err(|e| LdtkError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        Self::parse(&source)
+    }
+
+    /// Parses the JSON of an LDtk project.
+    pub fn parse(source: &str) -> Result<Self, LdtkError> {
+        let project: Project =
+            serde_json::from_str(source).map_err(|e| LdtkError::Parse(e.to_string()))?;
+        if project.external_levels {
+            return Err(LdtkError::Unsupported(
+                "external levels, save the levels in the project file".into(),
+            ));
+        }
+        Ok(Self { project })
+    }
+
+    /// Inserts every tile of the project tilesets into `textures`, keyed
+    /// `tileset/id`, with the value built by `texture`.
+    pub fn register_tilesets<T>(
+        &self,
+        textures: &mut Textures<T>,
+        mut texture: impl FnMut(&TileSource) -> T,
+    ) {
+        for tileset in &self.project.defs.tilesets {
+            let Some(image) = &tileset.rel_path else {
+                continue;
+            };
+            let step = tileset.tile_grid_size + tileset.spacing;
+            for id in 0..tileset.columns * tileset.rows {
+                let source = TileSource {
+                    tileset: tileset.identifier.clone(),
+                    image: image.clone(),
+                    x: tileset.padding + id % tileset.columns * step,
+                    y: tileset.padding + id / tileset.columns * step,
+                    size: tileset.tile_grid_size,
+                };
+                textures.insert(
+                    namespaced(&tileset.identifier, &id.to_string()),
+                    texture(&source),
+                );
+            }
+        }
+    }
+
+    /// Builds a map for every level, resolving tiles and entity actions through
+    /// `resources`.
+    pub fn levels<T, A, R>(
+        &self,
+        resources: &Resources<T, A, R>,
+    ) -> Result<Vec<LdtkLevel>, LdtkError> {
+        self.project
+            .levels
+            .iter()
+            .map(|level| self.level(level, resources))
+            .collect()
+    }
+
+    fn level<T, A, R>(
+        &self,
+        level: &Level,
+        resources: &Resources<T, A, R>,
+    ) -> Result<LdtkLevel, LdtkError> {
+        let instances = level.layer_instances.as_deref().unwrap_or_default();
+        let mut layers = Vec::new();
+        let mut spawns = Vec::new();
+
+        // LDtk lists layers from the top one down
+        for (i, instance) in instances.iter().enumerate() {
+            let mut masks = self.int_grid_masks(instance);
+            masks.extend(self.tile_masks(level, instance, resources)?);
+
+            for (e, entity) in instance.entity_instances.iter().enumerate() {
+                let area = entity_area(instance.grid_size, entity);
+                let mut effects = Vec::new();
+                // Other fields, such as names or dialogue, are left to the game
+                let keys = entity
+                    .field_instances
+                    .iter()
+                    .filter(|field| field.identifier == ACTION_FIELD)
+                    .flat_map(|field| match &field.value {
+                        Value::String(key) => vec![key.as_str()],
+                        Value::Array(keys) => keys.iter().filter_map(Value::as_str).collect(),
+                        _ => Vec::new(),
+                    });
+                for key in keys {
+                    let action =
+                        resources
+                            .actions
+                            .get_id(key)
+                            .ok_or_else(|| LdtkError::UnknownKey {
+                                kind: ResourceKind::Action,
+                                key: key.to_string(),
+                                level: level.identifier.clone(),
+                                layer: instance.identifier.clone(),
+                            })?;
+                    effects.push(Effect::Action(action));
+                }
+
+                if effects.is_empty() {
+                    spawns.push((entity.identifier.clone(), area.origin));
+                } else {
+                    let name = format!("{} {e}", entity.identifier);
+                    masks.push(Mask::new(name, vec![area], effects));
+                }
+            }
+
+            if !masks.is_empty() {
+                let z = (instances.len() - i) as u32;
+                layers.push(Layer::new(instance.identifier.clone(), masks, z));
+            }
+        }
+        layers.sort_by_key(|layer| layer.z);
+
+        let spawn = spawns.first().map(|(_, spawn)| *spawn).unwrap_or_default();
+        Ok(LdtkLevel {
+            map: Map::new(level.identifier.clone(), layers, spawn),
+            spawns,
+        })
+    }
+
+    /// One blocking mask per IntGrid value.
+    fn int_grid_masks(&self, instance: &LayerInstance) -> Vec<Mask> {
+        let mut cells: BTreeMap<u32, Vec<Coordinates>> = BTreeMap::new();
+        for (i, value) in instance.int_grid_csv.iter().enumerate() {
+            if *value != 0 && instance.columns > 0 {
+                let (x, y) = (i as u32 % instance.columns, i as u32 / instance.columns);
+                cells
+                    .entry(*value)
+                    .or_default()
+                    .push(Coordinates::new(x, y));
+            }
+        }
+
+        let definition = self
+            .project
+            .defs
+            .layers
+            .iter()
+            .find(|layer| layer.uid == instance.layer_def_uid);
+        cells
+            .into_iter()
+            .map(|(value, cells)| {
+                let name = definition
+                    .and_then(|layer| layer.int_grid_values.iter().find(|v| v.value == value))
+                    .and_then(|v| v.identifier.clone())
+                    .unwrap_or_else(|| format!("value {value}"));
+                let tiles = Rect::cover(cells);
+                let effects = tiles.iter().map(|rect| Effect::Block(*rect)).collect();
+                Mask::new(name, tiles, effects)
+            })
+            .collect()
+    }
+
+    /// One texture mask per tile of the auto-layer and tile layer tiles.
+    fn tile_masks<T, A, R>(
+        &self,
+        level: &Level,
+        instance: &LayerInstance,
+        resources: &Resources<T, A, R>,
+    ) -> Result<Vec<Mask>, LdtkError> {
+        let tiles = instance.auto_layer_tiles.iter().chain(&instance.grid_tiles);
+        let Some(uid) = instance.tileset else {
+            return Ok(Vec::new());
+        };
+        let tileset = self
+            .project
+            .defs
+            .tilesets
+            .iter()
+            .find(|tileset| tileset.uid == uid)
+            .ok_or_else(|| LdtkError::UnknownTileset {
+                uid,
+                level: level.identifier.clone(),
+                layer: instance.identifier.clone(),
+            })?;
+
+        let grid_size = instance.grid_size.max(1);
+        let mut cells: BTreeMap<u32, Vec<Coordinates>> = BTreeMap::new();
+        for tile in tiles {
+            cells.entry(tile.t).or_default().push(Coordinates::new(
+                tile.px[0] / grid_size,
+                tile.px[1] / grid_size,
+            ));
+        }
+
+        cells
+            .into_iter()
+            .map(|(id, cells)| {
+                let key = namespaced(&tileset.identifier, &id.to_string());
+                let texture =
+                    resources
+                        .textures
+                        .get_id(&key)
+                        .ok_or_else(|| LdtkError::UnknownKey {
+                            kind: ResourceKind::Texture,
+                            key: key.clone(),
+                            level: level.identifier.clone(),
+                            layer: instance.identifier.clone(),
+                        })?;
+                Ok(Mask::new(
+                    key,
+                    Rect::cover(cells),
+                    vec![Effect::Texture(texture)],
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Returns the cells covered by an entity, at least one.
+fn entity_area(grid_size: u32, entity: &EntityInstance) -> Rect {
+    let grid_size = grid_size.max(1) as f64;
+    let left = entity.px[0] - entity.pivot[0] * entity.width;
+    let top = entity.px[1] - entity.pivot[1] * entity.height;
+    let x = (left / grid_size).floor().max(0.0);
+    let y = (top / grid_size).floor().max(0.0);
+    let right = ((left + entity.width) / grid_size).ceil().max(x + 1.0);
+    let bottom = ((top + entity.height) / grid_size).ceil().max(y + 1.0);
+    Rect::from_xywh(x as u32, y as u32, (right - x) as u32, (bottom - y) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{ActionId, Library};
+
+    fn project() -> LdtkProject {
+        LdtkProject::from_file(format!(
+            "{}/fixtures/ldtk/world.ldtk",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap()
+    }
+
+    fn resources(project: &LdtkProject) -> Resources<TileSource, (), ()> {
+        let mut resources = Resources::new();
+        project.register_tilesets(&mut resources.textures, TileSource::clone);
+        resources.actions.insert("open_chest", ());
+        resources
+    }
+
+    #[test]
+    fn registers_tilesets() {
+        let project = project();
+        let mut textures: Textures<TileSource> = Library::new();
+        project.register_tilesets(&mut textures, TileSource::clone);
+
+        // Internal tilesets without an image are skipped
+        assert_eq!(textures.len(), 16);
+        let id = textures.get_id("Dungeon/5").unwrap();
+        assert_eq!(
+            textures.get_by_id(id),
+            Some(&TileSource {
+                tileset: "Dungeon".into(),
+                image: "dungeon.png".into(),
+                x: 16,
+                y: 16,
+                size: 16,
+            })
+        );
+    }
+
+    #[test]
+    fn imports_one_map_per_level() {
+        let project = project();
+        let resources = resources(&project);
+        let levels = project.levels(&resources).unwrap();
+        assert_eq!(levels.len(), 2);
+
+        let map = &levels[0].map;
+        let layers: Vec<_> = map
+            .layers()
+            .iter()
+            .map(|l| (l.name.as_str(), l.z))
+            .collect();
+        assert_eq!(layers, [("Floor", 1), ("Walls", 2), ("Entities", 3)]);
+
+        let walls = &map.layers()[1];
+        let names: Vec<_> = walls.masks.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["wall", "water", "Dungeon/4", "Dungeon/5"]);
+        assert_eq!(
+            walls.masks[0].effects,
+            vec![Effect::Block(Rect::from_xywh(0, 0, 4, 1))]
+        );
+        assert!(map.is_blocking_at(&Coordinates::new(3, 1)));
+        assert!(!map.is_blocking_at(&Coordinates::new(2, 1)));
+
+        let wall = resources.textures.get_id("Dungeon/4").unwrap();
+        let floor = resources.textures.get_id("Dungeon/0").unwrap();
+        assert_eq!(
+            map.get_textures_at(&Coordinates::new(1, 0)),
+            vec![floor, wall]
+        );
+        assert_eq!(
+            map.get_actions_at(&Coordinates::new(2, 1)),
+            vec![ActionId(1)]
+        );
+        assert_eq!(map.spawn, Coordinates::new(0, 1));
+
+        let empty = &levels[1];
+        assert_eq!(empty.map.layers().len(), 1);
+        assert!(empty.spawns.is_empty());
+    }
+
+    #[test]
+    fn ignores_fields_other_than_actions() {
+        let source = std::fs::read_to_string(format!(
+            "{}/fixtures/ldtk/world.ldtk",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap();
+        let named = source
+            .replacen(r#""__identifier": "hp""#, r#""__identifier": "name""#, 1)
+            .replacen(r#""__value": 10"#, r#""__value": "Hero""#, 1);
+        assert_ne!(named, source);
+
+        let project = LdtkProject::parse(&named).unwrap();
+        let levels = project.levels(&resources(&project)).unwrap();
+        assert_eq!(
+            levels[0].spawns,
+            vec![("Player".to_string(), Coordinates::new(0, 1))]
+        );
+        assert_eq!(
+            levels[0].map.get_actions_at(&Coordinates::new(2, 1)),
+            vec![ActionId(1)]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_actions() {
+        let project = project();
+        let mut resources = resources(&project);
+        resources.actions.remove(ActionId(1));
+        assert_eq!(
+            project.levels(&resources).err(),
+            Some(LdtkError::UnknownKey {
+                kind: ResourceKind::Action,
+                key: "open_chest".into(),
+                level: "Level_0".into(),
+                layer: "Entities".into(),
+            })
+        );
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
-    prelude::{Coordinates, Delta, Effect, Rect, Shape},
+    prelude::{ActionId, Coordinates, Delta, Effect, Rect, RenderId, Shape, TextureId, Trigger},
     traits::{Grid, Shaped, Shiftable},
 };
 
 #[doc = include_str!("../../docs/mask.md")]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mask {
     /// The name of the mask for identification or debugging.
     pub name: String,
@@ -73,29 +74,50 @@ impl Mask {
         })
     }
 
-    pub fn get_actions(&self) -> Vec<u32> {
+    /// Returns true if an [`Effect::Opaque`] area of this mask covers `target`.
+    pub fn is_opaque_at(&self, target: &Coordinates) -> bool {
+        self.effects.iter().any(|effect| match effect {
+            Effect::Opaque(rect) => rect.contains(target),
+            _ => false,
+        })
+    }
+
+    pub fn get_actions(&self) -> Vec<ActionId> {
+        self.get_triggers().into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Returns the action ids of this mask along with when they fire.
+    pub fn get_triggers(&self) -> Vec<(ActionId, Trigger)> {
         self.effects
             .iter()
             .filter_map(|effect| match effect {
-                Effect::Action(id) => Some(*id),
+                Effect::Action(id) => Some((*id, Trigger::default())),
+                Effect::Trigger(id, trigger) => Some((*id, *trigger)),
                 _ => None,
             })
             .collect()
     }
 
-    pub fn get_texture(&self) -> Option<u32> {
+    pub fn get_texture(&self) -> Option<TextureId> {
         self.effects.iter().find_map(|effect| match effect {
             Effect::Texture(id) => Some(*id),
             _ => None,
         })
     }
 
-    pub fn get_render(&self) -> Option<u32> {
+    pub fn get_render(&self) -> Option<RenderId> {
         self.effects.iter().find_map(|effect| match effect {
             Effect::Render(id) => Some(*id),
             _ => None,
         })
     }
+
+    pub fn get_cost(&self) -> Option<u32> {
+        self.effects.iter().find_map(|effect| match effect {
+            Effect::Cost(cost) => Some(*cost),
+            _ => None,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -164,4 +186,26 @@ mod tests {
         assert_eq!(shape.width, 7); // 4 + 3
         assert_eq!(shape.height, 4); // max(1+2, 3+1)
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use crate::prelude::TriggerOn;
+
+        let mask = Mask::new(
+            "door".into(),
+            vec![Rect::from_xywh(1, 1, 1, 2), Rect::from_xywh(4, 0, 1, 1)],
+            vec![
+                Effect::Texture(TextureId(2)),
+                Effect::Trigger(ActionId(1), Trigger::once(TriggerOn::Enter)),
+            ],
+        );
+
+        let json = serde_json::to_string(&mask).unwrap();
+        assert!(json.starts_with(r#"{"name":"door","tiles":[{"origin":{"x":1,"y":1}"#));
+        let loaded: Mask = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.name, mask.name);
+        assert_eq!(loaded.tiles, mask.tiles);
+        assert_eq!(loaded.effects, mask.effects);
+    }
 }
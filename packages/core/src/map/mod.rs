@@ -1,24 +1,45 @@
 use crate::{
-    prelude::{Coordinates, Delta, Direction, Layer, Shape},
+    prelude::{
+        ActionId, Coordinates, Delta, Direction, Layer, Rect, RenderId, ResourceRemap, Shape,
+        TextureId,
+    },
     traits::{Grid, Shaped, Shiftable},
 };
+use index::{TileIndex, TileInfo};
 use indexmap::IndexMap;
+use std::sync::OnceLock;
 
 pub mod effect;
+pub mod field;
+#[cfg(feature = "serde")]
+pub mod file;
+pub mod hierarchy;
+pub mod index;
 pub mod layer;
+#[cfg(feature = "ldtk")]
+pub mod ldtk;
 pub mod mask;
+pub mod planner;
+pub mod reach;
 pub mod routing;
+#[cfg(feature = "tiled")]
+pub mod tiled;
+pub mod visibility;
 
 #[doc = include_str!("../../docs/map.md")]
 /// Represents a game map with multiple layers, a name, and a spawn point.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Map {
     /// Name identifier for the map
     pub name: String,
-    /// Vector of layers stacked in this map
-    pub layers: Vec<Layer>,
+    /// Vector of layers stacked in this map, see [`Map::layers_mut`].
+    layers: Vec<Layer>,
     /// Default spawn coordinates for pawns/players
     pub spawn: Coordinates,
+    /// Lazily built raster of `layers`, see [`Map::index`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    index: OnceLock<TileIndex>,
 }
 
 impl Shaped for Map {
@@ -32,7 +53,7 @@ impl Shaped for Map {
 impl Grid for Map {
     /// Checks if the map contains a tile at the specified coordinate.
     fn contains(&self, coord: &Coordinates) -> bool {
-        self.layers.iter().any(|layer| layer.contains(coord))
+        self.tile_at(coord).is_some_and(|tile| tile.present)
     }
 }
 
@@ -46,17 +67,106 @@ impl Map {
             name,
             layers,
             spawn,
+            index: OnceLock::new(),
         }
     }
 
+    /// Returns the layers stacked in this map.
+    pub fn layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    /// Returns the layers for editing.
+    ///
+    /// The spatial index is dropped and rebuilt on the next query. To re-rasterize
+    /// only the tiles an edit touches, use [`Map::edit_area`] instead.
+    pub fn layers_mut(&mut self) -> &mut Vec<Layer> {
+        self.invalidate_index();
+        &mut self.layers
+    }
+
+    /// Adds a layer on top of the others, as is. See [`Map::load_layer`] to make
+    /// room for a larger layer.
+    pub fn push_layer(&mut self, layer: Layer) {
+        self.layers.push(layer);
+        self.invalidate_index();
+    }
+
+    /// Edits the layers with `edit`, then re-rasterizes only the tiles inside `area`.
+    ///
+    /// Changes outside `area` are only picked up once the index is rebuilt, so
+    /// `area` must cover every tile whose effects the edit changes.
+    pub fn edit_area<T>(&mut self, area: &Rect, edit: impl FnOnce(&mut Vec<Layer>) -> T) -> T {
+        let result = edit(&mut self.layers);
+        if let Some(index) = self.index.get_mut() {
+            index.patch(&self.layers, area);
+        }
+        result
+    }
+
+    /// Returns the spatial index of the map, rasterizing the layers on first use.
+    ///
+    /// The index is dropped by every `Map` method that changes the layers, including
+    /// [`Map::layers_mut`], and patched by [`Map::edit_area`].
+    pub fn index(&self) -> &TileIndex {
+        self.index.get_or_init(|| TileIndex::new(&self.layers))
+    }
+
+    /// Drops the spatial index so it is rebuilt on the next query.
+    fn invalidate_index(&mut self) {
+        self.index = OnceLock::new();
+    }
+
+    /// Returns the resolved effects at `target`, or `None` if it lies outside the map bounds.
+    pub fn tile_at(&self, target: &Coordinates) -> Option<&TileInfo> {
+        self.index().get(target)
+    }
+
     pub fn is_blocking_at(&self, target: &Coordinates) -> bool {
-        self.layers.iter().any(|layer| layer.is_blocking_at(target))
+        self.tile_at(target).is_some_and(|tile| tile.blocking)
     }
 
-    pub fn get_actions_at(&self, target: &Coordinates) -> Vec<u32> {
+    /// Returns true if an [`Effect::Opaque`] area covers `target`.
+    ///
+    /// [`Effect::Opaque`]: crate::prelude::Effect::Opaque
+    pub fn is_opaque_at(&self, target: &Coordinates) -> bool {
+        self.tile_at(target).is_some_and(|tile| tile.opaque)
+    }
+
+    pub fn get_actions_at(&self, target: &Coordinates) -> Vec<ActionId> {
+        self.tile_at(target)
+            .map(|tile| tile.actions.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the movement cost of entering `target`, `1` unless an [`Effect::Cost`] applies.
+    ///
+    /// [`Effect::Cost`]: crate::prelude::Effect::Cost
+    pub fn get_cost_at(&self, target: &Coordinates) -> u32 {
+        self.tile_at(target).map_or(1, |tile| tile.cost)
+    }
+
+    /// Returns the texture ids of every mask covering `target`, bottom layer first.
+    pub fn get_textures_at(&self, target: &Coordinates) -> Vec<TextureId> {
+        self.tile_at(target)
+            .map(|tile| tile.textures.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the render ids of every mask covering `target`, bottom layer first.
+    pub fn get_renders_at(&self, target: &Coordinates) -> Vec<RenderId> {
+        self.tile_at(target)
+            .map(|tile| tile.renders.clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of the masks covering `target`, bottom layer first.
+    pub fn get_masks_at(&self, target: &Coordinates) -> Vec<String> {
         self.layers
             .iter()
-            .flat_map(|layer| layer.get_actions_at(target))
+            .flat_map(|layer| &layer.masks)
+            .filter(|mask| mask.contains(target))
+            .map(|mask| mask.name.clone())
             .collect()
     }
 
@@ -103,6 +213,20 @@ impl Map {
         }
 
         self.layers.push(layer);
+        self.invalidate_index();
+    }
+
+    /// Replaces the resource ids of every effect with their new ids in `remap`,
+    /// after the library the map was built against was merged into another.
+    pub fn remap(&mut self, remap: &ResourceRemap) {
+        let masks = self
+            .layers
+            .iter_mut()
+            .flat_map(|layer| layer.masks.iter_mut());
+        for effect in masks.flat_map(|mask| mask.effects.iter_mut()) {
+            effect.remap(remap);
+        }
+        self.invalidate_index();
     }
 
     /// Returns a map from layer name to the corresponding `Layer`.
@@ -126,6 +250,7 @@ impl Map {
         if let Some(new_spawn) = spawn {
             self.spawn = new_spawn;
         }
+        self.invalidate_index();
     }
 
     /// Duplicates this map in the given direction by merging a copy adjacent to itself.
@@ -142,6 +267,12 @@ impl Map {
                 x: shape.width,
                 y: 0,
             },
+            Direction::UpLeft | Direction::UpRight | Direction::DownLeft | Direction::DownRight => {
+                Coordinates {
+                    x: shape.width,
+                    y: shape.height,
+                }
+            }
         };
         self.merge_at(&self.clone(), top_left, spawn);
     }
@@ -205,7 +336,7 @@ mod tests {
         map.merge_at(&map2, Coordinates::new(5, 5), None);
         let layers = map.layers_by_name();
         assert!(layers.contains_key("blocking"));
-        assert_eq!(map.layers.len(), 2);
+        assert_eq!(map.layers().len(), 2);
     }
 
     #[test]
@@ -214,6 +345,107 @@ mod tests {
         let mut dup = map.clone();
         dup.duplicate_to_the(Direction::Right, None);
 
-        assert_eq!(dup.layers.len(), map.layers.len() * 2);
+        assert_eq!(dup.layers().len(), map.layers().len() * 2);
+    }
+
+    #[test]
+    fn index_matches_layer_queries() {
+        let map = build_test_map(&[Coordinates::new(1, 1), Coordinates::new(2, 0)]);
+
+        for coord in Rect::from_xywh(0, 0, 4, 4).iter() {
+            assert_eq!(
+                map.is_blocking_at(&coord),
+                map.layers().iter().any(|l| l.is_blocking_at(&coord))
+            );
+            assert_eq!(
+                map.contains(&coord),
+                map.layers().iter().any(|l| l.contains(&coord))
+            );
+        }
+    }
+
+    #[test]
+    fn merge_invalidates_index() {
+        let mut map = build_test_map(&[]);
+        assert!(!map.is_blocking_at(&Coordinates::new(6, 6)));
+
+        map.merge_at(
+            &build_test_map(&[Coordinates::new(1, 1)]),
+            Coordinates::new(5, 5),
+            None,
+        );
+        assert!(map.is_blocking_at(&Coordinates::new(6, 6)));
+    }
+
+    #[test]
+    fn layer_edits_refresh_the_index() {
+        let mut map = build_test_map(&[Coordinates::new(1, 1)]);
+        assert!(map.is_blocking_at(&Coordinates::new(1, 1)));
+
+        map.layers_mut()[0].masks[0].effects.clear();
+        assert!(!map.is_blocking_at(&Coordinates::new(1, 1)));
+
+        map.push_layer(Layer::new(
+            "walls".into(),
+            vec![Mask::new(
+                "wall".into(),
+                vec![],
+                vec![Effect::Block(Rect::from_xywh(0, 2, 2, 1))],
+            )],
+            2,
+        ));
+        assert!(map.is_blocking_at(&Coordinates::new(1, 2)));
+    }
+
+    #[test]
+    fn edit_area_patches_the_edited_tiles() {
+        let mut map = build_test_map(&[Coordinates::new(1, 1)]);
+        assert!(map.is_blocking_at(&Coordinates::new(1, 1)));
+
+        map.edit_area(&Rect::from_xywh(1, 1, 1, 1), |layers| {
+            layers[0].masks[0].effects.clear()
+        });
+        assert!(!map.is_blocking_at(&Coordinates::new(1, 1)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use crate::prelude::{ActionId, TextureId, Trigger, TriggerOn};
+
+        let wall = Rect::from_xywh(2, 0, 1, 2);
+        let map = Map::new(
+            "room".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![Mask::new(
+                    "floor".into(),
+                    vec![Rect::from_xywh(0, 0, 4, 2)],
+                    vec![
+                        Effect::Texture(TextureId(3)),
+                        Effect::Trigger(ActionId(7), Trigger::once(TriggerOn::Interact)),
+                        Effect::Block(wall),
+                    ],
+                )],
+                1,
+            )],
+            Coordinates::new(1, 1),
+        );
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert!(json.starts_with(r#"{"name":"room","layers":[{"name":"ground","masks":"#));
+        assert!(json.contains(r#"{"Texture":3}"#));
+        assert!(
+            json.contains(r#"{"Block":{"origin":{"x":2,"y":0},"shape":{"width":1,"height":2}}}"#)
+        );
+        assert!(json.ends_with(r#""spawn":{"x":1,"y":1}}"#));
+
+        let loaded: Map = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+        assert!(loaded.is_blocking_at(&Coordinates::new(2, 1)));
+        assert_eq!(
+            loaded.get_textures_at(&Coordinates::new(3, 1)),
+            vec![TextureId(3)]
+        );
     }
 }
@@ -0,0 +1,446 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::{
+    errors::RPGXError,
+    prelude::{Coordinates, Map, PathOptions},
+};
+
+/// Priority of a tile in the open set: `(min(g, rhs) + h + km, min(g, rhs))`.
+type Key = (i32, i32);
+
+const INFINITY: i32 = i32::MAX;
+
+/// Map state of a tile as last seen by the planner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct TileState {
+    /// Whether the tile may be entered at all under the planner options.
+    open: bool,
+    cost: u32,
+}
+
+/// Incremental route planner (D* Lite) towards a fixed goal.
+///
+/// The planner keeps its search state between calls to [`Planner::next_step`].
+/// Every call checks the tiles it has already looked at against the map and, if
+/// blocks, masks or costs changed, repairs only the affected part of the search
+/// instead of starting over. A pawn walking with a planner reroutes around a door
+/// that closes mid-walk instead of failing.
+///
+/// With the `serde` feature only the goal and options are stored, the search
+/// starts over once loaded.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "PlannerGoal", into = "PlannerGoal")
+)]
+pub struct Planner {
+    goal: Coordinates,
+    options: PathOptions,
+    /// Estimated cost to the goal, settled by the search.
+    g: HashMap<Coordinates, i32>,
+    /// One-step lookahead of `g`.
+    rhs: HashMap<Coordinates, i32>,
+    open_set: BinaryHeap<Reverse<(Key, u32, u32)>>,
+    /// Current key of every tile in `open_set`, used to discard outdated heap entries.
+    queued: HashMap<Coordinates, Key>,
+    /// Heuristic offset accumulated as the start moves.
+    km: i32,
+    last_start: Option<Coordinates>,
+    seen: HashMap<Coordinates, TileState>,
+    /// Tiles taken by other pawns, see [`Planner::set_occupied`].
+    occupied: HashSet<Coordinates>,
+}
+
+/// Serialized form of a [`Planner`].
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PlannerGoal {
+    goal: Coordinates,
+    options: PathOptions,
+}
+
+#[cfg(feature = "serde")]
+impl From<PlannerGoal> for Planner {
+    fn from(planner: PlannerGoal) -> Self {
+        Planner::new(planner.goal, &planner.options)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Planner> for PlannerGoal {
+    fn from(planner: Planner) -> Self {
+        PlannerGoal {
+            goal: planner.goal,
+            options: planner.options,
+        }
+    }
+}
+
+impl Planner {
+    /// Creates a planner routing towards `goal` under `options`.
+    ///
+    /// The planner honours movement, bounds, forbidden tiles, the action policy,
+    /// `max_cost` and `max_nodes` (counted per call). `max_length` and the
+    /// tie-breaking policy are ignored.
+    pub fn new(goal: Coordinates, options: &PathOptions) -> Self {
+        let mut planner = Self {
+            goal,
+            options: options.clone(),
+            g: HashMap::new(),
+            rhs: HashMap::new(),
+            open_set: BinaryHeap::new(),
+            queued: HashMap::new(),
+            km: 0,
+            last_start: None,
+            seen: HashMap::new(),
+            occupied: HashSet::new(),
+        };
+        planner.rhs.insert(goal, 0);
+        planner.queue(goal, (0, 0));
+        planner
+    }
+
+    /// Returns the tile the planner routes towards.
+    pub fn goal(&self) -> Coordinates {
+        self.goal
+    }
+
+    /// Returns the options the planner routes with.
+    pub fn options(&self) -> &PathOptions {
+        &self.options
+    }
+
+    /// Replaces the tiles currently taken by other pawns.
+    ///
+    /// Occupied tiles other than the goal are avoided like forbidden tiles, and the
+    /// next call repairs the route around them like around any other map change.
+    pub fn set_occupied(&mut self, tiles: impl IntoIterator<Item = Coordinates>) {
+        self.occupied = tiles.into_iter().collect();
+    }
+
+    /// Returns the next tile to step onto from `from`, replanning if the map changed
+    /// since the last call. Returns `from` itself once it is the goal.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RPGXError::PathNotFround`] if the goal cannot be reached from `from`.
+    pub fn next_step(&mut self, map: &Map, from: Coordinates) -> Result<Coordinates, RPGXError> {
+        self.plan(map, from)?;
+        if from == self.goal {
+            return Ok(from);
+        }
+        self.best_successor(map, from)
+            .map(|(next, _)| next)
+            .ok_or(self.not_found(from))
+    }
+
+    /// Returns the full current route from `from` to the goal, both included.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RPGXError::PathNotFround`] if the goal cannot be reached from `from`.
+    pub fn path(&mut self, map: &Map, from: Coordinates) -> Result<Vec<Coordinates>, RPGXError> {
+        self.plan(map, from)?;
+        let mut path = vec![from];
+        let mut current = from;
+        while current != self.goal {
+            current = self
+                .best_successor(map, current)
+                .map(|(next, _)| next)
+                .ok_or(self.not_found(from))?;
+            // The settled g values strictly decrease towards the goal, but guard
+            // against cycles through tiles the search has not settled yet.
+            if path.contains(&current) {
+                return Err(self.not_found(from));
+            }
+            path.push(current);
+        }
+        Ok(path)
+    }
+
+    fn not_found(&self, from: Coordinates) -> RPGXError {
+        RPGXError::PathNotFround {
+            from,
+            to: self.goal,
+        }
+    }
+
+    /// Brings the search up to date with the map and with the start position.
+    fn plan(&mut self, map: &Map, start: Coordinates) -> Result<(), RPGXError> {
+        if let Some(last_start) = self.last_start
+            && last_start != start
+        {
+            self.km =
+                self.km
+                    .saturating_add(Map::estimate(self.options.movement, last_start, start));
+        }
+        self.last_start = Some(start);
+
+        self.sync(map);
+        self.compute(map, start)
+    }
+
+    /// Updates every tile around a seen tile whose state differs from the map.
+    fn sync(&mut self, map: &Map) {
+        let changed: Vec<Coordinates> = self
+            .seen
+            .iter()
+            .filter(|(coord, state)| self.state_of(map, coord) != **state)
+            .map(|(coord, _)| *coord)
+            .collect();
+
+        for coord in changed {
+            self.seen.insert(coord, self.state_of(map, &coord));
+            // Any edge entering `coord` or cutting its corner starts next to it
+            self.update_vertex(map, coord);
+            for neighbor in self.adjacent(coord) {
+                self.update_vertex(map, neighbor);
+            }
+        }
+    }
+
+    fn state_of(&self, map: &Map, coord: &Coordinates) -> TileState {
+        TileState {
+            open: map.is_passable(coord)
+                && self.options.in_bounds(coord)
+                && !self.options.forbidden.contains(coord)
+                && (*coord == self.goal
+                    || (!self.options.avoids(map, coord) && !self.occupied.contains(coord))),
+            cost: map.get_cost_at(coord),
+        }
+    }
+
+    /// Runs the search until the route from `start` is settled.
+    fn compute(&mut self, map: &Map, start: Coordinates) -> Result<(), RPGXError> {
+        let mut expanded = 0;
+        while let Some(Reverse((key, x, y))) = self.open_set.peek().copied() {
+            let current = Coordinates::new(x, y);
+            if self.queued.get(&current) != Some(&key) {
+                self.open_set.pop();
+                continue;
+            }
+
+            let start_key = self.key(start);
+            if key >= start_key && self.rhs_of(&start) == self.g_of(&start) {
+                break;
+            }
+
+            expanded += 1;
+            if self
+                .options
+                .max_nodes
+                .is_some_and(|max_nodes| expanded > max_nodes)
+            {
+                return Err(self.not_found(start));
+            }
+
+            self.open_set.pop();
+            self.queued.remove(&current);
+
+            let new_key = self.key(current);
+            if key < new_key {
+                self.queue(current, new_key);
+            } else if self.g_of(&current) > self.rhs_of(&current) {
+                self.g.insert(current, self.rhs_of(&current));
+                for neighbor in self.adjacent(current) {
+                    self.update_vertex(map, neighbor);
+                }
+            } else {
+                self.g.insert(current, INFINITY);
+                self.update_vertex(map, current);
+                for neighbor in self.adjacent(current) {
+                    self.update_vertex(map, neighbor);
+                }
+            }
+        }
+
+        if self.rhs_of(&start) > self.options.cost_limit() {
+            Err(self.not_found(start))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Recomputes the lookahead of `coord` from its successors.
+    fn update_vertex(&mut self, map: &Map, coord: Coordinates) {
+        if coord != self.goal {
+            let rhs = self
+                .best_successor(map, coord)
+                .map_or(INFINITY, |(_, cost)| cost);
+            self.rhs.insert(coord, rhs);
+        }
+
+        self.queued.remove(&coord);
+        if self.g_of(&coord) != self.rhs_of(&coord) {
+            self.queue(coord, self.key(coord));
+        }
+    }
+
+    /// Returns the successor of `coord` with the cheapest route to the goal, and that cost.
+    fn best_successor(&mut self, map: &Map, coord: Coordinates) -> Option<(Coordinates, i32)> {
+        let mut best = None;
+        for direction in self.options.movement.directions() {
+            let Some(next) = coord + direction.to_delta() else {
+                continue;
+            };
+            let state = self.state_of(map, &next);
+            self.seen.insert(next, state);
+            if !state.open || map.cuts_corner(&coord, *direction) {
+                continue;
+            }
+            let g = self.g_of(&next);
+            if g == INFINITY {
+                continue;
+            }
+            let cost = g.saturating_add(map.step_cost(*direction, &next));
+            if best.is_none_or(|(_, best_cost)| cost < best_cost) {
+                best = Some((next, cost));
+            }
+        }
+        best
+    }
+
+    /// Tiles one step away from `coord` in any direction of the planner movement.
+    fn adjacent(&self, coord: Coordinates) -> Vec<Coordinates> {
+        self.options
+            .movement
+            .directions()
+            .iter()
+            .filter_map(|direction| coord + direction.to_delta())
+            .collect()
+    }
+
+    fn g_of(&self, coord: &Coordinates) -> i32 {
+        *self.g.get(coord).unwrap_or(&INFINITY)
+    }
+
+    fn rhs_of(&self, coord: &Coordinates) -> i32 {
+        *self.rhs.get(coord).unwrap_or(&INFINITY)
+    }
+
+    fn key(&self, coord: Coordinates) -> Key {
+        let start = self.last_start.unwrap_or(coord);
+        let best = self.g_of(&coord).min(self.rhs_of(&coord));
+        (
+            best.saturating_add(Map::estimate(self.options.movement, start, coord))
+                .saturating_add(self.km),
+            best,
+        )
+    }
+
+    fn queue(&mut self, coord: Coordinates, key: Key) {
+        self.queued.insert(coord, key);
+        self.open_set.push(Reverse((key, coord.x, coord.y)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::routing::tests::{get_block_map, get_open_map};
+    use crate::prelude::{Effect, Layer, Mask, Movement, Rect};
+
+    /// Open 10x10 map split by a wall at `x = 5` with the given blocking rects.
+    fn walled_map(walls: Vec<Rect>) -> Map {
+        let mut map = get_open_map();
+        map.push_layer(Layer::new(
+            "walls".into(),
+            vec![Mask::new(
+                "wall".into(),
+                vec![],
+                walls.into_iter().map(Effect::Block).collect(),
+            )],
+            2,
+        ));
+        map
+    }
+
+    fn set_walls(map: &mut Map, walls: Vec<Rect>) {
+        map.layers_mut()[1].masks[0].effects = walls.into_iter().map(Effect::Block).collect();
+    }
+
+    #[test]
+    fn matches_a_star_cost() {
+        let map = get_block_map();
+        for movement in [Movement::Cardinal, Movement::Octile] {
+            let options = PathOptions {
+                movement,
+                ..Default::default()
+            };
+            let start = Coordinates::new(0, 1);
+            let goal = Coordinates::new(4, 3);
+            let mut planner = Planner::new(goal, &options);
+
+            let path = planner.path(&map, start).unwrap();
+            let expected = map.find_path_with(&start, &goal, &options).unwrap();
+            assert_eq!(path.first(), Some(&start));
+            assert_eq!(path.last(), Some(&goal));
+            assert_eq!(map.path_cost(&path), map.path_cost(&expected));
+        }
+    }
+
+    #[test]
+    fn reroutes_when_a_door_closes() {
+        let mut map = walled_map(vec![
+            Rect::from_xywh(5, 0, 1, 5),
+            Rect::from_xywh(5, 6, 1, 4),
+        ]);
+        let goal = Coordinates::new(9, 5);
+        let mut planner = Planner::new(goal, &PathOptions::default());
+
+        let mut position = Coordinates::new(0, 5);
+        for _ in 0..2 {
+            position = planner.next_step(&map, position).unwrap();
+        }
+        assert_eq!(position, Coordinates::new(2, 5));
+
+        // The door at (5, 5) closes and a gap opens at the top of the wall
+        set_walls(&mut map, vec![Rect::from_xywh(5, 1, 1, 9)]);
+
+        let path = planner.path(&map, position).unwrap();
+        assert!(path.contains(&Coordinates::new(5, 0)));
+        assert!(!path.contains(&Coordinates::new(5, 5)));
+        assert_eq!(
+            map.path_cost(&path),
+            map.path_cost(&map.find_path(&position, &goal).unwrap())
+        );
+
+        while position != goal {
+            position = planner.next_step(&map, position).unwrap();
+            assert!(!map.is_blocking_at(&position));
+        }
+    }
+
+    #[test]
+    fn recovers_when_goal_becomes_reachable() {
+        let mut map = walled_map(vec![Rect::from_xywh(5, 0, 1, 10)]);
+        let start = Coordinates::new(0, 0);
+        let goal = Coordinates::new(9, 9);
+        let mut planner = Planner::new(goal, &PathOptions::default());
+
+        assert_eq!(
+            planner.next_step(&map, start),
+            Err(RPGXError::PathNotFround {
+                from: start,
+                to: goal,
+            })
+        );
+
+        set_walls(&mut map, vec![Rect::from_xywh(5, 0, 1, 9)]);
+        assert_eq!(planner.path(&map, start).unwrap().len(), 19);
+    }
+
+    #[test]
+    fn stays_on_goal() {
+        let map = get_open_map();
+        let goal = Coordinates::new(3, 3);
+        let mut planner = Planner::new(goal, &PathOptions::default());
+
+        assert_eq!(planner.next_step(&map, goal), Ok(goal));
+        assert_eq!(planner.path(&map, goal), Ok(vec![goal]));
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+
+use crate::prelude::{Coordinates, Map, PathOptions, Rect, TileInfo};
+
+use super::routing::{Label, search, trace, unscale};
+
+/// Tiles a pawn can reach from an origin within a cost budget, see [`Map::reachable_from`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Reachable {
+    origin: Coordinates,
+    /// Cheapest route to every reached tile.
+    tiles: HashMap<Coordinates, Label<Coordinates>>,
+}
+
+impl Reachable {
+    /// Returns the tile the search started from.
+    pub fn origin(&self) -> Coordinates {
+        self.origin
+    }
+
+    /// Returns true if `target` can be reached.
+    pub fn contains(&self, target: &Coordinates) -> bool {
+        self.tiles.contains_key(target)
+    }
+
+    /// Returns the cost of reaching `target`, in the units of [`PathOptions::max_cost`].
+    ///
+    /// Under [`Movement::Cardinal`](crate::prelude::Movement::Cardinal) this is the
+    /// [`Map::path_cost`] of the path. Diagonal steps count `1.4` times the tile cost
+    /// and the total is rounded up, so `target` is within a `max_cost` budget exactly
+    /// when its cost is at most `max_cost`.
+    pub fn cost_to(&self, target: &Coordinates) -> Option<u32> {
+        self.tiles.get(target).map(|tile| unscale(tile.cost))
+    }
+
+    /// Returns the cost of reaching `target` in the scaled units used by the search.
+    pub(crate) fn scaled_cost_to(&self, target: &Coordinates) -> Option<i32> {
+        self.tiles.get(target).map(|tile| tile.cost)
+    }
+
+    /// Returns the cheapest path from the origin to `target`, both included.
+    pub fn path_to(&self, target: &Coordinates) -> Option<Vec<Coordinates>> {
+        trace(&self.tiles, *target)
+    }
+
+    /// Iterates every reachable tile along with the cost of reaching it, see [`Reachable::cost_to`].
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, u32)> + '_ {
+        self.tiles
+            .iter()
+            .map(|(coord, tile)| (*coord, unscale(tile.cost)))
+    }
+
+    /// Returns the number of reachable tiles, the origin included.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Returns true if not even the origin is reachable.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Returns the reachable tiles merged into rects, ready to be drawn as an overlay.
+    pub fn to_rects(&self) -> Vec<Rect> {
+        Rect::cover(self.tiles.keys().copied())
+    }
+}
+
+impl Map {
+    /// Returns every tile reachable from `start` for a total cost of at most `max_cost`.
+    pub fn reachable_from(&self, start: &Coordinates, max_cost: u32) -> Reachable {
+        self.reachable_from_with(
+            start,
+            &PathOptions {
+                max_cost: Some(max_cost),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Returns every tile reachable from `start` under `options`.
+    ///
+    /// Walkability follows [`Map::find_path_with`]; the budget is set by
+    /// [`PathOptions::max_cost`], [`PathOptions::max_length`] and [`PathOptions::max_nodes`].
+    /// Under [`ActionPolicy::Avoid`](crate::prelude::ActionPolicy::Avoid) action tiles are
+    /// reachable but not crossed.
+    pub fn reachable_from_with(&self, start: &Coordinates, options: &PathOptions) -> Reachable {
+        self.explore(start, options, |_| false).0
+    }
+
+    /// Finds the cheapest tile to reach from `start` whose effects satisfy `predicate`,
+    /// and the path to it. The start tile itself is a candidate.
+    ///
+    /// Returns `None` if no matching tile can be reached.
+    pub fn find_nearest(
+        &self,
+        start: &Coordinates,
+        predicate: impl FnMut(&TileInfo) -> bool,
+    ) -> Option<(Coordinates, Vec<Coordinates>)> {
+        self.find_nearest_with(start, predicate, &PathOptions::default())
+    }
+
+    /// Finds the cheapest tile to reach from `start` whose effects satisfy `predicate`
+    /// under `options`, and the path to it.
+    ///
+    /// Matching tiles may be entered even under
+    /// [`ActionPolicy::Avoid`](crate::prelude::ActionPolicy::Avoid).
+    pub fn find_nearest_with(
+        &self,
+        start: &Coordinates,
+        mut predicate: impl FnMut(&TileInfo) -> bool,
+        options: &PathOptions,
+    ) -> Option<(Coordinates, Vec<Coordinates>)> {
+        let (reachable, found) = self.explore(start, options, &mut predicate);
+        let target = found?;
+        Some((target, reachable.path_to(&target)?))
+    }
+
+    /// Returns the walkable tile closest to `target`, by straight-line distance, among
+    /// those reachable from `start`, and the path to it.
+    ///
+    /// Ties are broken by the cost of the path. Useful when the player clicks a wall or
+    /// an unreachable area: the pawn walks as close as it can instead.
+    pub fn closest_walkable(
+        &self,
+        start: &Coordinates,
+        target: &Coordinates,
+    ) -> Option<(Coordinates, Vec<Coordinates>)> {
+        let reachable = self.reachable_from_with(start, &PathOptions::default());
+        let (closest, _) = reachable.iter().min_by_key(|(coord, cost)| {
+            let dx = coord.x.abs_diff(target.x) as u64;
+            let dy = coord.y.abs_diff(target.y) as u64;
+            (dx * dx + dy * dy, *cost, coord.y, coord.x)
+        })?;
+        Some((closest, reachable.path_to(&closest)?))
+    }
+
+    /// Runs Dijkstra from `start` until the budget of `options` is spent or `stop`
+    /// accepts a settled tile, which is then returned along with everything explored.
+    fn explore(
+        &self,
+        start: &Coordinates,
+        options: &PathOptions,
+        mut stop: impl FnMut(&TileInfo) -> bool,
+    ) -> (Reachable, Option<Coordinates>) {
+        if !self.is_passable(start) {
+            return (
+                Reachable {
+                    origin: *start,
+                    tiles: HashMap::new(),
+                },
+                None,
+            );
+        }
+
+        let search = search(
+            [*start],
+            options,
+            |current, _| {
+                if current != *start && options.avoids(self, &current) {
+                    return Vec::new();
+                }
+                self.tile_edges(current, options, false)
+            },
+            |_| 0,
+            |current| self.tile_at(&current).is_some_and(&mut stop),
+        );
+        let reachable = Reachable {
+            origin: *start,
+            tiles: search.labels,
+        };
+        (reachable, search.goal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::routing::tests::{get_block_map, get_open_map};
+    use crate::prelude::{ActionId, Effect, Layer, Mask, Movement};
+
+    #[test]
+    fn reaches_diamond_within_budget() {
+        let map = get_open_map();
+        let reachable = map.reachable_from(&Coordinates::new(5, 5), 2);
+
+        assert_eq!(reachable.len(), 13);
+        assert_eq!(reachable.cost_to(&Coordinates::new(5, 5)), Some(0));
+        assert_eq!(reachable.cost_to(&Coordinates::new(6, 6)), Some(2));
+        assert!(!reachable.contains(&Coordinates::new(7, 6)));
+    }
+
+    #[test]
+    fn diagonal_costs_match_the_budget() {
+        let map = get_open_map();
+        let options = PathOptions {
+            movement: Movement::Octile,
+            max_cost: Some(3),
+            ..Default::default()
+        };
+        let reachable = map.reachable_from_with(&Coordinates::new(5, 5), &options);
+
+        assert_eq!(reachable.cost_to(&Coordinates::new(6, 6)), Some(2));
+        assert_eq!(reachable.cost_to(&Coordinates::new(7, 7)), Some(3));
+        assert_eq!(reachable.cost_to(&Coordinates::new(7, 6)), Some(3));
+        assert!(!reachable.contains(&Coordinates::new(8, 8)));
+
+        let tighter = map.reachable_from_with(
+            &Coordinates::new(5, 5),
+            &PathOptions {
+                max_cost: Some(2),
+                ..options
+            },
+        );
+        assert!(!tighter.contains(&Coordinates::new(7, 7)));
+        assert!(tighter.iter().all(|(_, cost)| cost <= 2));
+    }
+
+    #[test]
+    fn rects_cover_exactly_the_reachable_tiles() {
+        let map = get_block_map();
+        let reachable = map.reachable_from(&Coordinates::new(0, 0), 4);
+        let rects = reachable.to_rects();
+
+        let mut covered: Vec<_> = rects.iter().flat_map(|rect| rect.iter()).collect();
+        covered.sort_by_key(|c| (c.y, c.x));
+        let mut expected: Vec<_> = reachable.iter().map(|(coord, _)| coord).collect();
+        expected.sort_by_key(|c| (c.y, c.x));
+
+        assert_eq!(covered, expected);
+        assert!(!reachable.contains(&Coordinates::new(1, 1)));
+    }
+
+    #[test]
+    fn pays_for_expensive_terrain() {
+        let mut map = get_open_map();
+        map.push_layer(Layer::new(
+            "terrain".into(),
+            vec![Mask::new(
+                "mud".into(),
+                vec![Rect::from_xywh(1, 0, 1, 10)],
+                vec![Effect::Cost(3)],
+            )],
+            2,
+        ));
+        let reachable = map.reachable_from(&Coordinates::new(0, 0), 3);
+
+        assert_eq!(reachable.cost_to(&Coordinates::new(1, 0)), Some(3));
+        assert!(!reachable.contains(&Coordinates::new(2, 0)));
+        assert_eq!(
+            reachable.path_to(&Coordinates::new(0, 3)),
+            Some(vec![
+                Coordinates::new(0, 0),
+                Coordinates::new(0, 1),
+                Coordinates::new(0, 2),
+                Coordinates::new(0, 3),
+            ])
+        );
+    }
+
+    #[test]
+    fn blocked_origin_reaches_nothing() {
+        let map = get_block_map();
+        assert!(map.reachable_from(&Coordinates::new(1, 1), 5).is_empty());
+    }
+
+    #[test]
+    fn finds_nearest_tile_with_action() {
+        let mut map = get_block_map();
+        map.push_layer(Layer::new(
+            "actions".into(),
+            vec![
+                Mask::new(
+                    "far_chest".into(),
+                    vec![Rect::from_xywh(9, 9, 1, 1)],
+                    vec![Effect::Action(ActionId(7))],
+                ),
+                Mask::new(
+                    "near_chest".into(),
+                    vec![Rect::from_xywh(3, 0, 1, 1)],
+                    vec![Effect::Action(ActionId(7))],
+                ),
+                Mask::new(
+                    "lever".into(),
+                    vec![Rect::from_xywh(1, 0, 1, 1)],
+                    vec![Effect::Action(ActionId(2))],
+                ),
+            ],
+            2,
+        ));
+
+        let (target, path) = map
+            .find_nearest(&Coordinates::new(0, 2), |tile| {
+                tile.actions.contains(&ActionId(7))
+            })
+            .unwrap();
+        assert_eq!(target, Coordinates::new(3, 0));
+        assert_eq!(path.first(), Some(&Coordinates::new(0, 2)));
+        assert_eq!(path.last(), Some(&target));
+        assert_eq!(path.len(), 6);
+
+        assert!(
+            map.find_nearest(&Coordinates::new(0, 2), |tile| tile
+                .actions
+                .contains(&ActionId(99)))
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn walks_as_close_as_possible_to_blocked_tile() {
+        let map = get_block_map();
+        let (closest, path) = map
+            .closest_walkable(&Coordinates::new(0, 0), &Coordinates::new(2, 2))
+            .unwrap();
+
+        assert_eq!(closest, Coordinates::new(3, 2));
+        assert_eq!(path.last(), Some(&closest));
+        assert!(!map.is_blocking_at(&closest));
+    }
+}
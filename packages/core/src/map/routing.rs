@@ -1,36 +1,325 @@
-use std::collections::{BinaryHeap, HashMap};
+use std::{
+    collections::{BinaryHeap, HashMap, HashSet},
+    hash::Hash,
+};
 
 use crate::{
-    prelude::{Coordinates, Map},
+    errors::RPGXError,
+    prelude::{Coordinates, Direction, Map, Rect},
     traits::Grid,
 };
 
+/// Cost of a straight step, scaled so diagonal steps can be priced in integers.
+pub(crate) const STRAIGHT_COST: i32 = 10;
+/// Cost of a diagonal step, approximating `STRAIGHT_COST * √2`.
+pub(crate) const DIAGONAL_COST: i32 = 14;
+
+/// Converts a scaled search cost back to the units of [`PathOptions::max_cost`],
+/// rounding up so that a cost fits a budget exactly when the search keeps it.
+pub(crate) fn unscale(cost: i32) -> u32 {
+    (cost.max(0) as u32).div_ceil(STRAIGHT_COST as u32)
+}
+
+/// Neighborhood expanded by the pathfinder.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Movement {
+    /// Up, down, left and right steps only.
+    #[default]
+    Cardinal,
+    /// Cardinal and diagonal steps. Diagonal steps may not cut the corner of a blocking tile.
+    Octile,
+}
+
+impl Movement {
+    /// Returns the directions a pawn may step in under this movement.
+    pub fn directions(&self) -> &'static [Direction] {
+        match self {
+            Movement::Cardinal => &Direction::CARDINAL,
+            Movement::Octile => &Direction::ALL,
+        }
+    }
+}
+
+/// Whether routes may step on tiles that carry actions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ActionPolicy {
+    /// Action tiles are walkable like any other tile.
+    #[default]
+    Cross,
+    /// Action tiles are never stepped on, except as the goal.
+    Avoid,
+}
+
+/// How the search picks between routes of equal cost.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TieBreak {
+    /// Any of the cheapest routes may be returned.
+    #[default]
+    Any,
+    /// Prefers the cheapest route with the fewest changes of direction.
+    Straight,
+}
+
+/// Options for a route query, see [`Map::find_path_with`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PathOptions {
+    /// Neighborhood expanded by the search.
+    pub movement: Movement,
+    /// Restricts the search to tiles inside this rect, on top of the map's own tiles.
+    pub bounds: Option<Rect>,
+    /// Maximum number of nodes the search may expand before giving up.
+    pub max_nodes: Option<usize>,
+    /// Maximum total cost of a path: the sum of the [`Map::get_cost_at`] of the tiles
+    /// it enters, with diagonal steps counting `1.4` times the tile cost.
+    ///
+    /// Under [`Movement::Cardinal`] this is the [`Map::path_cost`] of the path.
+    pub max_cost: Option<u32>,
+    /// Maximum number of steps in the path, not counting the starting tile.
+    ///
+    /// [`Map::find_path_with`] returns the cheapest path within this length. Searches
+    /// that keep one route per tile, such as [`Map::reachable_from_with`] and
+    /// [`DistanceField`](crate::prelude::DistanceField), keep the cheapest one: on
+    /// weighted maps a tile only reachable in time by a costlier route is left out.
+    pub max_length: Option<usize>,
+    /// Whether tiles with actions may be crossed.
+    pub actions: ActionPolicy,
+    /// Tiles the path may not enter, such as tiles occupied by other pawns.
+    pub forbidden: HashSet<Coordinates>,
+    /// Policy for choosing between routes of equal cost.
+    pub tie_break: TieBreak,
+}
+
+impl PathOptions {
+    /// Returns true if `target` lies inside the optional search bounds.
+    pub(crate) fn in_bounds(&self, target: &Coordinates) -> bool {
+        self.bounds.is_none_or(|bounds| bounds.contains(target))
+    }
+
+    /// Returns `max_cost` in the scaled units used by the search.
+    pub(crate) fn cost_limit(&self) -> i32 {
+        self.max_cost
+            .map_or(i32::MAX, |cost| (cost as i32).saturating_mul(STRAIGHT_COST))
+    }
+
+    /// Returns true if the action policy keeps the search off `target`.
+    pub(crate) fn avoids(&self, map: &Map, target: &Coordinates) -> bool {
+        self.actions == ActionPolicy::Avoid
+            && map
+                .tile_at(target)
+                .is_some_and(|tile| !tile.actions.is_empty())
+    }
+}
+
+/// State of a [`search`]: a tile, possibly along with extra search data.
+pub(crate) trait SearchState: Copy + Eq + Hash {
+    /// Returns the tile the state stands on.
+    fn tile(&self) -> Coordinates;
+}
+
+impl SearchState for Coordinates {
+    fn tile(&self) -> Coordinates {
+        *self
+    }
+}
+
+/// Tile along with the steps taken to reach it, for searches bound by `max_length`.
+impl SearchState for (Coordinates, usize) {
+    fn tile(&self) -> Coordinates {
+        self.0
+    }
+}
+
+/// Best route found by a [`search`] to one of its states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Label<S> {
+    /// Scaled cost of the route.
+    pub(crate) cost: i32,
+    /// Changes of direction along the route, only counted for [`TieBreak::Straight`].
+    pub(crate) turns: u32,
+    /// Steps taken since the start of the route.
+    pub(crate) steps: usize,
+    /// Direction of the last step, if it was taken on the map grid.
+    pub(crate) heading: Option<Direction>,
+    /// State the route came from, `None` for a start.
+    pub(crate) previous: Option<S>,
+}
+
+/// Step a [`search`] may take from a state onto `to`.
+pub(crate) struct Edge<S> {
+    pub(crate) to: S,
+    /// Scaled cost of the step.
+    pub(crate) cost: i32,
+    pub(crate) direction: Option<Direction>,
+}
+
+/// Routes found by a [`search`], and the goal it stopped at, if any.
+pub(crate) struct Search<S> {
+    pub(crate) labels: HashMap<S, Label<S>>,
+    pub(crate) goal: Option<S>,
+}
+
 /// A node in the A* search graph.
 #[derive(Eq, PartialEq)]
-struct Node {
-    position: Coordinates,
+struct Node<S> {
+    state: S,
     cost: i32,     // g(n): Cost from start to current node
     estimate: i32, // f(n): Estimated total cost (g + h)
+    turns: u32,    // Changes of direction so far, only counted for TieBreak::Straight
 }
 
 // Implement ordering for BinaryHeap as a min-heap by `estimate` (f)
-impl Ord for Node {
+impl<S: SearchState> Ord for Node<S> {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        let (position, other_position) = (self.state.tile(), other.state.tile());
         // Reverse the order so the node with the *smallest* estimate is popped first
         other
             .estimate
             .cmp(&self.estimate)
-            .then_with(|| self.position.x.cmp(&other.position.x))
-            .then_with(|| self.position.y.cmp(&other.position.y))
+            .then_with(|| other.turns.cmp(&self.turns))
+            .then_with(|| position.x.cmp(&other_position.x))
+            .then_with(|| position.y.cmp(&other_position.y))
     }
 }
 
-impl PartialOrd for Node {
+impl<S: SearchState> PartialOrd for Node<S> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
+/// Best-first search shared by every route query: A* guided by `heuristic`, or
+/// Dijkstra when the heuristic is zero.
+///
+/// Starting from every state of `starts`, the search settles states cheapest first
+/// and follows the [`Edge`]s `edges` lists for each of them. It stops at the first
+/// settled state accepted by `goal`, once every reachable state is settled, or when
+/// the `max_nodes`, `max_cost` and `max_length` budgets of `options` run out.
+/// Ties are broken as set by [`PathOptions::tie_break`].
+pub(crate) fn search<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    options: &PathOptions,
+    mut edges: impl FnMut(S, &Label<S>) -> I,
+    mut heuristic: impl FnMut(S) -> i32,
+    mut goal: impl FnMut(S) -> bool,
+) -> Search<S>
+where
+    S: SearchState,
+    I: IntoIterator<Item = Edge<S>>,
+{
+    let max_cost = options.cost_limit();
+    let mut labels = HashMap::new();
+    let mut open_set = BinaryHeap::new();
+    for start in starts {
+        labels.insert(
+            start,
+            Label {
+                cost: 0,
+                turns: 0,
+                steps: 0,
+                heading: None,
+                previous: None,
+            },
+        );
+        open_set.push(Node {
+            state: start,
+            cost: 0,
+            estimate: heuristic(start),
+            turns: 0,
+        });
+    }
+
+    let mut expanded = 0;
+    while let Some(current_node) = open_set.pop() {
+        let current = current_node.state;
+        let label = labels[&current];
+
+        // Skip stale heap entries superseded by a cheaper route
+        if (current_node.cost, current_node.turns) > (label.cost, label.turns) {
+            continue;
+        }
+
+        if goal(current) {
+            return Search {
+                labels,
+                goal: Some(current),
+            };
+        }
+
+        expanded += 1;
+        if options
+            .max_nodes
+            .is_some_and(|max_nodes| expanded > max_nodes)
+        {
+            break;
+        }
+
+        if options
+            .max_length
+            .is_some_and(|max_length| label.steps >= max_length)
+        {
+            continue;
+        }
+
+        for edge in edges(current, &label) {
+            let cost = label.cost.saturating_add(edge.cost);
+            if cost > max_cost {
+                continue;
+            }
+
+            let turns = match options.tie_break {
+                TieBreak::Any => 0,
+                TieBreak::Straight => {
+                    label.turns
+                        + label
+                            .heading
+                            .is_some_and(|heading| Some(heading) != edge.direction)
+                            as u32
+                }
+            };
+
+            if labels
+                .get(&edge.to)
+                .is_none_or(|best| (cost, turns) < (best.cost, best.turns))
+            {
+                labels.insert(
+                    edge.to,
+                    Label {
+                        cost,
+                        turns,
+                        steps: label.steps + 1,
+                        heading: edge.direction,
+                        previous: Some(current),
+                    },
+                );
+                open_set.push(Node {
+                    state: edge.to,
+                    cost,
+                    estimate: cost.saturating_add(heuristic(edge.to)),
+                    turns,
+                });
+            }
+        }
+    }
+
+    Search { labels, goal: None }
+}
+
+/// Returns the states of the route `labels` hold to `state`, from its start to `state`.
+pub(crate) fn trace<S: SearchState>(labels: &HashMap<S, Label<S>>, state: S) -> Option<Vec<S>> {
+    let mut current = state;
+    let mut path = vec![current];
+    while let Some(previous) = labels.get(&current)?.previous {
+        path.push(previous);
+        current = previous;
+    }
+    path.reverse();
+    Some(path)
+}
+
 impl Map {
     /// Manhattan distance heuristic used in A* pathfinding.
     ///
@@ -46,91 +335,169 @@ impl Map {
         if distance > 0 { distance - 1 } else { 0 }
     }
 
-    /// Finds a path from `start` to `goal` coordinates using A* pathfinding.
+    /// Octile distance heuristic used in diagonal A* pathfinding.
     ///
-    /// - Uses 4-directional movement (up, down, left, right).
-    /// - Skips any tiles that are blocking.
-    /// - Returns `Some(path)` if a path is found, or `None` if unreachable.
-    pub fn find_path(&self, start: &Coordinates, goal: &Coordinates) -> Option<Vec<Coordinates>> {
-        let mut open_set = BinaryHeap::new();
-        open_set.push(Node {
-            position: *start,
-            cost: 0,
-            estimate: Self::heuristic(*start, *goal) as i32,
-        });
+    /// Diagonal steps are priced `14` and straight steps `10`, the same scale used
+    /// internally by [`Map::find_path_with`] under [`Movement::Octile`].
+    pub fn octile_heuristic(a: Coordinates, b: Coordinates) -> u32 {
+        let dx = a.x.abs_diff(b.x);
+        let dy = a.y.abs_diff(b.y);
+        let (long, short) = (dx.max(dy), dx.min(dy));
+        STRAIGHT_COST as u32 * (long - short) + DIAGONAL_COST as u32 * short
+    }
 
-        let mut came_from: HashMap<Coordinates, Coordinates> = HashMap::new();
-        let mut g_score: HashMap<Coordinates, i32> = HashMap::new();
-        g_score.insert(*start, 0);
+    /// Scaled heuristic for the given movement.
+    pub(crate) fn estimate(movement: Movement, a: Coordinates, b: Coordinates) -> i32 {
+        match movement {
+            Movement::Cardinal => Self::heuristic(a, b) as i32 * STRAIGHT_COST,
+            Movement::Octile => Self::octile_heuristic(a, b) as i32,
+        }
+    }
 
-        while let Some(current_node) = open_set.pop() {
-            let current = current_node.position;
+    /// Returns the total movement cost of walking `path`, excluding its starting tile.
+    pub fn path_cost(&self, path: &[Coordinates]) -> u32 {
+        path.iter().skip(1).map(|step| self.get_cost_at(step)).sum()
+    }
 
-            if current == *goal {
-                // Reconstruct path
-                let mut path = vec![current];
-                let mut cur = current;
-                while let Some(prev) = came_from.get(&cur) {
-                    cur = *prev;
-                    path.push(cur);
-                }
-                path.reverse();
-                return Some(path);
-            }
+    /// Scaled cost of stepping in `direction` onto `target`.
+    pub(crate) fn step_cost(&self, direction: Direction, target: &Coordinates) -> i32 {
+        let step = if direction.is_diagonal() {
+            DIAGONAL_COST
+        } else {
+            STRAIGHT_COST
+        };
+        step * self.get_cost_at(target) as i32
+    }
 
-            // Generate 4-directional neighbors
-            let neighbors = [
-                Some(Coordinates {
-                    x: current.x + 1,
-                    y: current.y,
-                }),
-                Some(Coordinates {
-                    x: current.x,
-                    y: current.y + 1,
-                }),
-                current
-                    .x
-                    .checked_sub(1)
-                    .map(|x| Coordinates { x, y: current.y }),
-                current
-                    .y
-                    .checked_sub(1)
-                    .map(|y| Coordinates { x: current.x, y }),
-            ]
-            .iter()
-            .filter_map(|opt| *opt) // unwrap Option<Coordinates> safely
-            .collect::<Vec<_>>();
+    /// Returns true if a diagonal step from `from` in `direction` would squeeze past
+    /// the corner of a blocking tile. Always false for cardinal directions.
+    pub fn cuts_corner(&self, from: &Coordinates, direction: Direction) -> bool {
+        direction
+            .components()
+            .is_some_and(|(vertical, horizontal)| {
+                [vertical, horizontal].iter().any(|side| {
+                    (*from + side.to_delta()).is_none_or(|side| !self.is_passable(&side))
+                })
+            })
+    }
 
-            for neighbor in neighbors {
-                // Skip if blocked
-                if self.contains(&neighbor) && self.is_blocking_at(&neighbor) {
-                    continue;
+    /// Whether routing may enter `target`: it must be a map tile and not blocking.
+    pub(crate) fn is_passable(&self, target: &Coordinates) -> bool {
+        self.contains(target) && !self.is_blocking_at(target)
+    }
+
+    /// Returns the steps a tile search may take from `current`, onto each walkable
+    /// neighbour.
+    ///
+    /// Searches running `backwards` step from each neighbour onto `current` instead:
+    /// the opposite step follows the same corner rules and is priced by `current`.
+    pub(crate) fn tile_edges(
+        &self,
+        current: Coordinates,
+        options: &PathOptions,
+        backwards: bool,
+    ) -> Vec<Edge<Coordinates>> {
+        options
+            .movement
+            .directions()
+            .iter()
+            .filter_map(|direction| {
+                let next = (current + direction.to_delta())?;
+                if !self.is_passable(&next)
+                    || !options.in_bounds(&next)
+                    || options.forbidden.contains(&next)
+                    || self.cuts_corner(&current, *direction)
+                {
+                    return None;
                 }
+                let entered = if backwards { current } else { next };
+                Some(Edge {
+                    to: next,
+                    cost: self.step_cost(*direction, &entered),
+                    direction: Some(*direction),
+                })
+            })
+            .collect()
+    }
+
+    /// Finds a path from `start` to `goal` coordinates using A* pathfinding.
+    ///
+    /// - Uses 4-directional movement (up, down, left, right).
+    /// - Only walks on map tiles and skips any tiles that are blocking.
+    /// - Entering a tile costs its [`Map::get_cost_at`], so cheaper terrain is preferred.
+    /// - Returns `Some(path)` if a path is found, or `None` if unreachable.
+    pub fn find_path(&self, start: &Coordinates, goal: &Coordinates) -> Option<Vec<Coordinates>> {
+        self.find_path_with(start, goal, &PathOptions::default())
+            .ok()
+    }
 
-                let tentative_g_score =
-                    g_score.get(&current).unwrap_or(&i32::MAX).saturating_add(1);
+    /// Finds a path from `start` to `goal` using A* pathfinding configured by `options`.
+    ///
+    /// Under [`Movement::Octile`] diagonal steps cost `√2` times the tile cost and the
+    /// search is guided by [`Map::octile_heuristic`].
+    ///
+    /// Tiles in [`PathOptions::forbidden`] are treated as blocking and, under
+    /// [`ActionPolicy::Avoid`], so are action tiles other than the goal.
+    /// [`PathOptions::max_length`] prunes routes longer than the given number of steps:
+    /// the cheapest path within that length is returned, even if a cheaper but longer
+    /// one exists.
+    ///
+    /// The search never leaves the map tiles (or [`PathOptions::bounds`]), so it
+    /// always terminates. [`PathOptions::max_nodes`] and [`PathOptions::max_cost`]
+    /// cut it short on large maps.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RPGXError::PathNotFround`] if the goal cannot be reached within
+    /// the bounds and budget.
+    pub fn find_path_with(
+        &self,
+        start: &Coordinates,
+        goal: &Coordinates,
+        options: &PathOptions,
+    ) -> Result<Vec<Coordinates>, RPGXError> {
+        let movement = options.movement;
+        // Search states are tiles, or (tile, steps) pairs under `max_length`: a cheap
+        // route with many steps must not hide a costlier one that fits the length.
+        let state = |position: Coordinates, steps: usize| {
+            (position, options.max_length.map_or(0, |_| steps))
+        };
 
-                if tentative_g_score < *g_score.get(&neighbor).unwrap_or(&i32::MAX) {
-                    came_from.insert(neighbor, current);
-                    g_score.insert(neighbor, tentative_g_score);
+        let search = search(
+            [state(*start, 0)],
+            options,
+            |(current, _), label| {
+                self.tile_edges(current, options, false)
+                    .into_iter()
+                    .filter(|edge| edge.to == *goal || !options.avoids(self, &edge.to))
+                    .map(|edge| Edge {
+                        to: state(edge.to, label.steps + 1),
+                        cost: edge.cost,
+                        direction: edge.direction,
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |(position, _)| Self::estimate(movement, position, *goal),
+            |(position, _)| position == *goal,
+        );
 
-                    open_set.push(Node {
-                        position: neighbor,
-                        cost: tentative_g_score,
-                        estimate: tentative_g_score
-                            .saturating_add(Self::heuristic(neighbor, *goal) as i32),
-                    });
-                }
-            }
+        if let Some(path) = search
+            .goal
+            .and_then(|reached| trace(&search.labels, reached))
+        {
+            return Ok(path.into_iter().map(|(position, _)| position).collect());
         }
 
-        None
+        Err(RPGXError::PathNotFround {
+            from: *start,
+            to: *goal,
+        })
     }
 }
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::prelude::{Effect, Layer, Mask, Rect, Shape};
+    use crate::prelude::{ActionId, Effect, Layer, Mask, Rect, Shape};
 
     pub fn get_open_map() -> Map {
         Map::new(
@@ -246,6 +613,106 @@ pub mod tests {
         assert_eq!(steps.last().unwrap(), &Coordinates::new(9, 9));
     }
 
+    #[test]
+    pub fn prefers_cheaper_terrain() {
+        let mut map = get_open_map();
+        // A swamp on the straight line and a road detouring around it.
+        map.push_layer(Layer::new(
+            "terrain".into(),
+            vec![
+                Mask::new(
+                    "swamp".into(),
+                    vec![Rect::from_xywh(2, 0, 3, 1)],
+                    vec![Effect::Cost(10)],
+                ),
+                Mask::new(
+                    "road".into(),
+                    vec![Rect::from_xywh(1, 1, 5, 1)],
+                    vec![Effect::Cost(1)],
+                ),
+            ],
+            2,
+        ));
+
+        let path = map
+            .find_path(&Coordinates::new(0, 0), &Coordinates::new(6, 0))
+            .unwrap();
+
+        assert!(
+            path.iter()
+                .all(|step| step.y != 0 || !(2..5).contains(&step.x))
+        );
+        assert_eq!(map.path_cost(&path), 8);
+    }
+
+    #[test]
+    pub fn crosses_expensive_terrain_when_cheaper() {
+        let mut map = get_open_map();
+        map.push_layer(Layer::new(
+            "terrain".into(),
+            vec![Mask::new(
+                "mud".into(),
+                vec![Rect::from_xywh(3, 0, 1, 10)],
+                vec![Effect::Cost(3)],
+            )],
+            2,
+        ));
+
+        let path = map
+            .find_path(&Coordinates::new(0, 0), &Coordinates::new(6, 0))
+            .unwrap();
+
+        assert_eq!(path.len(), 7);
+        assert_eq!(map.path_cost(&path), 8);
+    }
+
+    #[test]
+    pub fn octile_movement_walks_diagonally() {
+        let map = get_open_map();
+        let options = PathOptions {
+            movement: Movement::Octile,
+            ..Default::default()
+        };
+        let path = map
+            .find_path_with(&Coordinates::new(0, 0), &Coordinates::new(5, 5), &options)
+            .unwrap();
+
+        assert_eq!(path.len(), 6);
+        assert_eq!(path.last(), Some(&Coordinates::new(5, 5)));
+    }
+
+    #[test]
+    pub fn octile_movement_does_not_cut_corners() {
+        let map = get_block_map();
+        let options = PathOptions {
+            movement: Movement::Octile,
+            ..Default::default()
+        };
+        // The block covers (1,1)..=(2,2): stepping (3,2) -> (2,3) squeezes past (2,2).
+        assert!(map.cuts_corner(&Coordinates::new(3, 2), Direction::DownLeft));
+        assert!(!map.cuts_corner(&Coordinates::new(3, 3), Direction::UpRight));
+        assert!(!map.cuts_corner(&Coordinates::new(3, 2), Direction::Down));
+
+        let path = map
+            .find_path_with(&Coordinates::new(3, 0), &Coordinates::new(0, 3), &options)
+            .unwrap();
+        assert_eq!(path.last(), Some(&Coordinates::new(0, 3)));
+        for pair in path.windows(2) {
+            let direction = Direction::from_delta(&(pair[1].to_delta() - pair[0].to_delta()));
+            assert!(!map.cuts_corner(&pair[0], direction.unwrap()));
+            assert!(!map.is_blocking_at(&pair[1]));
+        }
+    }
+
+    #[test]
+    pub fn octile_heuristic_prices_diagonals() {
+        let a = Coordinates::new(0, 0);
+        assert_eq!(Map::octile_heuristic(a, a), 0);
+        assert_eq!(Map::octile_heuristic(a, Coordinates::new(3, 0)), 30);
+        assert_eq!(Map::octile_heuristic(a, Coordinates::new(3, 3)), 42);
+        assert_eq!(Map::octile_heuristic(a, Coordinates::new(5, 2)), 58);
+    }
+
     #[test]
     pub fn heuristic_returns_zero_for_same_point() {
         let a = Coordinates::new(2, 2);
@@ -258,4 +725,241 @@ pub mod tests {
         let b = Coordinates::new(3, 4); // distance = 7
         assert_eq!(Map::heuristic(a, b), 6);
     }
+
+    #[test]
+    pub fn does_not_leave_the_map() {
+        let map = get_open_map();
+        let result = map.find_path_with(
+            &Coordinates::new(0, 0),
+            &Coordinates::new(12, 0),
+            &PathOptions::default(),
+        );
+
+        assert_eq!(
+            result,
+            Err(RPGXError::PathNotFround {
+                from: Coordinates::new(0, 0),
+                to: Coordinates::new(12, 0),
+            })
+        );
+    }
+
+    #[test]
+    pub fn gives_up_on_walled_in_goal() {
+        let mut map = get_open_map();
+        map.push_layer(Layer::new(
+            "walls".into(),
+            vec![Mask::new(
+                "wall".into(),
+                vec![],
+                vec![Effect::Block(Rect::from_xywh(5, 0, 1, 10))],
+            )],
+            2,
+        ));
+
+        let path = map.find_path(&Coordinates::new(0, 0), &Coordinates::new(9, 0));
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    pub fn respects_search_bounds() {
+        let map = get_block_map();
+        let options = PathOptions {
+            bounds: Some(Rect::from_xywh(0, 0, 10, 3)),
+            ..Default::default()
+        };
+
+        // The only detour around the block within the bounds is row 0.
+        let path = map
+            .find_path_with(&Coordinates::new(0, 2), &Coordinates::new(4, 2), &options)
+            .unwrap();
+        assert!(path.iter().all(|step| step.y < 3));
+
+        let options = PathOptions {
+            bounds: Some(Rect::from_xywh(0, 1, 10, 2)),
+            ..Default::default()
+        };
+        assert!(
+            map.find_path_with(&Coordinates::new(0, 2), &Coordinates::new(4, 2), &options)
+                .is_err()
+        );
+    }
+
+    #[test]
+    pub fn stops_at_node_budget() {
+        let map = get_open_map();
+        let start = Coordinates::new(0, 0);
+        let goal = Coordinates::new(9, 9);
+
+        let options = PathOptions {
+            max_nodes: Some(5),
+            ..Default::default()
+        };
+        assert!(map.find_path_with(&start, &goal, &options).is_err());
+
+        let options = PathOptions {
+            max_nodes: Some(100),
+            ..Default::default()
+        };
+        assert!(map.find_path_with(&start, &goal, &options).is_ok());
+    }
+
+    #[test]
+    pub fn stops_at_cost_budget() {
+        let map = get_open_map();
+        let start = Coordinates::new(0, 0);
+        let goal = Coordinates::new(6, 0);
+
+        let options = PathOptions {
+            max_cost: Some(5),
+            ..Default::default()
+        };
+        assert!(map.find_path_with(&start, &goal, &options).is_err());
+
+        let options = PathOptions {
+            max_cost: Some(6),
+            ..Default::default()
+        };
+        assert_eq!(
+            map.find_path_with(&start, &goal, &options).unwrap().len(),
+            7
+        );
+    }
+
+    fn count_turns(path: &[Coordinates]) -> usize {
+        let headings: Vec<_> = path
+            .windows(2)
+            .map(|pair| {
+                (
+                    pair[1].x as i32 - pair[0].x as i32,
+                    pair[1].y as i32 - pair[0].y as i32,
+                )
+            })
+            .collect();
+        headings
+            .windows(2)
+            .filter(|pair| pair[0] != pair[1])
+            .count()
+    }
+
+    #[test]
+    pub fn max_length_keeps_costlier_short_routes() {
+        // A swamp on the short route, open ground on a longer one
+        let map = Map::new(
+            "test".into(),
+            vec![Layer::new(
+                "test".into(),
+                vec![
+                    Mask::new("ground".into(), vec![Rect::from_xywh(0, 0, 5, 2)], vec![]),
+                    Mask::new(
+                        "swamp".into(),
+                        vec![Rect::from_xywh(1, 0, 1, 1)],
+                        vec![Effect::Cost(5)],
+                    ),
+                ],
+                1,
+            )],
+            Coordinates::default(),
+        );
+        let start = Coordinates::new(0, 0);
+        let goal = Coordinates::new(4, 0);
+        assert_eq!(map.find_path(&start, &goal).unwrap().len(), 7);
+
+        // The detour reaches (2, 0) cheaper, but too late to finish within 4 steps
+        let options = PathOptions {
+            max_length: Some(4),
+            ..Default::default()
+        };
+        let path = map.find_path_with(&start, &goal, &options).unwrap();
+        assert_eq!(
+            path,
+            (0..5).map(|x| Coordinates::new(x, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(map.path_cost(&path), 8);
+    }
+
+    #[test]
+    pub fn respects_max_length() {
+        let map = get_block_map();
+        let start = Coordinates::new(0, 1);
+        let goal = Coordinates::new(3, 1);
+
+        // Walking around the block takes 5 steps.
+        let options = PathOptions {
+            max_length: Some(4),
+            ..Default::default()
+        };
+        assert!(map.find_path_with(&start, &goal, &options).is_err());
+
+        let options = PathOptions {
+            max_length: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(
+            map.find_path_with(&start, &goal, &options).unwrap().len(),
+            6
+        );
+    }
+
+    #[test]
+    pub fn avoids_forbidden_tiles() {
+        let map = get_open_map();
+        let options = PathOptions {
+            forbidden: HashSet::from([Coordinates::new(2, 0), Coordinates::new(2, 1)]),
+            ..Default::default()
+        };
+
+        let path = map
+            .find_path_with(&Coordinates::new(0, 0), &Coordinates::new(4, 0), &options)
+            .unwrap();
+        assert!(path.iter().all(|step| !options.forbidden.contains(step)));
+        assert_eq!(path.len(), 9);
+
+        assert!(
+            map.find_path_with(&Coordinates::new(0, 0), &Coordinates::new(2, 0), &options)
+                .is_err()
+        );
+    }
+
+    #[test]
+    pub fn avoids_action_tiles_except_the_goal() {
+        let mut map = get_open_map();
+        map.push_layer(Layer::new(
+            "actions".into(),
+            vec![Mask::new(
+                "traps".into(),
+                vec![Rect::from_xywh(2, 0, 1, 1), Rect::from_xywh(5, 0, 1, 1)],
+                vec![Effect::Action(ActionId(1))],
+            )],
+            2,
+        ));
+
+        let start = Coordinates::new(0, 0);
+        let goal = Coordinates::new(5, 0);
+        let crossing = map.find_path(&start, &goal).unwrap();
+        assert!(crossing.contains(&Coordinates::new(2, 0)));
+
+        let options = PathOptions {
+            actions: ActionPolicy::Avoid,
+            ..Default::default()
+        };
+        let avoiding = map.find_path_with(&start, &goal, &options).unwrap();
+        assert!(!avoiding.contains(&Coordinates::new(2, 0)));
+        assert_eq!(avoiding.last(), Some(&goal));
+    }
+
+    #[test]
+    pub fn straight_tie_break_minimizes_turns() {
+        let map = get_open_map();
+        let options = PathOptions {
+            tie_break: TieBreak::Straight,
+            ..Default::default()
+        };
+        let path = map
+            .find_path_with(&Coordinates::new(0, 0), &Coordinates::new(5, 5), &options)
+            .unwrap();
+
+        assert_eq!(path.len(), 11);
+        assert_eq!(count_turns(&path), 1);
+    }
 }
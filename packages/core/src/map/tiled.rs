@@ -0,0 +1,1197 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use serde::{Deserialize, de::IgnoredAny};
+use serde_json::{Value, json};
+
+use crate::{
+    prelude::{
+        Coordinates, Effect, Layer, Map, Mask, NAMESPACE_SEPARATOR, Rect, ResourceKind, Resources,
+        Shape, TextureId, Trigger, TriggerOn, namespaced,
+    },
+    traits::Shaped,
+};
+
+/// Flip and rotation flags stored in the high bits of Tiled gids.
+const GID_FLAGS: u32 = 0xF000_0000;
+
+#[doc = include_str!("../../docs/tiled.md")]
+/// File formats of the Tiled editor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TiledFormat {
+    /// XML maps, `.tmx`.
+    Tmx,
+    /// JSON maps, `.tmj` or `.json`.
+    Tmj,
+}
+
+impl TiledFormat {
+    /// Returns the format of a file from its extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "tmx" => Some(TiledFormat::Tmx),
+            "tmj" | "json" => Some(TiledFormat::Tmj),
+            _ => None,
+        }
+    }
+}
+
+/// Errors raised while importing or exporting Tiled maps.
+///
+/// `layer` is the name of the Tiled or RPGX layer holding the faulty item.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TiledError {
+    /// The file could not be read or written.
+    Io { path: String, message: String },
+    /// The extension of the file is not `.tmx`, `.tmj` or `.json`.
+    UnknownFormat(String),
+    /// The source is not a valid TMX or TMJ map.
+    Parse(String),
+    /// The map uses a Tiled feature that can't be imported, such as infinite maps.
+    Unsupported(String),
+    /// A tile of a layer belongs to no tileset.
+    UnknownGid { gid: u32, layer: String },
+    /// A tile or property key is not in the library.
+    UnknownKey {
+        kind: ResourceKind,
+        key: String,
+        layer: String,
+    },
+    /// An id of the map is not in the library, so it can't be exported.
+    UnknownId {
+        kind: ResourceKind,
+        id: u32,
+        layer: String,
+    },
+    /// A texture key is not `tileset/index`, so it can't be exported as a tile.
+    InvalidTextureKey { key: String, layer: String },
+}
+
+impl fmt::Display for TiledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TiledError::Io { path, message } => write!(f, "{path}: {message}"),
+            TiledError::UnknownFormat(path) => {
+                write!(
+                    f,
+                    "{path}: unknown Tiled format, expected .tmx, .tmj or .json"
+                )
+            }
+            TiledError::Parse(message) => write!(f, "{message}"),
+            TiledError::Unsupported(feature) => write!(f, "unsupported Tiled feature: {feature}"),
+            TiledError::UnknownGid { gid, layer } => {
+                write!(f, "layer \"{layer}\": gid {gid} belongs to no tileset")
+            }
+            TiledError::UnknownKey { kind, key, layer } => {
+                write!(f, "layer \"{layer}\": unknown {kind:?} key \"{key}\"")
+            }
+            TiledError::UnknownId { kind, id, layer } => {
+                write!(f, "layer \"{layer}\": unknown {kind:?} id {id}")
+            }
+            TiledError::InvalidTextureKey { key, layer } => write!(
+                f,
+                "layer \"{layer}\": texture key \"{key}\" is not \"tileset{NAMESPACE_SEPARATOR}index\""
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TiledError {}
+
+/// The parts of a Tiled map RPGX reads, shared by TMX and TMJ.
+#[derive(Debug, Deserialize)]
+struct TiledMap {
+    #[serde(default)]
+    orientation: String,
+    #[serde(default)]
+    infinite: bool,
+    tilewidth: u32,
+    tileheight: u32,
+    #[serde(default)]
+    layers: Vec<TiledLayer>,
+    #[serde(default)]
+    tilesets: Vec<TiledTileset>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum TiledLayer {
+    #[serde(rename = "tilelayer")]
+    Tiles {
+        name: String,
+        width: u32,
+        #[serde(default)]
+        encoding: Option<String>,
+        data: TileData,
+    },
+    #[serde(rename = "objectgroup")]
+    Objects {
+        name: String,
+        #[serde(default)]
+        objects: Vec<TiledObject>,
+    },
+    /// Image and group layers, which are skipped.
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TileData {
+    Gids(Vec<u32>),
+    /// Base64 data, which isn't read.
+    Encoded(IgnoredAny),
+}
+
+#[derive(Debug, Deserialize)]
+struct TiledObject {
+    #[serde(default)]
+    name: String,
+    #[serde(default, rename = "type", alias = "class")]
+    class: String,
+    x: f64,
+    y: f64,
+    #[serde(default)]
+    width: f64,
+    #[serde(default)]
+    height: f64,
+    #[serde(default)]
+    properties: Vec<TiledProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TiledProperty {
+    name: String,
+    value: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct TiledTileset {
+    firstgid: u32,
+    #[serde(default)]
+    name: Option<String>,
+    /// Path of an external `.tsx` tileset, named after its file.
+    #[serde(default)]
+    source: Option<String>,
+}
+
+impl TiledTileset {
+    fn name(&self) -> &str {
+        match (&self.name, &self.source) {
+            (Some(name), _) => name,
+            (None, Some(source)) => Path::new(source)
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or(source),
+            (None, None) => "",
+        }
+    }
+}
+
+fn parse_tmj(source: &str) -> Result<TiledMap, TiledError> {
+    serde_json::from_str(source).map_err(|e| TiledError::Parse(e.to_string()))
+}
+
+fn parse_tmx(source: &str) -> Result<TiledMap, TiledError> {
+    let document =
+        roxmltree::Document::parse(source).map_err(|e| TiledError::Parse(e.to_string()))?;
+    let root = document.root_element();
+    if !root.has_tag_name("map") {
+        return Err(TiledError::Parse(format!(
+            "expected a <map> root, found <{}>",
+            root.tag_name().name()
+        )));
+    }
+
+    let attribute = |node: roxmltree::Node, name: &str| -> Result<String, TiledError> {
+        node.attribute(name).map(str::to_string).ok_or_else(|| {
+            TiledError::Parse(format!(
+                "missing attribute \"{name}\" on <{}>",
+                node.tag_name().name()
+            ))
+        })
+    };
+    let number = |node: roxmltree::Node, name: &str| -> Result<f64, TiledError> {
+        match node.attribute(name) {
+            None => Ok(0.0),
+            Some(value) => value.parse().map_err(|_| {
+                TiledError::Parse(format!(
+                    "attribute \"{name}\" of <{}> is not a number: \"{value}\"",
+                    node.tag_name().name()
+                ))
+            }),
+        }
+    };
+
+    let mut map = TiledMap {
+        orientation: root
+            .attribute("orientation")
+            .unwrap_or_default()
+            .to_string(),
+        infinite: root.attribute("infinite") == Some("1"),
+        tilewidth: number(root, "tilewidth")? as u32,
+        tileheight: number(root, "tileheight")? as u32,
+        layers: Vec::new(),
+        tilesets: Vec::new(),
+    };
+
+    for node in root.children().filter(|node| node.is_element()) {
+        match node.tag_name().name() {
+            "tileset" => map.tilesets.push(TiledTileset {
+                firstgid: number(node, "firstgid")? as u32,
+                name: node.attribute("name").map(str::to_string),
+                source: node.attribute("source").map(str::to_string),
+            }),
+            "layer" => {
+                let data = node
+                    .children()
+                    .find(|child| child.has_tag_name("data"))
+                    .ok_or_else(|| TiledError::Parse("missing <data> in <layer>".into()))?;
+                let encoding = data.attribute("encoding").map(str::to_string);
+                let data = match encoding.as_deref() {
+                    Some("csv") => TileData::Gids(
+                        data.text()
+                            .unwrap_or_default()
+                            .split(',')
+                            .map(str::trim)
+                            .filter(|gid| !gid.is_empty())
+                            .map(|gid| {
+                                gid.parse().map_err(|_| {
+                                    TiledError::Parse(format!("invalid gid \"{gid}\""))
+                                })
+                            })
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    // Deprecated XML encoding, one <tile gid=".."/> per tile
+                    None => TileData::Gids(
+                        data.children()
+                            .filter(|child| child.has_tag_name("tile"))
+                            .map(|tile| number(tile, "gid").map(|gid| gid as u32))
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    Some(_) => TileData::Encoded(IgnoredAny),
+                };
+                map.layers.push(TiledLayer::Tiles {
+                    name: attribute(node, "name")?,
+                    width: number(node, "width")? as u32,
+                    encoding,
+                    data,
+                });
+            }
+            "objectgroup" => {
+                let mut objects = Vec::new();
+                for object in node.children().filter(|child| child.has_tag_name("object")) {
+                    let properties = object
+                        .children()
+                        .filter(|child| child.has_tag_name("properties"))
+                        .flat_map(|properties| properties.children())
+                        .filter(|child| child.has_tag_name("property"))
+                        .map(|property| {
+                            let value = property.attribute("value").unwrap_or_default();
+                            Ok(TiledProperty {
+                                name: attribute(property, "name")?,
+                                value: match property.attribute("type") {
+                                    Some("bool") => Value::Bool(value == "true"),
+                                    Some("int") => {
+                                        value.parse::<i64>().map_or(Value::Null, Value::from)
+                                    }
+                                    Some("float") => value
+                                        .parse::<f64>()
+                                        .ok()
+                                        .and_then(serde_json::Number::from_f64)
+                                        .map_or(Value::Null, Value::Number),
+                                    _ => Value::String(value.to_string()),
+                                },
+                            })
+                        })
+                        .collect::<Result<_, TiledError>>()?;
+                    objects.push(TiledObject {
+                        name: object.attribute("name").unwrap_or_default().to_string(),
+                        class: object
+                            .attribute("type")
+                            .or(object.attribute("class"))
+                            .unwrap_or_default()
+                            .to_string(),
+                        x: number(object, "x")?,
+                        y: number(object, "y")?,
+                        width: number(object, "width")?,
+                        height: number(object, "height")?,
+                        properties,
+                    });
+                }
+                map.layers.push(TiledLayer::Objects {
+                    name: attribute(node, "name")?,
+                    objects,
+                });
+            }
+            _ => map.layers.push(TiledLayer::Other),
+        }
+    }
+
+    Ok(map)
+}
+
+impl TiledMap {
+    fn to_map<T, A, R>(
+        &self,
+        name: &str,
+        resources: &Resources<T, A, R>,
+    ) -> Result<Map, TiledError> {
+        if !self.orientation.is_empty() && self.orientation != "orthogonal" {
+            return Err(TiledError::Unsupported(format!(
+                "{} orientation",
+                self.orientation
+            )));
+        }
+        if self.infinite {
+            return Err(TiledError::Unsupported("infinite maps".into()));
+        }
+
+        let mut tilesets: Vec<&TiledTileset> = self.tilesets.iter().collect();
+        tilesets.sort_by_key(|tileset| tileset.firstgid);
+
+        let mut map = Map::new(name.to_string(), Vec::new(), Coordinates::default());
+        for layer in &self.layers {
+            let (name, masks) = match layer {
+                TiledLayer::Tiles {
+                    name,
+                    width,
+                    encoding,
+                    data,
+                } => {
+                    let gids = match data {
+                        TileData::Gids(gids) => gids,
+                        TileData::Encoded(_) => {
+                            return Err(TiledError::Unsupported(format!(
+                                "{} tile layer encoding, save the map as CSV",
+                                encoding.as_deref().unwrap_or("base64")
+                            )));
+                        }
+                    };
+
+                    // Tiles of each gid, flips dropped
+                    let mut tiles: BTreeMap<u32, Vec<Coordinates>> = BTreeMap::new();
+                    for (i, gid) in gids.iter().enumerate() {
+                        let gid = gid & !GID_FLAGS;
+                        if gid != 0 && *width > 0 {
+                            let (x, y) = (i as u32 % width, i as u32 / width);
+                            tiles.entry(gid).or_default().push(Coordinates::new(x, y));
+                        }
+                    }
+
+                    let mut masks = Vec::with_capacity(tiles.len());
+                    for (gid, tiles) in tiles {
+                        let tileset = tilesets
+                            .iter()
+                            .rev()
+                            .find(|tileset| tileset.firstgid <= gid)
+                            .ok_or_else(|| TiledError::UnknownGid {
+                                gid,
+                                layer: name.clone(),
+                            })?;
+                        let key = namespaced(tileset.name(), &(gid - tileset.firstgid).to_string());
+                        let texture = resources.textures.get_id(&key).ok_or_else(|| {
+                            TiledError::UnknownKey {
+                                kind: ResourceKind::Texture,
+                                key: key.clone(),
+                                layer: name.clone(),
+                            }
+                        })?;
+                        masks.push(Mask::new(
+                            key,
+                            Rect::cover(tiles),
+                            vec![Effect::Texture(texture)],
+                        ));
+                    }
+                    (name, masks)
+                }
+                TiledLayer::Objects { name, objects } => {
+                    let mut masks = Vec::new();
+                    for (i, object) in objects.iter().enumerate() {
+                        let area = self.object_area(object);
+                        if object.name == "spawn" || object.class == "spawn" {
+                            map.spawn = area.origin;
+                            continue;
+                        }
+
+                        let effects = object_effects(object, area, name, resources)?;
+                        if !effects.is_empty() {
+                            let mask_name = match object.name.as_str() {
+                                "" => format!("object {i}"),
+                                object_name => object_name.to_string(),
+                            };
+                            masks.push(Mask::new(mask_name, vec![area], effects));
+                        }
+                    }
+                    (name, masks)
+                }
+                TiledLayer::Other => continue,
+            };
+
+            if !masks.is_empty() {
+                let z = map.layers().len() as u32 + 1;
+                map.push_layer(Layer::new(name.clone(), masks, z));
+            }
+        }
+
+        Ok(map)
+    }
+
+    /// Returns the tiles covered by an object, at least one.
+    fn object_area(&self, object: &TiledObject) -> Rect {
+        let (tile_width, tile_height) =
+            (self.tilewidth.max(1) as f64, self.tileheight.max(1) as f64);
+        let x = (object.x / tile_width).floor().max(0.0);
+        let y = (object.y / tile_height).floor().max(0.0);
+        let right = ((object.x + object.width) / tile_width).ceil().max(x + 1.0);
+        let bottom = ((object.y + object.height) / tile_height)
+            .ceil()
+            .max(y + 1.0);
+        Rect::from_xywh(x as u32, y as u32, (right - x) as u32, (bottom - y) as u32)
+    }
+}
+
+/// Names of the [`TriggerOn`] values in `action_on` properties.
+const TRIGGER_NAMES: [(TriggerOn, &str); 4] = [
+    (TriggerOn::Enter, "enter"),
+    (TriggerOn::Exit, "exit"),
+    (TriggerOn::Interact, "interact"),
+    (TriggerOn::Stand, "stand"),
+];
+
+/// Returns true for the names of action properties, `action` and `action_N`.
+fn is_action_property(name: &str) -> bool {
+    name == "action"
+        || name
+            .strip_prefix("action_")
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Reads the effects of an object covering `area` from its properties, ignoring
+/// the properties RPGX doesn't know.
+fn object_effects<T, A, R>(
+    object: &TiledObject,
+    area: Rect,
+    layer: &str,
+    resources: &Resources<T, A, R>,
+) -> Result<Vec<Effect>, TiledError> {
+    let value_of = |name: &str| {
+        object
+            .properties
+            .iter()
+            .find(|property| property.name == name)
+            .map(|property| &property.value)
+    };
+
+    let mut effects = Vec::new();
+    for property in &object.properties {
+        match (property.name.as_str(), &property.value) {
+            ("collision", Value::Bool(true)) => effects.push(Effect::Block(area)),
+            ("opaque", Value::Bool(true)) => effects.push(Effect::Opaque(area)),
+            ("cost", Value::Number(cost)) => {
+                if let Some(cost) = cost.as_u64() {
+                    effects.push(Effect::Cost(cost as u32));
+                }
+            }
+            (name, Value::String(key)) if is_action_property(name) => {
+                let action =
+                    resources
+                        .actions
+                        .get_id(key)
+                        .ok_or_else(|| TiledError::UnknownKey {
+                            kind: ResourceKind::Action,
+                            key: key.clone(),
+                            layer: layer.to_string(),
+                        })?;
+                let on = match value_of(&format!("{name}_on")) {
+                    None => None,
+                    Some(value) => Some(
+                        TRIGGER_NAMES
+                            .iter()
+                            .find(|(_, on)| value.as_str() == Some(on))
+                            .map(|(on, _)| *on)
+                            .ok_or_else(|| {
+                                TiledError::Parse(format!(
+                                    "layer \"{layer}\": unknown trigger {value} in \"{name}_on\""
+                                ))
+                            })?,
+                    ),
+                };
+                let once = value_of(&format!("{name}_once")) == Some(&Value::Bool(true));
+                effects.push(match (on, once) {
+                    (None, false) => Effect::Action(action),
+                    (on, once) => Effect::Trigger(
+                        action,
+                        Trigger {
+                            on: on.unwrap_or_default(),
+                            once,
+                        },
+                    ),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(effects)
+}
+
+/// A layer of a map ready to be written, in either format.
+enum ExportLayer {
+    Tiles {
+        name: String,
+        gids: Vec<u32>,
+    },
+    Objects {
+        name: String,
+        objects: Vec<ExportObject>,
+    },
+}
+
+struct ExportObject {
+    name: String,
+    area: Rect,
+    properties: Vec<(String, Value)>,
+}
+
+impl ExportObject {
+    /// Adds a `true` property named `flag`, unless the object already has it.
+    fn flag(&mut self, flag: &str) {
+        if !self.properties.iter().any(|(name, _)| name == flag) {
+            self.properties.push((flag.to_string(), Value::Bool(true)));
+        }
+    }
+}
+
+/// Everything [`Map::to_tiled`] writes, whichever the format.
+struct Export {
+    shape: Shape,
+    tile_size: Shape,
+    /// `(firstgid, name)` of the external tilesets.
+    tilesets: Vec<(u32, String)>,
+    layers: Vec<ExportLayer>,
+}
+
+impl Export {
+    fn new<T, A, R>(
+        map: &Map,
+        tile_size: Shape,
+        resources: &Resources<T, A, R>,
+    ) -> Result<Self, TiledError> {
+        let shape = map.get_shape();
+
+        // Tileset and index of every texture of the map
+        let mut textures: BTreeMap<TextureId, (String, u32)> = BTreeMap::new();
+        let mut counts: BTreeMap<String, u32> = BTreeMap::new();
+        for layer in map.layers() {
+            for effect in layer.masks.iter().flat_map(|mask| &mask.effects) {
+                if let Effect::Texture(id) = *effect
+                    && !textures.contains_key(&id)
+                {
+                    let key = resources
+                        .textures
+                        .get_key(id)
+                        .ok_or(TiledError::UnknownId {
+                            kind: ResourceKind::Texture,
+                            id: id.0,
+                            layer: layer.name.clone(),
+                        })?;
+                    let invalid = || TiledError::InvalidTextureKey {
+                        key: key.to_string(),
+                        layer: layer.name.clone(),
+                    };
+                    let (tileset, index) =
+                        key.rsplit_once(NAMESPACE_SEPARATOR).ok_or_else(invalid)?;
+                    let index: u32 = index.parse().map_err(|_| invalid())?;
+                    let count = counts.entry(tileset.to_string()).or_default();
+                    *count = (*count).max(index + 1);
+                    textures.insert(id, (tileset.to_string(), index));
+                }
+            }
+        }
+
+        let mut firstgids = BTreeMap::new();
+        let mut tilesets = Vec::with_capacity(counts.len());
+        let mut firstgid = 1;
+        for (tileset, count) in counts {
+            firstgids.insert(tileset.clone(), firstgid);
+            tilesets.push((firstgid, tileset));
+            firstgid += count;
+        }
+        let gid = |id: &TextureId| {
+            let (tileset, index) = &textures[id];
+            firstgids[tileset] + index
+        };
+
+        let mut layers = Vec::new();
+        for layer in map.layers() {
+            let mut gids = vec![0; (shape.width * shape.height) as usize];
+            let mut textured = false;
+            let mut objects = Vec::new();
+            for mask in &layer.masks {
+                if let Some(texture) = mask.effects.iter().find_map(|effect| match effect {
+                    Effect::Texture(id) => Some(id),
+                    _ => None,
+                }) {
+                    textured = true;
+                    for tile in mask.tiles.iter().flat_map(|rect| rect.iter()) {
+                        gids[(tile.y * shape.width + tile.x) as usize] = gid(texture);
+                    }
+                }
+
+                // Actions and cost apply to the tiles of the mask
+                let mut properties = Vec::new();
+                let actions = mask.effects.iter().filter_map(|effect| match effect {
+                    Effect::Action(id) => Some((*id, None)),
+                    Effect::Trigger(id, trigger) => Some((*id, Some(*trigger))),
+                    _ => None,
+                });
+                for (i, (id, trigger)) in actions.enumerate() {
+                    let key = resources.actions.get_key(id).ok_or(TiledError::UnknownId {
+                        kind: ResourceKind::Action,
+                        id: id.0,
+                        layer: layer.name.clone(),
+                    })?;
+                    let name = match i {
+                        0 => "action".to_string(),
+                        i => format!("action_{}", i + 1),
+                    };
+                    properties.push((name.clone(), Value::String(key.to_string())));
+                    if let Some(trigger) = trigger {
+                        let on = TRIGGER_NAMES
+                            .iter()
+                            .find(|(on, _)| *on == trigger.on)
+                            .map_or("enter", |(_, on)| on);
+                        properties.push((format!("{name}_on"), Value::String(on.into())));
+                        if trigger.once {
+                            properties.push((format!("{name}_once"), Value::Bool(true)));
+                        }
+                    }
+                }
+                if let Some(cost) = mask.get_cost() {
+                    properties.push(("cost".to_string(), Value::from(cost)));
+                }
+
+                let mut mask_objects: Vec<ExportObject> = Vec::new();
+                if !properties.is_empty() {
+                    for area in &mask.tiles {
+                        mask_objects.push(ExportObject {
+                            name: mask.name.clone(),
+                            area: *area,
+                            properties: properties.clone(),
+                        });
+                    }
+                }
+
+                // Blocking and opaque areas, joined to the object of the same area
+                for effect in &mask.effects {
+                    let (area, flag) = match effect {
+                        Effect::Block(area) => (area, "collision"),
+                        Effect::Opaque(area) => (area, "opaque"),
+                        _ => continue,
+                    };
+                    match mask_objects.iter_mut().find(|object| object.area == *area) {
+                        Some(object) => object.flag(flag),
+                        None => {
+                            let mut object = ExportObject {
+                                name: mask.name.clone(),
+                                area: *area,
+                                properties: Vec::new(),
+                            };
+                            object.flag(flag);
+                            mask_objects.push(object);
+                        }
+                    }
+                }
+                objects.extend(mask_objects);
+            }
+
+            if textured {
+                layers.push(ExportLayer::Tiles {
+                    name: layer.name.clone(),
+                    gids,
+                });
+            }
+            if !objects.is_empty() {
+                layers.push(ExportLayer::Objects {
+                    name: layer.name.clone(),
+                    objects,
+                });
+            }
+        }
+
+        layers.push(ExportLayer::Objects {
+            name: "spawn".into(),
+            objects: vec![ExportObject {
+                name: "spawn".into(),
+                area: Rect::new(map.spawn, Shape::new(0, 0)),
+                properties: Vec::new(),
+            }],
+        });
+
+        Ok(Self {
+            shape,
+            tile_size,
+            tilesets,
+            layers,
+        })
+    }
+
+    /// Returns the pixel position and size of an object.
+    fn pixels(&self, area: &Rect) -> [u32; 4] {
+        [
+            area.origin.x * self.tile_size.width,
+            area.origin.y * self.tile_size.height,
+            area.shape.width * self.tile_size.width,
+            area.shape.height * self.tile_size.height,
+        ]
+    }
+
+    fn object_count(&self) -> usize {
+        self.layers
+            .iter()
+            .map(|layer| match layer {
+                ExportLayer::Tiles { .. } => 0,
+                ExportLayer::Objects { objects, .. } => objects.len(),
+            })
+            .sum()
+    }
+
+    fn to_tmj(&self) -> String {
+        let mut object_id = 0;
+        let layers: Vec<Value> = self
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(i, layer)| match layer {
+                ExportLayer::Tiles { name, gids } => json!({
+                    "id": i + 1,
+                    "name": name,
+                    "type": "tilelayer",
+                    "width": self.shape.width,
+                    "height": self.shape.height,
+                    "x": 0,
+                    "y": 0,
+                    "opacity": 1,
+                    "visible": true,
+                    "data": gids,
+                }),
+                ExportLayer::Objects { name, objects } => json!({
+                    "id": i + 1,
+                    "name": name,
+                    "type": "objectgroup",
+                    "draworder": "topdown",
+                    "x": 0,
+                    "y": 0,
+                    "opacity": 1,
+                    "visible": true,
+                    "objects": objects.iter().map(|object| {
+                        object_id += 1;
+                        let [x, y, width, height] = self.pixels(&object.area);
+                        let properties: Vec<Value> = object
+                            .properties
+                            .iter()
+                            .map(|(name, value)| {
+                                let kind = match value {
+                                    Value::Bool(_) => "bool",
+                                    Value::Number(_) => "int",
+                                    _ => "string",
+                                };
+                                json!({ "name": name, "type": kind, "value": value })
+                            })
+                            .collect();
+                        let mut value = json!({
+                            "id": object_id,
+                            "name": object.name,
+                            "type": "",
+                            "x": x,
+                            "y": y,
+                            "width": width,
+                            "height": height,
+                            "rotation": 0,
+                            "visible": true,
+                        });
+                        if object.area.shape.width == 0 {
+                            value["point"] = Value::Bool(true);
+                        }
+                        if !properties.is_empty() {
+                            value["properties"] = Value::Array(properties);
+                        }
+                        value
+                    }).collect::<Vec<_>>(),
+                }),
+            })
+            .collect();
+
+        let tilesets: Vec<Value> = self
+            .tilesets
+            .iter()
+            .map(
+                |(firstgid, name)| json!({ "firstgid": firstgid, "source": format!("{name}.tsx") }),
+            )
+            .collect();
+
+        let map = json!({
+            "type": "map",
+            "version": "1.10",
+            "orientation": "orthogonal",
+            "renderorder": "right-down",
+            "infinite": false,
+            "width": self.shape.width,
+            "height": self.shape.height,
+            "tilewidth": self.tile_size.width,
+            "tileheight": self.tile_size.height,
+            "nextlayerid": self.layers.len() + 1,
+            "nextobjectid": self.object_count() + 1,
+            "tilesets": tilesets,
+            "layers": layers,
+        });
+        serde_json::to_string_pretty(&map).unwrap_or_default()
+    }
+
+    fn to_tmx(&self) -> String {
+        let mut tmx = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        tmx += &format!(
+            "<map version=\"1.10\" orientation=\"orthogonal\" renderorder=\"right-down\" width=\"{}\" height=\"{}\" tilewidth=\"{}\" tileheight=\"{}\" infinite=\"0\" nextlayerid=\"{}\" nextobjectid=\"{}\">\n",
+            self.shape.width,
+            self.shape.height,
+            self.tile_size.width,
+            self.tile_size.height,
+            self.layers.len() + 1,
+            self.object_count() + 1,
+        );
+        for (firstgid, name) in &self.tilesets {
+            tmx += &format!(
+                " <tileset firstgid=\"{firstgid}\" source=\"{}.tsx\"/>\n",
+                escape(name)
+            );
+        }
+
+        let mut object_id = 0;
+        for (i, layer) in self.layers.iter().enumerate() {
+            match layer {
+                ExportLayer::Tiles { name, gids } => {
+                    tmx += &format!(
+                        " <layer id=\"{}\" name=\"{}\" width=\"{}\" height=\"{}\">\n  <data encoding=\"csv\">\n",
+                        i + 1,
+                        escape(name),
+                        self.shape.width,
+                        self.shape.height,
+                    );
+                    let rows: Vec<String> = gids
+                        .chunks(self.shape.width.max(1) as usize)
+                        .map(|row| {
+                            row.iter()
+                                .map(|gid| gid.to_string())
+                                .collect::<Vec<_>>()
+                                .join(",")
+                        })
+                        .collect();
+                    tmx += &rows.join(",\n");
+                    tmx += "\n</data>\n </layer>\n";
+                }
+                ExportLayer::Objects { name, objects } => {
+                    tmx += &format!(
+                        " <objectgroup id=\"{}\" name=\"{}\">\n",
+                        i + 1,
+                        escape(name)
+                    );
+                    for object in objects {
+                        object_id += 1;
+                        let [x, y, width, height] = self.pixels(&object.area);
+                        tmx += &format!(
+                            "  <object id=\"{object_id}\" name=\"{}\" x=\"{x}\" y=\"{y}\"",
+                            escape(&object.name)
+                        );
+                        let properties = &object.properties;
+                        if object.area.shape.width == 0 {
+                            tmx += ">\n   <point/>\n";
+                        } else {
+                            tmx += &format!(" width=\"{width}\" height=\"{height}\">\n");
+                        }
+                        if !properties.is_empty() {
+                            tmx += "   <properties>\n";
+                            for (name, value) in properties {
+                                let (kind, value) = match value {
+                                    Value::Bool(value) => (" type=\"bool\"", value.to_string()),
+                                    Value::Number(value) => (" type=\"int\"", value.to_string()),
+                                    value => ("", escape(value.as_str().unwrap_or_default())),
+                                };
+                                tmx += &format!(
+                                    "    <property name=\"{}\"{kind} value=\"{value}\"/>\n",
+                                    escape(name)
+                                );
+                            }
+                            tmx += "   </properties>\n";
+                        }
+                        tmx += "  </object>\n";
+                    }
+                    tmx += " </objectgroup>\n";
+                }
+            }
+        }
+        tmx += "</map>\n";
+        tmx
+    }
+}
+
+/// Escapes text for an XML attribute.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Map {
+    /// Imports a Tiled map file, named after the file, resolving its tiles and
+    /// properties through `resources`.
+    pub fn from_tiled<T, A, R>(
+        path: impl AsRef<Path>,
+        resources: &Resources<T, A, R>,
+    ) -> Result<Self, TiledError> {
+        let path = path.as_ref();
+        let format = TiledFormat::from_path(path)
+            .ok_or_else(|| TiledError::UnknownFormat(path.display().to_string()))?;
+        let source = std::fs::read_to_string(path).map_err(|e| TiledError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        let name = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        Self::parse_tiled(name, &source, format, resources)
+    }
+
+    /// Imports a Tiled map written in `format` as a map called `name`.
+    pub fn parse_tiled<T, A, R>(
+        name: &str,
+        source: &str,
+        format: TiledFormat,
+        resources: &Resources<T, A, R>,
+    ) -> Result<Self, TiledError> {
+        let tiled = match format {
+            TiledFormat::Tmx => parse_tmx(source)?,
+            TiledFormat::Tmj => parse_tmj(source)?,
+        };
+        tiled.to_map(name, resources)
+    }
+
+    /// Exports the map as a Tiled map in `format`, with tiles of `tile_size` pixels.
+    ///
+    /// Texture keys must be `tileset/index`, with tilesets written as external
+    /// `tileset.tsx` files. Render effects are not exported.
+    pub fn to_tiled<T, A, R>(
+        &self,
+        format: TiledFormat,
+        tile_size: Shape,
+        resources: &Resources<T, A, R>,
+    ) -> Result<String, TiledError> {
+        let export = Export::new(self, tile_size, resources)?;
+        Ok(match format {
+            TiledFormat::Tmx => export.to_tmx(),
+            TiledFormat::Tmj => export.to_tmj(),
+        })
+    }
+
+    /// Exports the map to a Tiled file, in the format given by its extension.
+    pub fn to_tiled_file<T, A, R>(
+        &self,
+        path: impl AsRef<Path>,
+        tile_size: Shape,
+        resources: &Resources<T, A, R>,
+    ) -> Result<(), TiledError> {
+        let path = path.as_ref();
+        let format = TiledFormat::from_path(path)
+            .ok_or_else(|| TiledError::UnknownFormat(path.display().to_string()))?;
+        let source = self.to_tiled(format, tile_size, resources)?;
+        std::fs::write(path, source).map_err(|e| TiledError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{ActionId, MapFile};
+
+    fn resources() -> Resources<&'static str, (), ()> {
+        let mut resources = Resources::new();
+        resources.textures.insert("dungeon/0", "floor.webp");
+        resources.textures.insert("dungeon/1", "rug.webp");
+        resources.textures.insert("dungeon/4", "wall.webp");
+        resources.actions.insert("open_chest", ());
+        resources
+    }
+
+    fn fixture(name: &str) -> String {
+        format!("{}/fixtures/tiled/{name}", env!("CARGO_MANIFEST_DIR"))
+    }
+
+    fn describe(map: &Map) -> MapFile {
+        MapFile::from_map(map, &resources()).unwrap()
+    }
+
+    #[test]
+    fn imports_tmx_and_tmj_fixtures() {
+        let resources = resources();
+        let tmx = Map::from_tiled(fixture("dungeon.tmx"), &resources).unwrap();
+        let tmj = Map::from_tiled(fixture("dungeon.tmj"), &resources).unwrap();
+        assert_eq!(describe(&tmx), describe(&tmj));
+
+        assert_eq!(tmx.name, "dungeon");
+        assert_eq!(tmx.spawn, Coordinates::new(1, 1));
+        let layers: Vec<_> = tmx.layers().iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(layers, ["ground", "walls", "objects"]);
+
+        let rug = &tmx.layers()[0].masks[1];
+        assert_eq!(rug.name, "dungeon/1");
+        assert_eq!(rug.tiles, vec![Rect::from_xywh(2, 2, 2, 1)]);
+        // Flipped tiles join the mask of their gid
+        assert_eq!(
+            tmx.layers()[1].masks[0].tiles,
+            vec![Rect::from_xywh(0, 0, 6, 1)]
+        );
+
+        let table = Rect::from_xywh(3, 1, 2, 1);
+        assert_eq!(tmx.layers()[2].masks[0].effects, vec![Effect::Block(table)]);
+        assert!(tmx.is_blocking_at(&Coordinates::new(4, 1)));
+        assert_eq!(
+            tmx.get_actions_at(&Coordinates::new(5, 3)),
+            vec![ActionId(1)]
+        );
+        assert_eq!(
+            tmx.get_textures_at(&Coordinates::new(5, 0)),
+            vec![TextureId(1), TextureId(3)]
+        );
+    }
+
+    #[test]
+    fn exports_round_trip() {
+        let resources = resources();
+        let map = Map::from_tiled(fixture("dungeon.tmj"), &resources).unwrap();
+        let tile_size = Shape::new(16, 16);
+
+        for format in [TiledFormat::Tmx, TiledFormat::Tmj] {
+            let source = map.to_tiled(format, tile_size, &resources).unwrap();
+            let loaded = Map::parse_tiled("dungeon", &source, format, &resources).unwrap();
+            assert_eq!(describe(&loaded), describe(&map));
+        }
+
+        let tmx = map
+            .to_tiled(TiledFormat::Tmx, tile_size, &resources)
+            .unwrap();
+        assert!(tmx.contains(r#"<tileset firstgid="1" source="dungeon.tsx"/>"#));
+        assert!(tmx.contains(r#"<property name="action" value="open_chest"/>"#));
+    }
+
+    #[test]
+    fn exports_blocks_costs_and_triggers() {
+        let resources = resources();
+        let chest = ActionId(1);
+        let map = Map::new(
+            "vault".into(),
+            vec![Layer::new(
+                "ground".into(),
+                vec![
+                    Mask::new(
+                        "floor".into(),
+                        vec![Rect::from_xywh(0, 0, 6, 3)],
+                        vec![Effect::Texture(TextureId(1))],
+                    ),
+                    // A wall with no tiles of its own
+                    Mask::new(
+                        "wall".into(),
+                        vec![],
+                        vec![Effect::Block(Rect::from_xywh(0, 0, 6, 1))],
+                    ),
+                    Mask::new(
+                        "mud".into(),
+                        vec![Rect::from_xywh(0, 1, 3, 2)],
+                        vec![
+                            Effect::Cost(4),
+                            Effect::Block(Rect::from_xywh(0, 2, 1, 1)),
+                            Effect::Opaque(Rect::from_xywh(2, 2, 1, 1)),
+                        ],
+                    ),
+                    Mask::new(
+                        "chest".into(),
+                        vec![Rect::from_xywh(5, 2, 1, 1)],
+                        vec![
+                            Effect::Trigger(chest, Trigger::once(TriggerOn::Interact)),
+                            Effect::Action(chest),
+                        ],
+                    ),
+                ],
+                1,
+            )],
+            Coordinates::new(4, 1),
+        );
+
+        for format in [TiledFormat::Tmx, TiledFormat::Tmj] {
+            let source = map
+                .to_tiled(format, Shape::new(16, 16), &resources)
+                .unwrap();
+            assert!(source.contains("collision"));
+            let loaded = Map::parse_tiled("vault", &source, format, &resources).unwrap();
+
+            for x in 0..6 {
+                assert!(loaded.is_blocking_at(&Coordinates::new(x, 0)));
+            }
+            assert!(loaded.is_blocking_at(&Coordinates::new(0, 2)));
+            assert!(!loaded.is_blocking_at(&Coordinates::new(1, 2)));
+            assert!(loaded.is_opaque_at(&Coordinates::new(2, 2)));
+            assert_eq!(loaded.get_cost_at(&Coordinates::new(1, 1)), 4);
+
+            let objects = &loaded.layers()[1].masks;
+            let chest = objects.iter().find(|mask| mask.name == "chest").unwrap();
+            assert_eq!(
+                chest.effects,
+                vec![
+                    Effect::Trigger(ActionId(1), Trigger::once(TriggerOn::Interact)),
+                    Effect::Action(ActionId(1)),
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_unknown_properties() {
+        let source = std::fs::read_to_string(fixture("dungeon.tmj")).unwrap();
+        let noted = source.replacen(
+            r#""properties": [{ "name": "action","#,
+            r#""properties": [{ "name": "note", "type": "string", "value": "rusty" }, { "name": "action","#,
+            1,
+        );
+        assert_ne!(noted, source);
+
+        let resources = resources();
+        let map = Map::parse_tiled("dungeon", &noted, TiledFormat::Tmj, &resources).unwrap();
+        assert_eq!(
+            map.get_actions_at(&Coordinates::new(5, 3)),
+            vec![ActionId(1)]
+        );
+    }
+
+    #[test]
+    fn reports_unknown_tiles_and_encodings() {
+        let mut resources = resources();
+        resources.textures.remove(TextureId(3));
+        assert_eq!(
+            Map::from_tiled(fixture("dungeon.tmx"), &resources).err(),
+            Some(TiledError::UnknownKey {
+                kind: ResourceKind::Texture,
+                key: "dungeon/4".into(),
+                layer: "walls".into(),
+            })
+        );
+
+        let source = std::fs::read_to_string(fixture("dungeon.tmx")).unwrap();
+        let base64 = source.replacen(r#"<data encoding="csv">"#, r#"<data encoding="base64">"#, 1);
+        assert!(matches!(
+            Map::parse_tiled("dungeon", &base64, TiledFormat::Tmx, &resources).err(),
+            Some(TiledError::Unsupported(_))
+        ));
+    }
+}
@@ -0,0 +1,281 @@
+use std::collections::HashSet;
+
+use crate::{
+    prelude::{Coordinates, Map},
+    traits::Grid,
+};
+
+/// Maps `(depth, col)` of a scan to `(col * cx + depth * rx, col * cy + depth * ry)`
+/// for the north, east, south and west quadrants.
+const QUADRANTS: [(i64, i64, i64, i64); 4] =
+    [(1, 0, 0, -1), (0, 1, 1, 0), (1, 0, 0, 1), (0, -1, 1, 0)];
+
+/// A slope `num / den` measured from the origin of a scan, with `den > 0`.
+#[derive(Clone, Copy)]
+struct Slope {
+    num: i64,
+    den: i64,
+}
+
+impl Slope {
+    /// Slope of the left edge of the tile at `col` in the row at `depth`.
+    fn of(depth: i64, col: i64) -> Self {
+        Self {
+            num: 2 * col - 1,
+            den: 2 * depth,
+        }
+    }
+}
+
+/// A row of tiles at a fixed distance from the origin, bounded by two slopes.
+struct Row {
+    depth: i64,
+    start: Slope,
+    end: Slope,
+}
+
+impl Row {
+    /// Columns of the row whose centers lie between its slopes, edges rounded outwards.
+    fn columns(&self) -> std::ops::RangeInclusive<i64> {
+        // floor(depth * start + 1/2) and ceil(depth * end - 1/2)
+        let (start, end) = (self.start, self.end);
+        let min = (2 * self.depth * start.num + start.den).div_euclid(2 * start.den);
+        let max = -(end.den - 2 * self.depth * end.num).div_euclid(2 * end.den);
+        min..=max
+    }
+
+    /// Returns true if the center of `col` lies strictly inside the row, which keeps
+    /// floor tiles visible from `a` exactly when `a` is visible from them.
+    fn is_symmetric(&self, col: i64) -> bool {
+        col * self.start.den >= self.depth * self.start.num
+            && col * self.end.den <= self.depth * self.end.num
+    }
+
+    fn next(&self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
+
+impl Map {
+    /// Returns true if `target` lets sight through: it is part of the map and no
+    /// [`Effect::Opaque`] area covers it.
+    ///
+    /// [`Effect::Opaque`]: crate::prelude::Effect::Opaque
+    pub fn is_transparent(&self, target: &Coordinates) -> bool {
+        self.contains(target) && !self.is_opaque_at(target)
+    }
+
+    /// Computes the tiles visible from `origin` within `radius` tiles, using symmetric
+    /// shadowcasting.
+    ///
+    /// Opaque tiles are visible but hide what lies behind them, and tiles outside the
+    /// map behave like opaque tiles that are never reported. Blocking tiles do not
+    /// affect sight. The origin is always visible when it is part of the map.
+    pub fn field_of_view(&self, origin: &Coordinates, radius: u32) -> HashSet<Coordinates> {
+        let mut visible = HashSet::new();
+        if !self.contains(origin) {
+            return visible;
+        }
+        visible.insert(*origin);
+
+        let radius = radius as i64;
+        let (ox, oy) = (origin.x as i64, origin.y as i64);
+        for (cx, rx, cy, ry) in QUADRANTS {
+            let tile_at = |depth: i64, col: i64| {
+                let (dx, dy) = (col * cx + depth * rx, col * cy + depth * ry);
+                let (x, y) = (ox + dx, oy + dy);
+                let coord = (x >= 0 && y >= 0 && x <= u32::MAX as i64 && y <= u32::MAX as i64)
+                    .then(|| Coordinates::new(x as u32, y as u32));
+                let in_radius = dx * dx + dy * dy <= radius * radius;
+                let transparent = coord.is_some_and(|coord| self.is_transparent(&coord));
+                (coord, in_radius, transparent)
+            };
+
+            let mut rows = vec![Row {
+                depth: 1,
+                start: Slope { num: -1, den: 1 },
+                end: Slope { num: 1, den: 1 },
+            }];
+            while let Some(mut row) = rows.pop() {
+                if row.depth > radius {
+                    continue;
+                }
+
+                let mut previous = None;
+                for col in row.columns() {
+                    let (coord, in_radius, transparent) = tile_at(row.depth, col);
+                    if let Some(coord) = coord
+                        && in_radius
+                        && self.contains(&coord)
+                        && (!transparent || row.is_symmetric(col))
+                    {
+                        visible.insert(coord);
+                    }
+
+                    match previous {
+                        Some(false) if transparent => row.start = Slope::of(row.depth, col),
+                        Some(true) if !transparent => {
+                            let mut next = row.next();
+                            next.end = Slope::of(row.depth, col);
+                            rows.push(next);
+                        }
+                        _ => {}
+                    }
+                    previous = Some(transparent);
+                }
+
+                if previous == Some(true) {
+                    rows.push(row.next());
+                }
+            }
+        }
+
+        visible
+    }
+
+    /// Returns true if nothing blocks the straight line between `from` and `to`.
+    ///
+    /// The tiles crossed by the line are traced with Bresenham's algorithm in both
+    /// directions, and sight passes when either trace only crosses transparent tiles,
+    /// so the check is symmetric. The endpoints themselves may be opaque, which lets a
+    /// pawn see a wall or a door.
+    pub fn line_of_sight(&self, from: &Coordinates, to: &Coordinates) -> bool {
+        self.trace_sight(from, to) || self.trace_sight(to, from)
+    }
+
+    fn trace_sight(&self, from: &Coordinates, to: &Coordinates) -> bool {
+        if from == to {
+            return true;
+        }
+        let (mut x, mut y) = (from.x as i64, from.y as i64);
+        let (tx, ty) = (to.x as i64, to.y as i64);
+        let (dx, dy) = ((tx - x).abs(), -(ty - y).abs());
+        let (sx, sy) = ((tx - x).signum(), (ty - y).signum());
+        let mut error = dx + dy;
+
+        loop {
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += sx;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += sy;
+            }
+            if (x, y) == (tx, ty) {
+                return true;
+            }
+            if !self.is_transparent(&Coordinates::new(x as u32, y as u32)) {
+                return false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        prelude::{Coordinates, Effect, Layer, Map, Mask, Rect},
+        traits::Grid,
+    };
+
+    /// A 9x9 room with a pillar of opaque tiles at (4, 2)..(4, 3) and a blocking
+    /// but transparent window at (2, 6).
+    fn room() -> Map {
+        Map::new(
+            "room".into(),
+            vec![
+                Layer::new(
+                    "ground".into(),
+                    vec![Mask::new(
+                        "floor".into(),
+                        vec![Rect::from_xywh(0, 0, 9, 9)],
+                        vec![],
+                    )],
+                    1,
+                ),
+                Layer::new(
+                    "walls".into(),
+                    vec![
+                        Mask::new(
+                            "pillar".into(),
+                            vec![Rect::from_xywh(4, 2, 1, 2)],
+                            vec![
+                                Effect::Block(Rect::from_xywh(4, 2, 1, 2)),
+                                Effect::Opaque(Rect::from_xywh(4, 2, 1, 2)),
+                            ],
+                        ),
+                        Mask::new(
+                            "window".into(),
+                            vec![Rect::from_xywh(2, 6, 1, 1)],
+                            vec![Effect::Block(Rect::from_xywh(2, 6, 1, 1))],
+                        ),
+                    ],
+                    2,
+                ),
+            ],
+            Coordinates::new(4, 6),
+        )
+    }
+
+    #[test]
+    fn pillar_casts_a_shadow() {
+        let map = room();
+        let visible = map.field_of_view(&Coordinates::new(4, 6), 10);
+
+        assert!(visible.contains(&Coordinates::new(4, 6)));
+        assert!(visible.contains(&Coordinates::new(4, 3)));
+        assert!(!visible.contains(&Coordinates::new(4, 1)));
+        assert!(!visible.contains(&Coordinates::new(4, 0)));
+        assert!(visible.contains(&Coordinates::new(0, 0)));
+        assert!(visible.contains(&Coordinates::new(8, 8)));
+    }
+
+    #[test]
+    fn blocking_tiles_do_not_hide() {
+        let map = room();
+        let visible = map.field_of_view(&Coordinates::new(4, 6), 10);
+
+        assert!(visible.contains(&Coordinates::new(2, 6)));
+        assert!(visible.contains(&Coordinates::new(0, 6)));
+    }
+
+    #[test]
+    fn radius_limits_sight() {
+        let map = room();
+        let visible = map.field_of_view(&Coordinates::new(0, 8), 2);
+
+        assert!(visible.contains(&Coordinates::new(2, 8)));
+        assert!(visible.contains(&Coordinates::new(1, 7)));
+        assert!(!visible.contains(&Coordinates::new(3, 8)));
+        assert!(!visible.contains(&Coordinates::new(2, 6)));
+        assert!(visible.iter().all(|coord| map.contains(coord)));
+    }
+
+    #[test]
+    fn field_of_view_is_symmetric() {
+        let map = room();
+        let from = Coordinates::new(3, 7);
+        for to in map.field_of_view(&from, 10) {
+            if map.is_transparent(&to) {
+                assert!(map.field_of_view(&to, 10).contains(&from), "{to:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn checks_line_of_sight() {
+        let map = room();
+
+        assert!(map.line_of_sight(&Coordinates::new(4, 6), &Coordinates::new(4, 3)));
+        assert!(!map.line_of_sight(&Coordinates::new(4, 6), &Coordinates::new(4, 0)));
+        assert!(!map.line_of_sight(&Coordinates::new(4, 0), &Coordinates::new(4, 6)));
+        assert!(map.line_of_sight(&Coordinates::new(0, 6), &Coordinates::new(8, 6)));
+        assert!(map.line_of_sight(&Coordinates::new(1, 1), &Coordinates::new(1, 1)));
+    }
+}
@@ -1,15 +1,38 @@
 pub use crate::engine::Engine;
-pub use crate::engine::pawn::Pawn;
+pub use crate::engine::behavior::Behavior;
+pub use crate::engine::event::GameEvent;
+pub use crate::engine::fog::{Fog, FogState};
+pub use crate::engine::motion::{DEFAULT_SPEED, MAX_TILES_PER_ADVANCE, Motion};
+pub use crate::engine::pawn::{Pawn, PawnId};
 pub use crate::engine::scene::Scene;
+pub use crate::engine::turns::{Combatant, DEFAULT_ACTION_POINTS, TurnEvent, Turns};
 pub use crate::errors::RPGXError;
 pub use crate::eucl::coordinates::Coordinates;
 pub use crate::eucl::delta::Delta;
 pub use crate::eucl::direction::Direction;
 pub use crate::eucl::rect::{Rect, RectError};
 pub use crate::eucl::shape::Shape;
-pub use crate::library::Library;
+pub use crate::library::{
+    ActionId, Actions, Library, LibraryId, NAMESPACE_SEPARATOR, Remap, RenderId, Renders,
+    ResourceRemap, Resources, TextureId, Textures, namespaced,
+};
 pub use crate::map::Map;
-pub use crate::map::effect::Effect;
+pub use crate::map::effect::{Effect, Trigger, TriggerOn};
+pub use crate::map::field::DistanceField;
+#[cfg(feature = "serde")]
+pub use crate::map::file::{
+    EffectFile, LayerFile, MAP_FILE_VERSION, MapFile, MapFileError, MapFormat, MaskFile,
+    ResourceKind,
+};
+pub use crate::map::hierarchy::Hierarchy;
+pub use crate::map::index::{TileIndex, TileInfo, TileTrigger};
 pub use crate::map::layer::Layer;
+#[cfg(feature = "ldtk")]
+pub use crate::map::ldtk::{LdtkError, LdtkLevel, LdtkProject, TileSource};
 pub use crate::map::mask::Mask;
+pub use crate::map::planner::Planner;
+pub use crate::map::reach::Reachable;
+pub use crate::map::routing::{ActionPolicy, Movement, PathOptions, TieBreak};
+#[cfg(feature = "tiled")]
+pub use crate::map::tiled::{TiledError, TiledFormat};
 pub use crate::traits::*;
@@ -0,0 +1,72 @@
+use crate::{
+    prelude::{WasmCoordinates, WasmRect},
+    traits::WasmWrapper,
+};
+use rpgx::prelude::{Behavior, PawnId};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(js_name = Behavior)]
+#[derive(Clone, Debug)]
+pub struct WasmBehavior {
+    inner: Behavior,
+}
+
+impl WasmWrapper<Behavior> for WasmBehavior {
+    fn from_inner(inner: Behavior) -> Self {
+        WasmBehavior { inner }
+    }
+
+    fn inner(&self) -> &Behavior {
+        &self.inner
+    }
+
+    fn into_inner(self) -> Behavior {
+        self.inner
+    }
+}
+
+#[wasm_bindgen(js_class = Behavior)]
+impl WasmBehavior {
+    // === Constructors ===
+
+    #[wasm_bindgen(js_name = patrol)]
+    pub fn patrol(waypoints: Vec<WasmCoordinates>) -> WasmBehavior {
+        WasmBehavior {
+            inner: Behavior::patrol(waypoints.into_iter().map(|w| w.into_inner()).collect()),
+        }
+    }
+
+    #[wasm_bindgen(js_name = wander)]
+    pub fn wander(area: &WasmRect, seed: u64) -> WasmBehavior {
+        WasmBehavior {
+            inner: Behavior::wander(*area.inner(), seed),
+        }
+    }
+
+    #[wasm_bindgen(js_name = follow)]
+    pub fn follow(target: u32, distance: u32) -> WasmBehavior {
+        WasmBehavior {
+            inner: Behavior::follow(PawnId(target), distance),
+        }
+    }
+
+    #[wasm_bindgen(js_name = flee)]
+    pub fn flee(from: &WasmCoordinates, distance: u32) -> WasmBehavior {
+        WasmBehavior {
+            inner: Behavior::flee(*from.inner(), distance),
+        }
+    }
+
+    // === Accessors ===
+
+    #[wasm_bindgen(js_name = kind)]
+    pub fn kind(&self) -> String {
+        match self.inner {
+            Behavior::Patrol { .. } => "Patrol",
+            Behavior::Wander { .. } => "Wander",
+            Behavior::Follow { .. } => "Follow",
+            Behavior::Flee { .. } => "Flee",
+        }
+        .to_string()
+    }
+}
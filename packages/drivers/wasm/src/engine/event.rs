@@ -0,0 +1,132 @@
+use crate::{prelude::WasmCoordinates, traits::WasmWrapper};
+use rpgx::prelude::{GameEvent, TurnEvent};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(js_name = GameEvent)]
+#[derive(Clone, Debug)]
+pub struct WasmGameEvent {
+    inner: GameEvent,
+}
+
+impl WasmWrapper<GameEvent> for WasmGameEvent {
+    fn from_inner(inner: GameEvent) -> Self {
+        WasmGameEvent { inner }
+    }
+
+    fn inner(&self) -> &GameEvent {
+        &self.inner
+    }
+
+    fn into_inner(self) -> GameEvent {
+        self.inner
+    }
+}
+
+#[wasm_bindgen(js_class = GameEvent)]
+impl WasmGameEvent {
+    /// Returns `"PawnMoved"`, `"MaskEntered"`, `"MaskExited"`, `"ActionTriggered"`,
+    /// `"Blocked"`, `"ScenePushed"`, `"ScenePopped"`, `"TurnStarted"`, `"TurnEnded"`
+    /// or `"TurnSkipped"`.
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> String {
+        match self.inner {
+            GameEvent::PawnMoved { .. } => "PawnMoved",
+            GameEvent::MaskEntered { .. } => "MaskEntered",
+            GameEvent::MaskExited { .. } => "MaskExited",
+            GameEvent::ActionTriggered { .. } => "ActionTriggered",
+            GameEvent::Blocked { .. } => "Blocked",
+            GameEvent::ScenePushed { .. } => "ScenePushed",
+            GameEvent::ScenePopped { .. } => "ScenePopped",
+            GameEvent::Turn(TurnEvent::Started { .. }) => "TurnStarted",
+            GameEvent::Turn(TurnEvent::Ended { .. }) => "TurnEnded",
+            GameEvent::Turn(TurnEvent::Skipped { .. }) => "TurnSkipped",
+        }
+        .to_string()
+    }
+
+    /// Pawn the event is about, if any.
+    #[wasm_bindgen(getter)]
+    pub fn pawn(&self) -> Option<u32> {
+        match &self.inner {
+            GameEvent::PawnMoved { pawn, .. }
+            | GameEvent::MaskEntered { pawn, .. }
+            | GameEvent::MaskExited { pawn, .. }
+            | GameEvent::ActionTriggered { pawn, .. }
+            | GameEvent::Blocked { pawn, .. }
+            | GameEvent::Turn(TurnEvent::Started { pawn, .. })
+            | GameEvent::Turn(TurnEvent::Ended { pawn, .. })
+            | GameEvent::Turn(TurnEvent::Skipped { pawn, .. }) => Some(pawn.0),
+            GameEvent::ScenePushed { .. } | GameEvent::ScenePopped { .. } => None,
+        }
+    }
+
+    /// Tile left by a `PawnMoved` pawn.
+    #[wasm_bindgen(getter)]
+    pub fn from(&self) -> Option<WasmCoordinates> {
+        match &self.inner {
+            GameEvent::PawnMoved { from, .. } => Some(WasmCoordinates::from_inner(*from)),
+            _ => None,
+        }
+    }
+
+    /// Tile the pawn stands on after a move, mask or action event.
+    #[wasm_bindgen(getter)]
+    pub fn at(&self) -> Option<WasmCoordinates> {
+        match &self.inner {
+            GameEvent::PawnMoved { to: at, .. }
+            | GameEvent::MaskEntered { at, .. }
+            | GameEvent::MaskExited { at, .. }
+            | GameEvent::ActionTriggered { at, .. } => Some(WasmCoordinates::from_inner(*at)),
+            _ => None,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mask(&self) -> Option<String> {
+        match &self.inner {
+            GameEvent::MaskEntered { mask, .. } | GameEvent::MaskExited { mask, .. } => {
+                Some(mask.clone())
+            }
+            _ => None,
+        }
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn action(&self) -> Option<u32> {
+        match &self.inner {
+            GameEvent::ActionTriggered { action, .. } => Some(action.0),
+            _ => None,
+        }
+    }
+
+    /// Why a `Blocked` move was refused.
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        match &self.inner {
+            GameEvent::Blocked { error, .. } => Some(format!("{:?}", error)),
+            _ => None,
+        }
+    }
+
+    /// Timeline index of a pushed or popped scene.
+    #[wasm_bindgen(getter)]
+    pub fn index(&self) -> Option<usize> {
+        match &self.inner {
+            GameEvent::ScenePushed { index } | GameEvent::ScenePopped { index } => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Round of a turn event.
+    #[wasm_bindgen(getter)]
+    pub fn round(&self) -> Option<u32> {
+        match &self.inner {
+            GameEvent::Turn(
+                TurnEvent::Started { round, .. }
+                | TurnEvent::Ended { round, .. }
+                | TurnEvent::Skipped { round, .. },
+            ) => Some(*round),
+            _ => None,
+        }
+    }
+}
@@ -1,7 +1,12 @@
+pub mod behavior;
+pub mod event;
 pub mod pawn;
 pub mod scene;
 
-use crate::{engine::scene::WasmScene, traits::WasmWrapper};
+use crate::{
+    engine::{event::WasmGameEvent, scene::WasmScene},
+    traits::WasmWrapper,
+};
 use js_sys::Array;
 use rpgx::prelude::Engine;
 use wasm_bindgen::prelude::*;
@@ -100,6 +105,55 @@ impl WasmEngine {
             .collect()
     }
 
+    /// Advance the active scene by `dt` seconds, moving pawns at their own speed.
+    ///
+    /// Returns the ids of the pawns that arrived on a tile.
+    #[wasm_bindgen]
+    pub fn tick(&mut self, dt: f32) -> Vec<u32> {
+        self.inner
+            .tick(dt)
+            .into_iter()
+            .map(|(id, _)| id.0)
+            .collect()
+    }
+
+    /// Pawn whose turn it is in the active scene, if it is in turn-based mode.
+    #[wasm_bindgen(js_name = currentTurn)]
+    pub fn current_turn(&self) -> Option<u32> {
+        self.inner.current_turn().map(|id| id.0)
+    }
+
+    #[wasm_bindgen(js_name = endTurn)]
+    pub fn end_turn(&mut self) -> Option<u32> {
+        self.inner.end_turn().map(|id| id.0)
+    }
+
+    /// Events since the last call, oldest first, after passing them to the subscribers.
+    #[wasm_bindgen(js_name = drainEvents)]
+    pub fn drain_events(&mut self) -> Vec<WasmGameEvent> {
+        self.inner
+            .drain_events()
+            .into_iter()
+            .map(WasmGameEvent::from_inner)
+            .collect()
+    }
+
+    /// Call `callback` with every event drained by `drainEvents`.
+    ///
+    /// Returns an id to pass to `unsubscribe`.
+    #[wasm_bindgen]
+    pub fn subscribe(&mut self, callback: js_sys::Function) -> usize {
+        self.inner.subscribe(move |event| {
+            let event = JsValue::from(WasmGameEvent::from_inner(event.clone()));
+            let _ = callback.call1(&JsValue::NULL, &event);
+        })
+    }
+
+    #[wasm_bindgen]
+    pub fn unsubscribe(&mut self, id: usize) -> bool {
+        self.inner.unsubscribe(id)
+    }
+
     /// Get current time index
     #[wasm_bindgen(js_name = getCurrentIndex)]
     pub fn get_current_index(&self) -> usize {
@@ -1,5 +1,5 @@
 use crate::{prelude::WasmCoordinates, traits::WasmWrapper}; // Assuming you have a WasmTile wrapper
-use rpgx::prelude::Pawn;
+use rpgx::prelude::{Pawn, TextureId};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(js_name = Pawn)]
@@ -31,7 +31,7 @@ impl WasmPawn {
         WasmPawn {
             inner: Pawn {
                 pointer: pointer.into_inner(),
-                texture_id,
+                texture_id: TextureId(texture_id),
             },
         }
     }
@@ -48,11 +48,11 @@ impl WasmPawn {
 
     #[wasm_bindgen(getter, js_name = textureId)]
     pub fn texture_id(&self) -> u32 {
-        self.inner.texture_id
+        self.inner.texture_id.0
     }
 
     #[wasm_bindgen(setter, js_name = textureId)]
     pub fn set_texture_id(&mut self, texture_id: u32) {
-        self.inner.texture_id = texture_id;
+        self.inner.texture_id = TextureId(texture_id);
     }
 }
@@ -1,9 +1,12 @@
+use crate::engine::behavior::WasmBehavior;
+use crate::engine::event::WasmGameEvent;
 use crate::engine::pawn::WasmPawn;
 use crate::eucl::coordinates::WasmCoordinates;
 use crate::eucl::direction::WasmDirection;
 use crate::map::WasmMap;
+use crate::map::routing::WasmPathOptions;
 use crate::traits::WasmWrapper;
-use rpgx::prelude::Scene;
+use rpgx::prelude::{Combatant, FogState, PawnId, Scene, TextureId};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(js_name = Scene)]
@@ -38,36 +41,83 @@ impl WasmScene {
         self.inner.name.clone()
     }
 
+    /// Loads a pawn at the map spawn and returns its id.
     #[wasm_bindgen(js_name = loadPawn)]
-    pub fn load_pawn(&mut self, texture_id: u32) {
-        self.inner.load_pawn(texture_id);
+    pub fn load_pawn(&mut self, texture_id: u32) -> u32 {
+        self.inner.load_pawn(TextureId(texture_id)).0
     }
 
+    /// Loads a pawn at its own position and returns its id.
     #[wasm_bindgen(js_name = loadPawnAt)]
-    pub fn load_pawn_at(&mut self, pawn: WasmPawn) {
-        self.inner.load_pawn_at(pawn.into_inner());
+    pub fn load_pawn_at(&mut self, pawn: WasmPawn) -> u32 {
+        self.inner.load_pawn_at(pawn.into_inner()).0
+    }
+
+    #[wasm_bindgen(js_name = removePawn)]
+    pub fn remove_pawn(&mut self, pawn: u32) -> Option<WasmPawn> {
+        self.inner
+            .remove_pawn(PawnId(pawn))
+            .map(WasmPawn::from_inner)
+    }
+
+    /// Returns the ids of every pawn, in loading order.
+    #[wasm_bindgen(js_name = pawnIds)]
+    pub fn pawn_ids(&self) -> Vec<u32> {
+        self.inner.pawns.keys().map(|id| id.0).collect()
+    }
+
+    #[wasm_bindgen(js_name = pawnAt)]
+    pub fn pawn_at(&self, target: &WasmCoordinates) -> Option<u32> {
+        self.inner.pawn_at(target.inner()).map(|id| id.0)
+    }
+
+    #[wasm_bindgen(getter, js_name = pawnCollision)]
+    pub fn pawn_collision(&self) -> bool {
+        self.inner.pawn_collision
+    }
+
+    #[wasm_bindgen(setter, js_name = pawnCollision)]
+    pub fn set_pawn_collision(&mut self, value: bool) {
+        self.inner.pawn_collision = value;
     }
 
     #[wasm_bindgen(js_name = moveTo)]
-    pub fn move_to(&mut self, target: &WasmCoordinates) -> Result<WasmCoordinates, JsValue> {
+    pub fn move_to(
+        &mut self,
+        pawn: u32,
+        target: &WasmCoordinates,
+    ) -> Result<WasmCoordinates, JsValue> {
         self.inner
-            .move_to(target.clone().into_inner())
+            .move_to(PawnId(pawn), target.clone().into_inner())
             .map(WasmCoordinates::from_inner)
             .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
     }
 
     #[wasm_bindgen(js_name = stepTo)]
-    pub fn step_to(&mut self, direction: WasmDirection) -> Result<WasmCoordinates, JsValue> {
+    pub fn step_to(
+        &mut self,
+        pawn: u32,
+        direction: WasmDirection,
+    ) -> Result<WasmCoordinates, JsValue> {
         self.inner
-            .step_to(direction.into_inner())
+            .step_to(PawnId(pawn), direction.into_inner())
             .map(WasmCoordinates::from_inner)
             .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
     }
 
     #[wasm_bindgen(js_name = stepsTo)]
-    pub fn steps_to(&self, target: &WasmCoordinates) -> Result<js_sys::Array, JsValue> {
+    pub fn steps_to(
+        &self,
+        pawn: u32,
+        target: &WasmCoordinates,
+        options: Option<WasmPathOptions>,
+    ) -> Result<js_sys::Array, JsValue> {
         self.inner
-            .steps_to(target.clone().into_inner())
+            .steps_to_with(
+                PawnId(pawn),
+                target.clone().into_inner(),
+                &options.map(|o| o.into_inner()).unwrap_or_default(),
+            )
             .map(|steps| {
                 steps
                     .into_iter()
@@ -79,9 +129,18 @@ impl WasmScene {
     }
 
     #[wasm_bindgen(js_name = walkTo)]
-    pub async fn walk_to(&mut self, target: WasmCoordinates) -> Result<WasmCoordinates, JsValue> {
+    pub async fn walk_to(
+        &mut self,
+        pawn: u32,
+        target: WasmCoordinates,
+        options: Option<WasmPathOptions>,
+    ) -> Result<WasmCoordinates, JsValue> {
         self.inner
-            .walk_to(target.into_inner())
+            .walk_to_with(
+                PawnId(pawn),
+                target.into_inner(),
+                &options.map(|o| o.into_inner()).unwrap_or_default(),
+            )
             .await
             .map(WasmCoordinates::from_inner)
             .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
@@ -93,7 +152,209 @@ impl WasmScene {
     }
 
     #[wasm_bindgen(js_name = getPawn)]
-    pub fn get_pawn(&self) -> Option<WasmPawn> {
-        self.inner.pawn.clone().map(WasmPawn::from_inner)
+    pub fn get_pawn(&self, pawn: u32) -> Option<WasmPawn> {
+        self.inner
+            .pawn(PawnId(pawn))
+            .cloned()
+            .map(WasmPawn::from_inner)
+    }
+
+    /// Order a pawn to walk to `target`, one step at a time as `Engine.tick` runs.
+    #[wasm_bindgen]
+    pub fn walk(&mut self, pawn: u32, target: WasmCoordinates) -> Result<(), JsValue> {
+        self.inner
+            .walk(PawnId(pawn), target.into_inner())
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+
+    #[wasm_bindgen]
+    pub fn stop(&mut self, pawn: u32) {
+        self.inner.stop(PawnId(pawn));
+    }
+
+    /// Set how fast a pawn moves, in tiles per second.
+    #[wasm_bindgen(js_name = setSpeed)]
+    pub fn set_speed(&mut self, pawn: u32, speed: f32) -> Result<(), JsValue> {
+        self.inner
+            .set_speed(PawnId(pawn), speed)
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+
+    #[wasm_bindgen(js_name = isMoving)]
+    pub fn is_moving(&self, pawn: u32) -> bool {
+        self.inner.is_moving(PawnId(pawn))
+    }
+
+    /// Returns `[x, y]` where the pawn should be drawn, in tiles, between two tiles while moving.
+    #[wasm_bindgen(js_name = interpolatedPosition)]
+    pub fn interpolated_position(&self, pawn: u32) -> Option<Vec<f32>> {
+        self.inner
+            .interpolated_position(PawnId(pawn))
+            .map(|(x, y)| vec![x, y])
+    }
+
+    /// Set the initiative and the action points per turn of a pawn.
+    #[wasm_bindgen(js_name = setCombatant)]
+    pub fn set_combatant(
+        &mut self,
+        pawn: u32,
+        initiative: i32,
+        action_points: u32,
+    ) -> Result<(), JsValue> {
+        self.inner
+            .set_combatant(
+                PawnId(pawn),
+                Combatant {
+                    initiative,
+                    action_points,
+                },
+            )
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+
+    /// Switch to turn-based mode: only the pawn whose turn it is can move.
+    #[wasm_bindgen(js_name = startTurns)]
+    pub fn start_turns(&mut self) {
+        self.inner.start_turns();
+    }
+
+    #[wasm_bindgen(js_name = stopTurns)]
+    pub fn stop_turns(&mut self) {
+        self.inner.stop_turns();
+    }
+
+    #[wasm_bindgen(js_name = currentTurn)]
+    pub fn current_turn(&self) -> Option<u32> {
+        self.inner.current_turn().map(|id| id.0)
+    }
+
+    #[wasm_bindgen(js_name = actionPoints)]
+    pub fn action_points(&self) -> Option<u32> {
+        self.inner.action_points()
+    }
+
+    /// End the current turn, returning the pawn whose turn it is next.
+    #[wasm_bindgen(js_name = endTurn)]
+    pub fn end_turn(&mut self) -> Option<u32> {
+        self.inner.end_turn().map(|id| id.0)
+    }
+
+    #[wasm_bindgen(js_name = skipTurn)]
+    pub fn skip_turn(&mut self, pawn: u32) -> Result<(), JsValue> {
+        self.inner
+            .skip_turn(PawnId(pawn))
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+
+    /// Events of this scene since the last call. Prefer `Engine.drainEvents`,
+    /// which also reports timeline changes.
+    #[wasm_bindgen(js_name = drainEvents)]
+    pub fn drain_events(&mut self) -> Vec<WasmGameEvent> {
+        self.inner
+            .drain_events()
+            .into_iter()
+            .map(WasmGameEvent::from_inner)
+            .collect()
+    }
+
+    /// Fire the interact actions of `target` for a pawn, returning the fired action ids.
+    #[wasm_bindgen(js_name = interactAt)]
+    pub fn interact_at(&mut self, pawn: u32, target: WasmCoordinates) -> Result<Vec<u32>, JsValue> {
+        self.inner
+            .interact_at(PawnId(pawn), target.into_inner())
+            .map(|actions| actions.into_iter().map(|action| action.0).collect())
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+
+    /// Direction a pawn faces, updated by its moves.
+    pub fn facing(&self, pawn: u32) -> Option<WasmDirection> {
+        self.inner
+            .facing(PawnId(pawn))
+            .map(WasmDirection::from_inner)
+    }
+
+    /// Turn a pawn toward a direction without moving it.
+    pub fn face(&mut self, pawn: u32, direction: WasmDirection) -> Result<(), JsValue> {
+        self.inner
+            .face(PawnId(pawn), direction.into_inner())
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+
+    /// Tile in front of a pawn, if any.
+    #[wasm_bindgen(js_name = facingTile)]
+    pub fn facing_tile(&self, pawn: u32) -> Option<WasmCoordinates> {
+        self.inner
+            .facing_tile(PawnId(pawn))
+            .map(WasmCoordinates::from_inner)
+    }
+
+    /// Fire the interact actions of the tile in front of a pawn, returning the fired
+    /// action ids.
+    pub fn interact(&mut self, pawn: u32) -> Result<Vec<u32>, JsValue> {
+        self.inner
+            .interact(PawnId(pawn))
+            .map(|actions| actions.into_iter().map(|action| action.0).collect())
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+
+    /// Give a pawn a movement behavior, run by `Engine.tick`.
+    #[wasm_bindgen(js_name = setBehavior)]
+    pub fn set_behavior(&mut self, pawn: u32, behavior: WasmBehavior) -> Result<(), JsValue> {
+        self.inner
+            .set_behavior(PawnId(pawn), behavior.into_inner())
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+
+    #[wasm_bindgen(js_name = clearBehavior)]
+    pub fn clear_behavior(&mut self, pawn: u32) -> Option<WasmBehavior> {
+        self.inner
+            .clear_behavior(PawnId(pawn))
+            .map(WasmBehavior::from_inner)
+    }
+
+    /// Start tracking the tiles a pawn sees within `radius` tiles.
+    #[wasm_bindgen(js_name = enableFog)]
+    pub fn enable_fog(&mut self, pawn: u32, radius: u32) -> Result<(), JsValue> {
+        self.inner
+            .enable_fog(PawnId(pawn), radius)
+            .map_err(|e| JsValue::from_str(&format!("{:?}", e)))
+    }
+
+    #[wasm_bindgen(js_name = disableFog)]
+    pub fn disable_fog(&mut self, pawn: u32) {
+        self.inner.disable_fog(PawnId(pawn));
+    }
+
+    /// Returns `"Unknown"`, `"Explored"` or `"Visible"`, or `undefined` when the fog is disabled.
+    #[wasm_bindgen(js_name = fogStateAt)]
+    pub fn fog_state_at(&self, pawn: u32, target: &WasmCoordinates) -> Option<String> {
+        self.inner.fog(PawnId(pawn)).map(|fog| {
+            match fog.state_at(target.inner()) {
+                FogState::Unknown => "Unknown",
+                FogState::Explored => "Explored",
+                FogState::Visible => "Visible",
+            }
+            .to_string()
+        })
+    }
+
+    #[wasm_bindgen(js_name = visibleTiles)]
+    pub fn visible_tiles(&self, pawn: u32) -> Vec<WasmCoordinates> {
+        self.inner
+            .fog(PawnId(pawn))
+            .into_iter()
+            .flat_map(|fog| fog.visible().iter().copied())
+            .map(WasmCoordinates::from_inner)
+            .collect()
+    }
+
+    #[wasm_bindgen(js_name = exploredTiles)]
+    pub fn explored_tiles(&self, pawn: u32) -> Vec<WasmCoordinates> {
+        self.inner
+            .fog(PawnId(pawn))
+            .into_iter()
+            .flat_map(|fog| fog.explored().iter().copied())
+            .map(WasmCoordinates::from_inner)
+            .collect()
     }
 }
@@ -24,15 +24,24 @@ impl WasmWrapper<Direction> for WasmDirection {
 
 #[wasm_bindgen(js_class = Direction)]
 impl WasmDirection {
-    /// Constructs a WasmDirection from a string like "Up", "Down", "Left", "Right".
+    /// Constructs a WasmDirection from a string like "Up", "Down", "Left", "Right",
+    /// or a diagonal such as "UpLeft" / "down-right".
     /// Returns None if the string does not match any direction.
     #[wasm_bindgen(constructor)]
     pub fn new(direction_str: String) -> WasmDirection {
-        let dir = match direction_str.to_lowercase().as_str() {
+        let dir = match direction_str
+            .to_lowercase()
+            .replace(['-', '_'], "")
+            .as_str()
+        {
             "up" => Direction::Up,
             "down" => Direction::Down,
             "left" => Direction::Left,
             "right" => Direction::Right,
+            "upleft" => Direction::UpLeft,
+            "upright" => Direction::UpRight,
+            "downleft" => Direction::DownLeft,
+            "downright" => Direction::DownRight,
             _ => panic!("Invalid direction string"),
         };
         WasmDirection { inner: dir }
@@ -44,9 +53,19 @@ impl WasmDirection {
             Direction::Down => "Down".to_string(),
             Direction::Left => "Left".to_string(),
             Direction::Right => "Right".to_string(),
+            Direction::UpLeft => "UpLeft".to_string(),
+            Direction::UpRight => "UpRight".to_string(),
+            Direction::DownLeft => "DownLeft".to_string(),
+            Direction::DownRight => "DownRight".to_string(),
         }
     }
 
+    /// Returns true for the four diagonal directions.
+    #[wasm_bindgen(js_name = isDiagonal)]
+    pub fn is_diagonal(&self) -> bool {
+        self.inner.is_diagonal()
+    }
+
     /// Creates a WasmDirection from a WasmDelta if possible.
     #[wasm_bindgen(js_name = fromDelta)]
     pub fn from_delta(delta: &WasmDelta) -> Option<WasmDirection> {
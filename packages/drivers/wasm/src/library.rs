@@ -1,10 +1,10 @@
 use rpgx::library::Library;
-use std::any::Any;
 use wasm_bindgen::prelude::*;
 
+/// Resources shared by textures, actions and renders, with a single id space.
 #[wasm_bindgen(js_name = Library)]
 pub struct WasmLibrary {
-    inner: Library<Box<dyn Any>>,
+    inner: Library<u32, JsValue>,
 }
 
 #[wasm_bindgen(js_class = Library)]
@@ -16,40 +16,43 @@ impl WasmLibrary {
         }
     }
 
+    /// Stores a value and returns its id.
     #[wasm_bindgen]
-    pub fn insert(&mut self, key: &str, value: JsValue) {
-        // Safety: converting &str to &'static str by leaking it.
-        // You should manage this carefully or use another approach for dynamic lifetimes.
-        let static_key: &'static str = Box::leak(key.to_string().into_boxed_str());
-        self.inner.insert(static_key, Box::new(value));
+    pub fn insert(&mut self, key: &str, value: JsValue) -> u32 {
+        self.inner.insert(key, value)
+    }
+
+    /// Removes a value and returns it. Its id is never given again.
+    #[wasm_bindgen]
+    pub fn remove(&mut self, id: u32) -> JsValue {
+        self.inner.remove(id).unwrap_or(JsValue::NULL)
+    }
+
+    /// Keys of the library in id order, or those within `namespace` if given.
+    #[wasm_bindgen]
+    pub fn keys(&self, namespace: Option<String>) -> Vec<String> {
+        match namespace {
+            Some(namespace) => self
+                .inner
+                .iter_namespace(&namespace)
+                .map(|(_, key, _)| key.to_string())
+                .collect(),
+            None => self
+                .inner
+                .iter()
+                .map(|(_, key, _)| key.to_string())
+                .collect(),
+        }
     }
 
     #[wasm_bindgen(js_name = getByKey)]
     pub fn get_by_key(&self, key: &str) -> JsValue {
-        match self.inner.get_by_key(key) {
-            Some(boxed) => {
-                if let Some(s) = boxed.downcast_ref::<JsValue>() {
-                    s.clone()
-                } else {
-                    JsValue::NULL
-                }
-            }
-            None => JsValue::NULL,
-        }
+        self.inner.get_by_key(key).cloned().unwrap_or(JsValue::NULL)
     }
 
     #[wasm_bindgen(js_name = getById)]
     pub fn get_by_id(&self, id: u32) -> JsValue {
-        match self.inner.get_by_id(id) {
-            Some(boxed) => {
-                if let Some(s) = boxed.downcast_ref::<JsValue>() {
-                    s.clone()
-                } else {
-                    JsValue::NULL
-                }
-            }
-            None => JsValue::NULL,
-        }
+        self.inner.get_by_id(id).cloned().unwrap_or(JsValue::NULL)
     }
 
     #[wasm_bindgen(js_name = getId)]
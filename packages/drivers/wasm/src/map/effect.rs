@@ -1,6 +1,6 @@
 use crate::prelude::{WasmDelta, WasmRect};
 use crate::traits::WasmWrapper;
-use rpgx::prelude::Effect;
+use rpgx::prelude::{ActionId, Effect, RenderId, TextureId, Trigger, TriggerOn};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen(js_name = Effect)]
@@ -37,21 +37,36 @@ impl WasmEffect {
     #[wasm_bindgen(js_name = action)]
     pub fn action(id: u32) -> WasmEffect {
         WasmEffect {
-            inner: Effect::Action(id),
+            inner: Effect::Action(ActionId(id)),
         }
     }
 
+    /// Action fired `on` `"enter"`, `"exit"`, `"interact"` or `"stand"`, optionally once only.
+    #[wasm_bindgen(js_name = trigger)]
+    pub fn trigger(id: u32, on: String, once: bool) -> Result<WasmEffect, JsValue> {
+        let on = match on.to_lowercase().as_str() {
+            "enter" => TriggerOn::Enter,
+            "exit" => TriggerOn::Exit,
+            "interact" => TriggerOn::Interact,
+            "stand" => TriggerOn::Stand,
+            _ => return Err(JsValue::from_str(&format!("Invalid trigger: {}", on))),
+        };
+        Ok(WasmEffect {
+            inner: Effect::Trigger(ActionId(id), Trigger { on, once }),
+        })
+    }
+
     #[wasm_bindgen(js_name = texture)]
     pub fn texture(id: u32) -> WasmEffect {
         WasmEffect {
-            inner: Effect::Texture(id),
+            inner: Effect::Texture(TextureId(id)),
         }
     }
 
     #[wasm_bindgen(js_name = render)]
     pub fn render(id: u32) -> WasmEffect {
         WasmEffect {
-            inner: Effect::Render(id),
+            inner: Effect::Render(RenderId(id)),
         }
     }
 
@@ -62,6 +77,20 @@ impl WasmEffect {
         }
     }
 
+    #[wasm_bindgen(js_name = opaque)]
+    pub fn opaque(rect: &WasmRect) -> WasmEffect {
+        WasmEffect {
+            inner: Effect::Opaque(*rect.inner()),
+        }
+    }
+
+    #[wasm_bindgen(js_name = cost)]
+    pub fn cost(value: u32) -> WasmEffect {
+        WasmEffect {
+            inner: Effect::Cost(value),
+        }
+    }
+
     // === Accessors ===
 
     #[wasm_bindgen(js_name = kind)]
@@ -69,26 +98,55 @@ impl WasmEffect {
         match self.inner {
             Effect::None => "None",
             Effect::Action(_) => "Action",
+            Effect::Trigger(..) => "Trigger",
             Effect::Texture(_) => "Texture",
             Effect::Render(_) => "Render",
             Effect::Block(_) => "Block",
+            Effect::Cost(_) => "Cost",
+            Effect::Opaque(_) => "Opaque",
         }
         .to_string()
     }
 
+    /// Action id of an `Action` or `Trigger` effect.
     #[wasm_bindgen(js_name = asAction)]
     pub fn as_action(&self) -> Option<u32> {
-        if let Effect::Action(id) = self.inner {
-            Some(id)
+        if let Effect::Action(id) | Effect::Trigger(id, _) = self.inner {
+            Some(id.0)
         } else {
             None
         }
     }
 
+    /// Returns `"enter"`, `"exit"`, `"interact"` or `"stand"` for action effects.
+    #[wasm_bindgen(js_name = triggerOn)]
+    pub fn trigger_on(&self) -> Option<String> {
+        let on = match self.inner {
+            Effect::Action(_) => TriggerOn::Enter,
+            Effect::Trigger(_, trigger) => trigger.on,
+            _ => return None,
+        };
+        Some(
+            match on {
+                TriggerOn::Enter => "enter",
+                TriggerOn::Exit => "exit",
+                TriggerOn::Interact => "interact",
+                TriggerOn::Stand => "stand",
+            }
+            .to_string(),
+        )
+    }
+
+    /// Whether an action effect fires only once.
+    #[wasm_bindgen(js_name = isOnce)]
+    pub fn is_once(&self) -> bool {
+        matches!(self.inner, Effect::Trigger(_, trigger) if trigger.once)
+    }
+
     #[wasm_bindgen(js_name = asTexture)]
     pub fn as_texture(&self) -> Option<u32> {
         if let Effect::Texture(id) = self.inner {
-            Some(id)
+            Some(id.0)
         } else {
             None
         }
@@ -97,7 +155,7 @@ impl WasmEffect {
     #[wasm_bindgen(js_name = asRender)]
     pub fn as_render(&self) -> Option<u32> {
         if let Effect::Render(id) = self.inner {
-            Some(id)
+            Some(id.0)
         } else {
             None
         }
@@ -112,6 +170,24 @@ impl WasmEffect {
         }
     }
 
+    #[wasm_bindgen(js_name = asCost)]
+    pub fn as_cost(&self) -> Option<u32> {
+        if let Effect::Cost(value) = self.inner {
+            Some(value)
+        } else {
+            None
+        }
+    }
+
+    #[wasm_bindgen(js_name = asOpaque)]
+    pub fn as_opaque(&self) -> Option<WasmRect> {
+        if let Effect::Opaque(rect) = self.inner {
+            Some(WasmRect::from_inner(rect))
+        } else {
+            None
+        }
+    }
+
     // === Methods ===
 
     #[wasm_bindgen]
@@ -69,7 +69,7 @@ impl WasmMask {
 
     #[wasm_bindgen(js_name = getTexture)]
     pub fn get_texture(&self) -> Option<u32> {
-        self.inner.get_texture()
+        self.inner.get_texture().map(|id| id.0)
     }
 
     #[wasm_bindgen]
@@ -1,6 +1,7 @@
 pub mod effect;
 pub mod layer;
 pub mod mask;
+pub mod routing;
 
 use layer::WasmLayer;
 
@@ -93,7 +94,7 @@ impl WasmMap {
     #[wasm_bindgen(getter)]
     pub fn layers(&self) -> Vec<WasmLayer> {
         self.inner
-            .layers
+            .layers()
             .iter()
             .cloned()
             .map(WasmLayer::from_inner)
@@ -151,6 +152,32 @@ impl WasmMap {
     /// Returns all action IDs at a coordinate from all layers.
     #[wasm_bindgen(js_name = getActionsAt)]
     pub fn get_actions_at(&self, pointer: &WasmCoordinates) -> Vec<u32> {
-        self.inner.get_actions_at(pointer.inner())
+        self.inner
+            .get_actions_at(pointer.inner())
+            .into_iter()
+            .map(|id| id.0)
+            .collect()
+    }
+
+    /// Returns true if an opaque area covers the coordinate.
+    #[wasm_bindgen(js_name = isOpaqueAt)]
+    pub fn is_opaque_at(&self, pointer: &WasmCoordinates) -> bool {
+        self.inner.is_opaque_at(pointer.inner())
+    }
+
+    /// Returns the tiles visible from `origin` within `radius` tiles.
+    #[wasm_bindgen(js_name = fieldOfView)]
+    pub fn field_of_view(&self, origin: &WasmCoordinates, radius: u32) -> Vec<WasmCoordinates> {
+        self.inner
+            .field_of_view(origin.inner(), radius)
+            .into_iter()
+            .map(WasmCoordinates::from_inner)
+            .collect()
+    }
+
+    /// Returns true if no opaque tile lies on the straight line between two coordinates.
+    #[wasm_bindgen(js_name = lineOfSight)]
+    pub fn line_of_sight(&self, from: &WasmCoordinates, to: &WasmCoordinates) -> bool {
+        self.inner.line_of_sight(from.inner(), to.inner())
     }
 }
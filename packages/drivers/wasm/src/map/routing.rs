@@ -0,0 +1,145 @@
+use std::collections::HashSet;
+
+use crate::{
+    prelude::{WasmCoordinates, WasmRect},
+    traits::WasmWrapper,
+};
+use rpgx::prelude::{ActionPolicy, Movement, PathOptions, TieBreak};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(js_name = PathOptions)]
+#[derive(Clone, Debug, Default)]
+pub struct WasmPathOptions {
+    inner: PathOptions,
+}
+
+impl WasmWrapper<PathOptions> for WasmPathOptions {
+    fn from_inner(inner: PathOptions) -> Self {
+        WasmPathOptions { inner }
+    }
+
+    fn inner(&self) -> &PathOptions {
+        &self.inner
+    }
+
+    fn into_inner(self) -> PathOptions {
+        self.inner
+    }
+}
+
+#[wasm_bindgen(js_class = PathOptions)]
+impl WasmPathOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmPathOptions {
+        WasmPathOptions::default()
+    }
+
+    /// Whether the route may take diagonal steps.
+    #[wasm_bindgen(getter)]
+    pub fn diagonal(&self) -> bool {
+        self.inner.movement == Movement::Octile
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_diagonal(&mut self, diagonal: bool) {
+        self.inner.movement = if diagonal {
+            Movement::Octile
+        } else {
+            Movement::Cardinal
+        };
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn bounds(&self) -> Option<WasmRect> {
+        self.inner.bounds.map(WasmRect::from_inner)
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_bounds(&mut self, bounds: Option<WasmRect>) {
+        self.inner.bounds = bounds.map(|b| b.into_inner());
+    }
+
+    #[wasm_bindgen(getter, js_name = maxNodes)]
+    pub fn max_nodes(&self) -> Option<usize> {
+        self.inner.max_nodes
+    }
+
+    #[wasm_bindgen(setter, js_name = maxNodes)]
+    pub fn set_max_nodes(&mut self, max_nodes: Option<usize>) {
+        self.inner.max_nodes = max_nodes;
+    }
+
+    #[wasm_bindgen(getter, js_name = maxCost)]
+    pub fn max_cost(&self) -> Option<u32> {
+        self.inner.max_cost
+    }
+
+    #[wasm_bindgen(setter, js_name = maxCost)]
+    pub fn set_max_cost(&mut self, max_cost: Option<u32>) {
+        self.inner.max_cost = max_cost;
+    }
+
+    #[wasm_bindgen(getter, js_name = maxLength)]
+    pub fn max_length(&self) -> Option<usize> {
+        self.inner.max_length
+    }
+
+    #[wasm_bindgen(setter, js_name = maxLength)]
+    pub fn set_max_length(&mut self, max_length: Option<usize>) {
+        self.inner.max_length = max_length;
+    }
+
+    /// Whether action tiles other than the goal are avoided.
+    #[wasm_bindgen(getter, js_name = avoidActions)]
+    pub fn avoid_actions(&self) -> bool {
+        self.inner.actions == ActionPolicy::Avoid
+    }
+
+    #[wasm_bindgen(setter, js_name = avoidActions)]
+    pub fn set_avoid_actions(&mut self, avoid: bool) {
+        self.inner.actions = if avoid {
+            ActionPolicy::Avoid
+        } else {
+            ActionPolicy::Cross
+        };
+    }
+
+    /// Whether routes with fewer turns are preferred among equally cheap ones.
+    #[wasm_bindgen(getter, js_name = preferStraight)]
+    pub fn prefer_straight(&self) -> bool {
+        self.inner.tie_break == TieBreak::Straight
+    }
+
+    #[wasm_bindgen(setter, js_name = preferStraight)]
+    pub fn set_prefer_straight(&mut self, straight: bool) {
+        self.inner.tie_break = if straight {
+            TieBreak::Straight
+        } else {
+            TieBreak::Any
+        };
+    }
+
+    /// Tiles the route may not enter.
+    #[wasm_bindgen(getter)]
+    pub fn forbidden(&self) -> Vec<WasmCoordinates> {
+        self.inner
+            .forbidden
+            .iter()
+            .copied()
+            .map(WasmCoordinates::from_inner)
+            .collect()
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_forbidden(&mut self, tiles: Vec<WasmCoordinates>) {
+        self.inner.forbidden = tiles
+            .into_iter()
+            .map(|c| c.into_inner())
+            .collect::<HashSet<_>>();
+    }
+
+    /// Adds a tile the route may not enter.
+    pub fn forbid(&mut self, tile: &WasmCoordinates) {
+        self.inner.forbidden.insert(*tile.inner());
+    }
+}
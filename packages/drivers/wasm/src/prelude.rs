@@ -4,9 +4,13 @@ pub use crate::eucl::direction::WasmDirection;
 pub use crate::eucl::rect::WasmRect;
 pub use crate::eucl::shape::WasmShape;
 
+pub use crate::engine::behavior::WasmBehavior;
+pub use crate::engine::event::WasmGameEvent;
+
 pub use crate::map::WasmMap;
 pub use crate::map::effect::WasmEffect;
 pub use crate::map::layer::WasmLayer;
 pub use crate::map::mask::WasmMask;
+pub use crate::map::routing::WasmPathOptions;
 
 pub use crate::traits::*;
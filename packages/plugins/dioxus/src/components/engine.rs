@@ -1,27 +1,29 @@
-use std::any::Any;
-
 use dioxus::prelude::*;
-use rpgx::{
-    library::Library,
-    prelude::{Direction, RPGXError, Rect},
-};
+use rpgx::prelude::{Direction, PawnId, RPGXError, Rect};
 
 use crate::{
     components::{grid::Grid, pawn::Pawn},
-    controller::{use_controller, Command},
+    controller::{Command, use_controller},
+    resources::GameResources,
 };
 
 #[derive(PartialEq, Props, Clone)]
 pub struct EngineProps {
     pub engine: Signal<rpgx::prelude::Engine>,
-    pub library: Signal<Library<Box<dyn Any>>>,
+    pub library: Signal<GameResources>,
     pub square_size: u32,
+    /// Pawn moved by clicks and keys, followed by the camera and whose fog is drawn.
+    #[props(default)]
+    pub player: PawnId,
+    /// Areas highlighted above the map, forwarded to the [`Grid`].
+    #[props(default)]
+    pub overlay: Vec<Rect>,
 }
 
 #[allow(non_snake_case)]
 pub fn Engine(props: EngineProps) -> Element {
     let engine = props.engine.clone();
-    let controller = use_controller(engine.clone(), props.library.clone());
+    let controller = use_controller(engine.clone(), props.library.clone(), props.player);
 
     let onclick = move |tile: Rect| -> Result<(), RPGXError> {
         controller.send(Command::WalkTo(tile.origin));
@@ -30,6 +32,13 @@ pub fn Engine(props: EngineProps) -> Element {
 
     let onkeydown = {
         move |evt: KeyboardEvent| {
+            if matches!(evt.key(), Key::Enter)
+                || matches!(evt.key(), Key::Character(ref k) if matches!(k.as_str(), "e" | "E" | " "))
+            {
+                controller.send(Command::Interact);
+                return;
+            }
+
             let direction = match evt.key() {
                 Key::ArrowUp => Some(Direction::Up),
                 Key::ArrowDown => Some(Direction::Down),
@@ -96,6 +105,8 @@ pub fn Engine(props: EngineProps) -> Element {
                 engine: engine.clone(),
                 library: props.library.clone(),
                 square_size: props.square_size,
+                player: props.player,
+                overlay: props.overlay.clone(),
                 onclick: EventHandler::new(move |tile: Result<Rect, RPGXError>| {
                     if let Ok(tile) = tile {
                         let _ = onclick(tile);
@@ -107,6 +118,7 @@ pub fn Engine(props: EngineProps) -> Element {
                 engine: engine.clone(),
                 library: props.library.clone(),
                 square_size: props.square_size,
+                player: props.player,
             }
         }
     }
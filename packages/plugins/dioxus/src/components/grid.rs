@@ -1,26 +1,39 @@
-use std::any::Any;
-
 use dioxus::prelude::*;
-use rpgx::{engine::Engine, library::Library, prelude::RPGXError};
+use rpgx::{
+    engine::Engine,
+    prelude::{FogState, PawnId, RPGXError, TextureId},
+};
+
+use crate::resources::GameResources;
 
 #[derive(PartialEq, Props, Clone)]
 pub struct GridProps {
     pub engine: Signal<Engine>,
-    pub library: Signal<Library<Box<dyn Any>>>,
+    pub library: Signal<GameResources>,
     pub square_size: u32,
     pub onclick: EventHandler<Result<rpgx::prelude::Rect, RPGXError>>,
+    /// Pawn whose fog of war is drawn, if it has one.
+    #[props(default)]
+    pub player: PawnId,
+    /// Areas highlighted above the map, such as [`rpgx::prelude::Reachable::to_rects`].
+    #[props(default)]
+    pub overlay: Vec<rpgx::prelude::Rect>,
+    /// CSS color of the overlay.
+    #[props(default = "rgba(80, 160, 255, 0.35)".to_string())]
+    pub overlay_color: String,
+    /// CSS color drawn over explored tiles that are out of sight, when the player has fog.
+    #[props(default = "rgba(0, 0, 0, 0.55)".to_string())]
+    pub fog_explored_color: String,
+    /// CSS color drawn over tiles never seen, when the player has fog.
+    #[props(default = "rgb(0, 0, 0)".to_string())]
+    pub fog_unknown_color: String,
 }
 
 #[allow(non_snake_case)]
 pub fn Grid(props: GridProps) -> Element {
     let engine = props.engine.read();
-    let get_background = |texture_id: u32| {
-        if let Some(asset) = props
-            .library
-            .read()
-            .get_by_id(texture_id)
-            .and_then(|boxed| boxed.downcast_ref::<String>())
-        {
+    let get_background = |texture_id: TextureId| {
+        if let Some(asset) = props.library.read().textures.get_by_id(texture_id) {
             format!("background-image: url({}); background-size: cover;", asset)
         } else {
             "background-size: cover;".to_string()
@@ -31,7 +44,7 @@ pub fn Grid(props: GridProps) -> Element {
             {
                 scene
                     .map
-                    .layers
+                    .layers()
                     .iter()
                     .flat_map(|layer| {
                         layer
@@ -89,9 +102,9 @@ pub fn Grid(props: GridProps) -> Element {
                                                             .and_then(|id| {
                                                                 println!(
                                                                     "Rendering custom VNode from library {:?}",
-                                                                    library.get_by_id(id),
+                                                                    library.renders.get_key(id),
                                                                 );
-                                                                let f = library.get_by_id(id)?.downcast_ref::<Box<dyn Fn() -> VNode>>()?;
+                                                                let f = library.renders.get_by_id(id)?;
                                                                 Some(f())
                                                             })
                                                             .unwrap_or(rsx! {}.unwrap())
@@ -103,6 +116,59 @@ pub fn Grid(props: GridProps) -> Element {
                                 .collect::<Vec<_>>()
                         })
             }
+            {
+                props.overlay.iter().map(|rect| {
+                    let overlay_style = format!(
+                        "position: absolute; \
+                        left: {}px; \
+                        top: {}px; \
+                        width: {}px; \
+                        height: {}px; \
+                        background-color: {}; \
+                        z-index: 99; \
+                        pointer-events: none;",
+                        rect.origin.x * props.square_size,
+                        rect.origin.y * props.square_size,
+                        rect.shape.width * props.square_size,
+                        rect.shape.height * props.square_size,
+                        props.overlay_color,
+                    );
+                    rsx! {
+                        div { class: "overlay-tile", style: "{overlay_style}" }
+                    }
+                })
+            }
+            {
+                scene.fog(props.player).into_iter().flat_map(|fog| {
+                    [
+                        (FogState::Explored, &props.fog_explored_color),
+                        (FogState::Unknown, &props.fog_unknown_color),
+                    ]
+                    .into_iter()
+                    .flat_map(move |(state, color)| {
+                        fog.to_rects(&scene.map, state).into_iter().map(move |rect| {
+                            let fog_style = format!(
+                                "position: absolute; \
+                                left: {}px; \
+                                top: {}px; \
+                                width: {}px; \
+                                height: {}px; \
+                                background-color: {}; \
+                                z-index: 98; \
+                                pointer-events: none;",
+                                rect.origin.x * props.square_size,
+                                rect.origin.y * props.square_size,
+                                rect.shape.width * props.square_size,
+                                rect.shape.height * props.square_size,
+                                color,
+                            );
+                            rsx! {
+                                div { class: "fog-tile", style: "{fog_style}" }
+                            }
+                        })
+                    })
+                })
+            }
         }
     } else {
         rsx! {
@@ -1,64 +1,82 @@
-use std::{any::Any, convert::TryFrom};
-
 use dioxus::prelude::*;
-use rpgx::{engine::Engine, library::Library};
+use rpgx::{engine::Engine, prelude::PawnId};
+
+use crate::resources::GameResources;
 
 #[derive(PartialEq, Props, Clone)]
 pub struct PawnProps {
     pub engine: Signal<Engine>,
-    pub library: Signal<Library<Box<dyn Any>>>,
+    pub library: Signal<GameResources>,
     pub square_size: u32,
+    /// Pawn followed by the camera. Other pawns are hidden when out of its sight.
+    #[props(default)]
+    pub player: PawnId,
 }
 
+/// Renders every pawn of the active scene.
 #[allow(non_snake_case)]
 pub fn Pawn(props: PawnProps) -> Element {
     let engine = props.engine.read();
 
     if let Some(scene) = engine.get_active_scene() {
-        let pawn = scene.pawn.as_ref().unwrap();
-        let pawn_pos = pawn.pointer;
         let library = props.library.read();
         let default_texture = String::new();
-        let pawn_texture = library
-            .get_by_id(pawn.texture_id)
-            .and_then(|boxed| boxed.downcast_ref::<String>())
-            .unwrap_or(&default_texture);
+        let fog = scene.fog(props.player);
 
-        // Safely calculate pixel position
-        let left = pawn_pos
-            .x
-            .checked_mul(props.square_size)
-            .and_then(|v| i32::try_from(v).ok())
-            .unwrap_or(0);
+        rsx! {
+            {
+                scene
+                    .pawns
+                    .iter()
+                    .filter(|(id, pawn)| {
+                        **id == props.player
+                            || fog.is_none_or(|fog| fog.visible().contains(&pawn.pointer))
+                    })
+                    .map(|(id, pawn)| {
+                        // Drawn between two tiles while a move is in progress
+                        let (x, y) = scene
+                            .interpolated_position(*id)
+                            .unwrap_or((pawn.pointer.x as f32, pawn.pointer.y as f32));
+                        let pawn_texture = library
+                            .textures
+                            .get_by_id(pawn.texture_id)
+                            .unwrap_or(&default_texture);
 
-        let top = pawn_pos
-            .y
-            .checked_mul(props.square_size)
-            .and_then(|v| i32::try_from(v).ok())
-            .map(|v| v.saturating_sub(props.square_size as i32))
-            .unwrap_or(0);
+                        let size = props.square_size as f32;
+                        let left = x * size;
+                        let top = y * size - size;
 
-        rsx! {
-            div {
-                id: "pawn",
-                class: "pawn",
-                style: format!(
-                    "position: absolute; \
-                                                                                                         left: {}px; \
-                                                                                                         top: {}px; \
-                                                                                                         background-image: url({}); \
-                                                                                                         background-size: cover; \
-                                                                                                         background-position: center center; \
-                                                                                                         z-index: 100; \
-                                                                                                         width: {}px; \
-                                                                                                         height: {}px; \
-                                                                                                         transition: all 0.1s;",
-                    left,
-                    top,
-                    pawn_texture,
-                    props.square_size,
-                    props.square_size * 2,
-                ),
+                        // The player keeps the `pawn` element id the camera scrolls to
+                        let element_id = if *id == props.player {
+                            "pawn".to_string()
+                        } else {
+                            format!("pawn-{}", id.0)
+                        };
+
+                        rsx! {
+                            div {
+                                key: "{id.0}",
+                                id: "{element_id}",
+                                class: "pawn",
+                                style: format!(
+                                    "position: absolute; \
+                                    left: {}px; \
+                                    top: {}px; \
+                                    background-image: url({}); \
+                                    background-size: cover; \
+                                    background-position: center center; \
+                                    z-index: 100; \
+                                    width: {}px; \
+                                    height: {}px;",
+                                    left,
+                                    top,
+                                    pawn_texture,
+                                    props.square_size,
+                                    props.square_size * 2,
+                                ),
+                            }
+                        }
+                    })
             }
         }
     } else {
@@ -1,16 +1,17 @@
-use std::any::Any;
-
 use dioxus::prelude::*;
 use futures_util::stream::StreamExt;
 use log::error;
-use rpgx::library::Library;
 use rpgx::prelude::Engine;
-use rpgx::prelude::{Coordinates, Direction};
+use rpgx::prelude::{Coordinates, Direction, GameEvent, PawnId, TriggerOn};
+
+use crate::resources::GameResources;
 
 #[derive(Clone, Debug)]
 pub enum Command {
     WalkTo(Coordinates),
     Step(Direction),
+    /// Fires the interact actions of the tile the player faces.
+    Interact,
 }
 
 pub async fn sleep_ms(_ms: u64) {
@@ -25,10 +26,53 @@ pub async fn sleep_ms(_ms: u64) {
     }
 }
 
+/// Drains the engine events and runs the library actions triggered by `player`.
+fn dispatch_events(engine: &mut Engine, library: &GameResources, player: PawnId) {
+    for event in engine.drain_events() {
+        if let GameEvent::ActionTriggered { pawn, action, .. } = event
+            && pawn == player
+            && let Some(action) = library.actions.get_by_id(action)
+        {
+            action(engine)
+        }
+    }
+}
+
+/// Delay between two frames of the simulation loop, in milliseconds.
+const FRAME_MS: u64 = 16;
+
 pub fn use_controller(
-    engine: Signal<Engine>,
-    library: Signal<Library<Box<dyn Any>>>,
+    mut engine: Signal<Engine>,
+    library: Signal<GameResources>,
+    player: PawnId,
 ) -> Coroutine<Command> {
+    // Simulation loop: the engine moves pawns at their own speed, renderers only
+    // read its interpolated positions.
+    use_future(move || async move {
+        loop {
+            sleep_ms(FRAME_MS).await;
+            let busy = engine.peek().get_active_scene().is_some_and(|scene| {
+                !scene.motions.is_empty()
+                    || !scene.orders.is_empty()
+                    || !scene.behaviors.is_empty()
+                    // Stand actions fire over time
+                    || scene.pawns.values().any(|pawn| {
+                        scene.map.tile_at(&pawn.pointer).is_some_and(|tile| {
+                            tile.triggers
+                                .iter()
+                                .any(|trigger| trigger.trigger.on == TriggerOn::Stand)
+                        })
+                    })
+            });
+            // Idle scenes are not written to, so they don't re-render every frame
+            if busy {
+                let mut engine = engine.write();
+                engine.tick(FRAME_MS as f32 / 1000.0);
+                dispatch_events(&mut engine, &library.read(), player);
+            }
+        }
+    });
+
     use_coroutine({
         to_owned![engine];
         move |mut rx: UnboundedReceiver<Command>| async move {
@@ -36,67 +80,52 @@ pub fn use_controller(
                 let result: Result<(), Box<dyn std::error::Error>> = async {
                     match command {
                         Command::WalkTo(target) => {
-                            let steps = engine.read().get_active_scene().unwrap().map.find_path(
-                                &engine
-                                    .read()
-                                    .get_active_scene()
-                                    .unwrap()
-                                    .pawn
-                                    .as_ref()
-                                    .unwrap()
-                                    .pointer,
-                                &target,
-                            );
-                            match steps {
-                                None => {
-                                    error!("Path not found");
-                                    return Err("Path not found".into());
-                                }
-                                Some(steps) => {
-                                    for step in steps {
-                                        sleep_ms(100).await;
-                                        engine
-                                            .write()
-                                            .get_active_scene_mut()
-                                            .unwrap()
-                                            .move_to(step)
-                                            .map_err(|e| {
-                                                Box::<dyn std::error::Error>::from(format!(
-                                                    "{:?}",
-                                                    e
-                                                ))
-                                            })?;
-                                    }
-                                    Ok(())
+                            // Clicking a wall or an unreachable tile walks as close as possible
+                            let target = {
+                                let engine = engine.read();
+                                let scene = engine.get_active_scene().ok_or("No active scene")?;
+                                let pointer = scene.pawn(player).ok_or("No pawn")?.pointer;
+                                if scene.map.find_path(&pointer, &target).is_some() {
+                                    target
+                                } else {
+                                    scene
+                                        .map
+                                        .closest_walkable(&pointer, &target)
+                                        .map(|(closest, _)| closest)
+                                        .ok_or("Path not found")?
                                 }
-                            }
+                            };
+
+                            // Moves are played out by the simulation loop
+                            engine
+                                .write()
+                                .get_active_scene_mut()
+                                .ok_or("No active scene")?
+                                .walk(player, target)
+                                .map_err(|e| format!("{:?}", e))?;
+                            Ok(())
                         }
                         Command::Step(direction) => {
                             let mut _engine = engine.write();
-                            if let Ok(pointer) =
-                                _engine.get_active_scene_mut().unwrap().step_to(direction)
-                            {
-                                _engine
-                                    .get_active_scene()
-                                    .unwrap()
-                                    .map
-                                    .get_actions_at(&pointer)
-                                    .into_iter()
-                                    .for_each(|action_id| {
-                                        if let Some(boxed) = library.read().get_by_id(action_id) {
-                                            if let Some(unboxed) =
-                                                boxed.downcast_ref::<Box<dyn Fn(&mut Engine)>>()
-                                            {
-                                                println!("calling unboxed action");
-                                                unboxed(&mut _engine)
-                                            }
-                                        }
-                                    });
-                                // for action_id in action_ids {
-                                //     // Keep this as log only or handle as needed
-                                //     log::info!("Action triggered: {:?}", action_id);
-                                // }
+                            let scene = _engine.get_active_scene_mut().ok_or("No active scene")?;
+                            // Keys pressed mid-step are dropped, like a held key
+                            if scene.is_moving(player) {
+                                return Ok(());
+                            }
+                            scene.stop(player);
+                            // A refused step is reported as a `Blocked` event
+                            let _ = scene.step_to(player, direction);
+                            dispatch_events(&mut _engine, &library.read(), player);
+                            Ok(())
+                        }
+                        Command::Interact => {
+                            let mut _engine = engine.write();
+                            let scene = _engine.get_active_scene_mut().ok_or("No active scene")?;
+                            if scene.is_moving(player) {
+                                return Ok(());
                             }
+                            scene.interact(player).map_err(|e| format!("{:?}", e))?;
+                            dispatch_events(&mut _engine, &library.read(), player);
                             Ok(())
                         }
                     }
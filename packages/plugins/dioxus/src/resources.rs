@@ -0,0 +1,11 @@
+use dioxus::prelude::*;
+use rpgx::prelude::{Engine, Resources};
+
+/// Callback run when the player fires an action, see [`crate::controller`].
+pub type Action = Box<dyn Fn(&mut Engine)>;
+
+/// Callback drawing the content of the tiles of an `Effect::Render` mask.
+pub type Render = Box<dyn Fn() -> VNode>;
+
+/// Texture URLs, actions and render callbacks used by the components.
+pub type GameResources = Resources<String, Action, Render>;
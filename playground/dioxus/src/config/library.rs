@@ -1,9 +1,9 @@
 use dioxus::prelude::*;
-use rpgx::library::Library;
-use std::any::Any;
+use rpgx::prelude::Engine;
+use rpgx_dioxus::resources::GameResources;
 
-pub fn use_library() -> Library<Box<dyn Any>> {
-    let mut library: Library<Box<dyn Any>> = Library::new();
+pub fn use_library() -> GameResources {
+    let mut library = GameResources::new();
 
     // Platform-agnostic logger
     fn log_message(message: &str) {
@@ -14,45 +14,45 @@ pub fn use_library() -> Library<Box<dyn Any>> {
         println!("{message}");
     }
 
-    library.insert(
+    library.textures.insert(
         "floor_1",
-        Box::new("https://s3.rottigni.tech/rpgx/spaceship_floor_1.webp".to_string()),
+        "https://s3.rottigni.tech/rpgx/spaceship_floor_1.webp".to_string(),
     );
-    library.insert(
+    library.textures.insert(
         "floor_2",
-        Box::new("https://s3.rottigni.tech/rpgx/spaceship_floor_2.webp".to_string()),
+        "https://s3.rottigni.tech/rpgx/spaceship_floor_2.webp".to_string(),
     );
-    library.insert(
+    library.textures.insert(
         "floor_3",
-        Box::new("https://s3.rottigni.tech/rpgx/spaceship_floor_3.webp".to_string()),
+        "https://s3.rottigni.tech/rpgx/spaceship_floor_3.webp".to_string(),
     );
-    library.insert(
+    library.textures.insert(
         "building_1",
-        Box::new("https://s3.rottigni.tech/rpgx/processor_8.webp".to_string()),
+        "https://s3.rottigni.tech/rpgx/processor_8.webp".to_string(),
     );
-    library.insert(
+    library.textures.insert(
         "building_2",
-        Box::new("https://s3.rottigni.tech/rpgx/processor_9.webp".to_string()),
+        "https://s3.rottigni.tech/rpgx/processor_9.webp".to_string(),
     );
-    library.insert(
+    library.textures.insert(
         "portal_1",
-        Box::new("https://s3.rottigni.tech/rpgx/portal_1.webp".to_string()),
+        "https://s3.rottigni.tech/rpgx/portal_1.webp".to_string(),
     );
-    library.insert(
+    library.textures.insert(
         "character_1",
-        Box::new("https://s3.rottigni.tech/rpgx/character_1.webp".to_string()),
+        "https://s3.rottigni.tech/rpgx/character_1.webp".to_string(),
     );
     // Platform-agnostic action
-    library.insert(
+    library.actions.insert(
         "consolelog",
-        Box::new(|| {
+        Box::new(|_: &mut Engine| {
             log_message("Hello from Rust!");
         }),
     );
 
-    library.insert(
+    library.renders.insert(
         "sign",
-        Box::new(Box::new(|| {
+        Box::new(|| {
             println!("Invoked render closure for sign");
             rsx! {
                 div {
@@ -62,7 +62,7 @@ pub fn use_library() -> Library<Box<dyn Any>> {
                 }
             }
             .unwrap()
-        }) as Box<dyn Fn() -> VNode>) as Box<dyn Any>,
+        }),
     );
 
     library
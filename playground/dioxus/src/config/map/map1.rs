@@ -1,16 +1,15 @@
-use std::any::Any;
+use rpgx::prelude::*;
+use rpgx_dioxus::resources::GameResources;
 
-use rpgx::{library::Library, prelude::*};
-
-pub fn use_map1(library: &Library<Box<dyn Any>>) -> Map {
-    println!("loading render id: {:?}", library.get_id("sign"));
+pub fn use_map1(library: &GameResources) -> Map {
+    println!("loading render id: {:?}", library.renders.get_id("sign"));
     let mut single_map = rpgx::factory::map::presets::building::building_2x3(
         Shape {
             width: 4,
             height: 6,
         },
-        library.get_id("building_1").unwrap(),
-        library.get_id("consolelog").unwrap(),
+        library.textures.get_id("building_1").unwrap(),
+        library.actions.get_id("consolelog").unwrap(),
     );
     println!("length: {:?}", single_map.get_shape());
     single_map.load_layer(rpgx::factory::layer::presets::ground::ground_layer(
@@ -18,21 +17,21 @@ pub fn use_map1(library: &Library<Box<dyn Any>>) -> Map {
             width: 4,
             height: 6,
         },
-        library.get_id("floor_1").unwrap(),
+        library.textures.get_id("floor_1").unwrap(),
     ));
     single_map.load_layer(rpgx::factory::layer::presets::street::street_layer_around(
         Shape {
             width: 4,
             height: 6,
         },
-        library.get_id("floor_2").unwrap(),
+        library.textures.get_id("floor_2").unwrap(),
     ));
     single_map.load_layer(rpgx::factory::layer::presets::street::street_layer_around(
         Shape {
             width: 6,
             height: 8,
         },
-        library.get_id("floor_2").unwrap(),
+        library.textures.get_id("floor_2").unwrap(),
     ));
     single_map.load_layer(Layer::new(
         "sign".into(),
@@ -52,7 +51,7 @@ pub fn use_map1(library: &Library<Box<dyn Any>>) -> Map {
                     height: 3,
                 },
             )],
-            vec![Effect::Render(library.get_id("sign").unwrap())],
+            vec![Effect::Render(library.renders.get_id("sign").unwrap())],
         )],
         8,
     ));
@@ -67,8 +66,8 @@ pub fn use_map1(library: &Library<Box<dyn Any>>) -> Map {
             width: 4,
             height: 6,
         },
-        library.get_id("portal_1").unwrap(),
-        library.get_id("consolelog").unwrap(),
+        library.textures.get_id("portal_1").unwrap(),
+        library.actions.get_id("consolelog").unwrap(),
     );
     map.merge_at(&portal, Coordinates { x: 6, y: 0 }, None);
     map.merge_at(&map.clone(), Coordinates { x: 16, y: 0 }, None);
@@ -1,39 +1,35 @@
-use std::any::Any;
+use rpgx::prelude::{Coordinates, Direction, Effect, Layer, Map, Mask, Rect, Shape};
+use rpgx_dioxus::resources::GameResources;
 
-use rpgx::{
-    library::Library,
-    prelude::{Coordinates, Direction, Effect, Layer, Map, Mask, Rect, Shape},
-};
-
-pub fn use_map2(library: &Library<Box<dyn Any>>) -> Map {
+pub fn use_map2(library: &GameResources) -> Map {
     let mut building_1 = rpgx::factory::map::presets::building::building_2x3(
         Shape {
             width: 4,
             height: 6,
         },
-        library.get_id("building_1").unwrap(),
-        library.get_id("consolelog").unwrap(),
+        library.textures.get_id("building_1").unwrap(),
+        library.actions.get_id("consolelog").unwrap(),
     );
     building_1.load_layer(rpgx::factory::layer::presets::ground::ground_layer(
         Shape {
             width: 6,
             height: 8,
         },
-        library.get_id("floor_1").unwrap(),
+        library.textures.get_id("floor_1").unwrap(),
     ));
     building_1.load_layer(rpgx::factory::layer::presets::street::street_layer_around(
         Shape {
             width: 4,
             height: 6,
         },
-        library.get_id("floor_2").unwrap(),
+        library.textures.get_id("floor_2").unwrap(),
     ));
     building_1.load_layer(rpgx::factory::layer::presets::street::street_layer_around(
         Shape {
             width: 6,
             height: 8,
         },
-        library.get_id("floor_2").unwrap(),
+        library.textures.get_id("floor_2").unwrap(),
     ));
 
     let mut building_2 = rpgx::factory::map::presets::building::building_2x3(
@@ -41,29 +37,29 @@ pub fn use_map2(library: &Library<Box<dyn Any>>) -> Map {
             width: 4,
             height: 6,
         },
-        library.get_id("building_2").unwrap(),
-        library.get_id("consolelog").unwrap(),
+        library.textures.get_id("building_2").unwrap(),
+        library.actions.get_id("consolelog").unwrap(),
     );
     building_2.load_layer(rpgx::factory::layer::presets::ground::ground_layer(
         Shape {
             width: 6,
             height: 8,
         },
-        library.get_id("floor_1").unwrap(),
+        library.textures.get_id("floor_1").unwrap(),
     ));
     building_2.load_layer(rpgx::factory::layer::presets::street::street_layer_around(
         Shape {
             width: 4,
             height: 6,
         },
-        library.get_id("floor_2").unwrap(),
+        library.textures.get_id("floor_2").unwrap(),
     ));
     building_2.load_layer(rpgx::factory::layer::presets::street::street_layer_around(
         Shape {
             width: 6,
             height: 8,
         },
-        library.get_id("floor_2").unwrap(),
+        library.textures.get_id("floor_2").unwrap(),
     ));
 
     let decoration_shape = Shape {
@@ -104,7 +100,7 @@ pub fn use_map2(library: &Library<Box<dyn Any>>) -> Map {
                 .iter()
                 .map(|c| Rect::new(*c, Shape::from_square(1)))
                 .collect(),
-            vec![Effect::Texture(library.get_id("floor_3").unwrap())],
+            vec![Effect::Texture(library.textures.get_id("floor_3").unwrap())],
         )],
         1,
     ));
@@ -1,9 +1,7 @@
-use std::{any::Any, vec};
+use std::vec;
 
-use rpgx::{
-    library::Library,
-    prelude::{Coordinates, Effect, Layer, Map, Mask, Rect, Shape},
-};
+use rpgx::prelude::{Coordinates, Effect, Layer, Map, Mask, Rect, Shape};
+use rpgx_dioxus::resources::GameResources;
 
 const SHAPE_WIDTH: u32 = 4;
 const SHAPE_HEIGHT: u32 = 6;
@@ -16,7 +14,7 @@ fn is_center_tile(pointer: Coordinates, shape: Shape) -> bool {
         && (pointer.y == center_y || pointer.y == center_y - 1)
 }
 
-pub fn use_map3(library: &Library<Box<dyn Any>>) -> Map {
+pub fn use_map3(library: &GameResources) -> Map {
     let shape: Shape = Shape {
         width: SHAPE_WIDTH,
         height: SHAPE_HEIGHT,
@@ -43,7 +41,7 @@ pub fn use_map3(library: &Library<Box<dyn Any>>) -> Map {
                 "default_floor".to_string(),
                 // Selector::Block(Rect::new(Coordinates { x: 0, y: 0 }, shape)),
                 Rect::new(Coordinates { x: 0, y: 0 }, shape).into_many(),
-                vec![Effect::Texture(library.get_id("floor_1").unwrap())],
+                vec![Effect::Texture(library.textures.get_id("floor_1").unwrap())],
             ),
             Mask::new(
                 "floor_alt".to_string(),
@@ -51,7 +49,7 @@ pub fn use_map3(library: &Library<Box<dyn Any>>) -> Map {
                     .iter()
                     .map(|c| Rect::new(*c, Shape::from_square(1)))
                     .collect(),
-                vec![Effect::Texture(library.get_id("floor_2").unwrap())],
+                vec![Effect::Texture(library.textures.get_id("floor_2").unwrap())],
             ),
         ],
         1,
@@ -75,7 +73,9 @@ pub fn use_map3(library: &Library<Box<dyn Any>>) -> Map {
                     height: 4 - 1, // 3
                 },
             )],
-            vec![Effect::Texture(library.get_id("building_1").unwrap())],
+            vec![Effect::Texture(
+                library.textures.get_id("building_1").unwrap(),
+            )],
         )],
         5,
     );
@@ -100,8 +100,8 @@ pub fn use_map3(library: &Library<Box<dyn Any>>) -> Map {
             )
             .into_many(),
             vec![
-                Effect::Texture(library.get_id("portal_1").unwrap()),
-                Effect::Action(library.get_id("consolelog").unwrap()),
+                Effect::Texture(library.textures.get_id("portal_1").unwrap()),
+                Effect::Action(library.actions.get_id("consolelog").unwrap()),
             ],
         )],
         6,
@@ -1,14 +1,15 @@
-use std::{any::Any, vec};
+use std::vec;
 
-use rpgx::{library::Library, prelude::*};
+use rpgx::prelude::*;
+use rpgx_dioxus::resources::GameResources;
 
-pub fn use_map4(library: &Library<Box<dyn Any>>) -> Map {
+pub fn use_map4(library: &GameResources) -> Map {
     let layer1 = Layer::new(
         "ground".into(),
         vec![Mask::new(
             "ground".into(),
             Rect::from_shape(Shape::from_square(15)).into_many(),
-            vec![Effect::Texture(library.get_id("floor_1").unwrap())],
+            vec![Effect::Texture(library.textures.get_id("floor_1").unwrap())],
         )],
         1,
     );
@@ -17,7 +18,7 @@ pub fn use_map4(library: &Library<Box<dyn Any>>) -> Map {
         vec![Mask::new(
             "ground".into(),
             Rect::from_shape(Shape::from_square(15)).into_perimeter(0, 2),
-            vec![Effect::Texture(library.get_id("floor_3").unwrap())],
+            vec![Effect::Texture(library.textures.get_id("floor_3").unwrap())],
         )],
         1,
     );
@@ -26,7 +27,7 @@ pub fn use_map4(library: &Library<Box<dyn Any>>) -> Map {
         vec![Mask::new(
             "ground".into(),
             Rect::from_shape(Shape::from_square(15)).into_circle(),
-            vec![Effect::Texture(library.get_id("floor_2").unwrap())],
+            vec![Effect::Texture(library.textures.get_id("floor_2").unwrap())],
         )],
         1,
     );
@@ -35,7 +36,7 @@ pub fn use_map4(library: &Library<Box<dyn Any>>) -> Map {
         vec![Mask::new(
             "ground".into(),
             Rect::from_shape(Shape::from_square(15)).into_rhombus(5),
-            vec![Effect::Texture(library.get_id("floor_3").unwrap())],
+            vec![Effect::Texture(library.textures.get_id("floor_3").unwrap())],
         )],
         1,
     );
@@ -47,7 +48,9 @@ pub fn use_map4(library: &Library<Box<dyn Any>>) -> Map {
                 Coordinates::new(5, 3),
                 Shape::from_rectangle(5, 7),
             )],
-            vec![Effect::Texture(library.get_id("building_1").unwrap())],
+            vec![Effect::Texture(
+                library.textures.get_id("building_1").unwrap(),
+            )],
         )],
         2,
     );
@@ -64,7 +67,9 @@ pub fn use_map4(library: &Library<Box<dyn Any>>) -> Map {
         vec![Mask::new(
             "portal".into(),
             vec![Rect::from_many(Rect::from_shape(map.get_shape()).into_center(0, 2)).unwrap()],
-            vec![Effect::Texture(library.get_id("portal_1").unwrap())],
+            vec![Effect::Texture(
+                library.textures.get_id("portal_1").unwrap(),
+            )],
         )],
         2,
     );
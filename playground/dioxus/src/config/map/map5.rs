@@ -1,14 +1,15 @@
-use std::{any::Any, vec};
+use std::vec;
 
 use rpgx::prelude::*;
+use rpgx_dioxus::resources::GameResources;
 
-pub fn use_map5(library: &Library<Box<dyn Any>>) -> Map {
+pub fn use_map5(library: &GameResources) -> Map {
     let layer1 = Layer::new(
         "ground".into(),
         vec![Mask::new(
             "ground".into(),
             Rect::from_shape(Shape::from_square(15)).into_many(),
-            vec![Effect::Texture(library.get_id("floor_1").unwrap())],
+            vec![Effect::Texture(library.textures.get_id("floor_1").unwrap())],
         )],
         1,
     );
@@ -17,7 +18,7 @@ pub fn use_map5(library: &Library<Box<dyn Any>>) -> Map {
         vec![Mask::new(
             "ground".into(),
             Rect::from_shape(Shape::from_square(15)).into_evens(),
-            vec![Effect::Texture(library.get_id("floor_3").unwrap())],
+            vec![Effect::Texture(library.textures.get_id("floor_3").unwrap())],
         )],
         1,
     );
@@ -26,7 +27,7 @@ pub fn use_map5(library: &Library<Box<dyn Any>>) -> Map {
         vec![Mask::new(
             "ground".into(),
             Rect::from_shape(Shape::from_square(15)).into_circle(),
-            vec![Effect::Texture(library.get_id("floor_2").unwrap())],
+            vec![Effect::Texture(library.textures.get_id("floor_2").unwrap())],
         )],
         1,
     );
@@ -35,7 +36,7 @@ pub fn use_map5(library: &Library<Box<dyn Any>>) -> Map {
         vec![Mask::new(
             "ground".into(),
             Rect::from_shape(Shape::from_square(15)).into_rhombus(5),
-            vec![Effect::Texture(library.get_id("floor_3").unwrap())],
+            vec![Effect::Texture(library.textures.get_id("floor_3").unwrap())],
         )],
         1,
     );
@@ -47,7 +48,9 @@ pub fn use_map5(library: &Library<Box<dyn Any>>) -> Map {
                 Coordinates::new(5, 3),
                 Shape::from_rectangle(5, 7),
             )],
-            vec![Effect::Texture(library.get_id("building_1").unwrap())],
+            vec![Effect::Texture(
+                library.textures.get_id("building_1").unwrap(),
+            )],
         )],
         2,
     );
@@ -67,7 +70,9 @@ pub fn use_map5(library: &Library<Box<dyn Any>>) -> Map {
                 Coordinates::new(11, 7),
                 Shape::from_rectangle(8, 11),
             )],
-            vec![Effect::Texture(library.get_id("building_2").unwrap())],
+            vec![Effect::Texture(
+                library.textures.get_id("building_2").unwrap(),
+            )],
         )],
         2,
     );
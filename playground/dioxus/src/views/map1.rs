@@ -10,7 +10,7 @@ pub fn Map1() -> Element {
     let map = use_map1(&library.read());
 
     let mut scene = Scene::new("default".into(), map, None);
-    scene.load_pawn(library.read().get_id("character_1").unwrap());
+    scene.load_pawn(library.read().textures.get_id("character_1").unwrap());
     let engine = use_signal(|| Engine::new(scene));
     rsx! {
         div { class: "cluster",
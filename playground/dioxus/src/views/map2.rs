@@ -11,7 +11,7 @@ pub fn Map2() -> Element {
 
     let pawn = Pawn {
         pointer: Coordinates { x: 0, y: 0 },
-        texture_id: library.read().get_id("character_1").unwrap(),
+        texture_id: library.read().textures.get_id("character_1").unwrap(),
     };
     let mut scene = Scene::new("default".into(), map, None);
     scene.load_pawn_at(pawn);
@@ -32,7 +32,7 @@ pub fn Map2() -> Element {
     //         if let Some(tile) = layer.get_tile_at(Coordinates { x: 0, y: 0 }) {
     //             let pawn = Pawn {
     //                 pointer: tile.pointer,
-    //                 texture_id: library.read().get_id("character_1").unwrap(),
+    //                 texture_id: library.read().textures.get_id("character_1").unwrap(),
     //             };
     //             let mut scene = Scene::new("default".into(), map, None);
     //             scene.load_pawn_at(pawn);
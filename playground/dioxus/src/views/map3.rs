@@ -11,7 +11,7 @@ pub fn Map3() -> Element {
 
     let pawn = Pawn {
         pointer: Coordinates { x: 0, y: 0 },
-        texture_id: library.read().get_id("character_1").unwrap(),
+        texture_id: library.read().textures.get_id("character_1").unwrap(),
     };
     let scene = Scene::new("default".into(), map, Some(pawn));
     let engine = use_signal(|| Engine::new(scene));